@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use sawp::parser::Direction;
+use sawp_dns::Dns;
+use sawp_fuzz::check::probe_parse;
+use sawp_fuzz::dns::Message;
+
+fuzz_target!(|message: Message| {
+    probe_parse(&Dns::default(), &message.to_bytes(), Direction::Unknown);
+});
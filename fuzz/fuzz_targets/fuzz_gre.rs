@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use sawp::parser::{Direction, Parse};
+use sawp_gre::Gre;
+
+fuzz_target!(|data: &[u8]| {
+    let parser = Gre {};
+    if let Err(e) = parser.parse(data, Direction::Unknown) {
+        eprintln!("GRE: Error parsing {:?}", e);
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use sawp::parser::{Direction, Parse};
+use sawp_json::Json;
+
+fuzz_target!(|data: &[u8]| {
+    let parser = Json {};
+    if let Err(e) = parser.parse(data, Direction::Unknown) {
+        eprintln!("JSON: Error parsing {:?}", e);
+    }
+});
@@ -0,0 +1,48 @@
+#![no_main]
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+use sawp::parser::Direction;
+use sawp_fuzz::check::probe_parse;
+
+#[derive(Arbitrary, Debug)]
+enum Target {
+    Diameter,
+    Dns,
+    Gre,
+    Json,
+    Modbus,
+    Resp,
+    Tftp,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Dir {
+    ToServer,
+    ToClient,
+    Unknown,
+}
+
+impl From<Dir> for Direction {
+    fn from(direction: Dir) -> Self {
+        match direction {
+            Dir::ToServer => Direction::ToServer,
+            Dir::ToClient => Direction::ToClient,
+            Dir::Unknown => Direction::Unknown,
+        }
+    }
+}
+
+fuzz_target!(|input: (Target, Dir, &[u8])| {
+    let (target, direction, data) = input;
+    let direction = direction.into();
+    match target {
+        Target::Diameter => probe_parse(&sawp_diameter::Diameter {}, data, direction),
+        Target::Dns => probe_parse(&sawp_dns::Dns::default(), data, direction),
+        Target::Gre => probe_parse(&sawp_gre::Gre {}, data, direction),
+        Target::Json => probe_parse(&sawp_json::Json {}, data, direction),
+        Target::Modbus => probe_parse(&sawp_modbus::Modbus {}, data, direction),
+        Target::Resp => probe_parse(&sawp_resp::Resp {}, data, direction),
+        Target::Tftp => probe_parse(&sawp_tftp::TFTP {}, data, direction),
+    }
+});
@@ -0,0 +1,25 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use sawp::parser::{Direction, Parse};
+use sawp_fuzz::check::probe_parse;
+use sawp_fuzz::resp::Stream;
+use sawp_resp::Resp;
+
+fuzz_target!(|stream: Stream| {
+    let parser = Resp {};
+    let bytes = stream.to_bytes();
+    let mut input = bytes.as_slice();
+
+    // Parse every value in the stream, each one must make progress.
+    while !input.is_empty() {
+        probe_parse(&parser, input, Direction::ToServer);
+        match parser.parse(input, Direction::ToServer) {
+            Ok((rest, _)) => {
+                assert!(rest.len() < input.len(), "parser did not advance");
+                input = rest;
+            }
+            Err(_) => break,
+        }
+    }
+});
@@ -0,0 +1,59 @@
+//! Differential checks between the `Probe` and `Parse` implementations.
+
+use sawp::error::{Error, ErrorKind};
+use sawp::parser::Direction;
+use sawp::probe::{Probe, Status};
+use sawp::protocol::Protocol;
+
+use std::fmt::Debug;
+
+/// Parse and probe `input`, panicking if the results are inconsistent.
+///
+/// Checks that:
+/// - parsing the same input twice gives the same result,
+/// - the remaining input is a suffix of the input,
+/// - probe reports `Incomplete` if and only if parse needs more data,
+/// - probe only recognizes input that parses successfully.
+pub fn probe_parse<P>(parser: &P, input: &[u8], direction: Direction)
+where
+    P: for<'a> Probe<'a>,
+    for<'a> <P as Protocol<'a>>::Message: Debug + PartialEq,
+{
+    let parsed = parser.parse(input, direction.clone());
+    assert_eq!(
+        parsed,
+        parser.parse(input, direction.clone()),
+        "parse is not deterministic"
+    );
+
+    if let Ok((rest, _)) = &parsed {
+        assert!(
+            rest.len() <= input.len() && input.ends_with(rest),
+            "remaining input is not a suffix of the input"
+        );
+    }
+
+    let incomplete = matches!(
+        parsed,
+        Err(Error {
+            kind: ErrorKind::Incomplete(_)
+        })
+    );
+
+    match parser.probe(input, direction) {
+        Status::Incomplete => assert!(
+            incomplete,
+            "probe is incomplete but parse returned {:?}",
+            parsed
+        ),
+        Status::Recognized => assert!(
+            parsed.is_ok(),
+            "probe recognized input which parse rejected with {:?}",
+            parsed
+        ),
+        Status::Unrecognized => assert!(
+            !incomplete,
+            "probe rejected input which parse needs more data for"
+        ),
+    }
+}
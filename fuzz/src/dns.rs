@@ -0,0 +1,318 @@
+//! Structure-aware DNS message generator.
+
+use arbitrary::Arbitrary;
+
+const TYPE_A: u16 = 1;
+const TYPE_NS: u16 = 2;
+const TYPE_CNAME: u16 = 5;
+const TYPE_SOA: u16 = 6;
+const TYPE_PTR: u16 = 12;
+const TYPE_MX: u16 = 15;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+const TYPE_SRV: u16 = 33;
+const TYPE_OPT: u16 = 41;
+const TYPE_SSHFP: u16 = 44;
+const TYPE_CAA: u16 = 257;
+
+/// Largest offset a compression pointer can refer to.
+const MAX_POINTER: usize = 0x3fff;
+
+/// Encoding of a domain name.
+#[derive(Arbitrary, Debug)]
+pub enum Name {
+    /// Labels terminated by the root label.
+    Labels(Vec<Vec<u8>>),
+    /// Labels followed by a pointer to a previously written name.
+    Compressed(Vec<Vec<u8>>, u8),
+    /// Pointer to an arbitrary offset.
+    Pointer(u16),
+    /// Pointer to itself.
+    Loop,
+    /// Two pointers referring to each other.
+    MutualLoop,
+    /// Raw bytes, terminated or not.
+    Raw(Vec<u8>),
+}
+
+/// Record data, encoded independently from the record type.
+#[derive(Arbitrary, Debug)]
+pub enum RData {
+    A([u8; 4]),
+    Aaaa([u8; 16]),
+    Cname(Name),
+    Ns(Name),
+    Ptr(Name),
+    Mx(u16, Name),
+    Txt(Vec<Vec<u8>>),
+    Soa {
+        mname: Name,
+        rname: Name,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: Name,
+    },
+    Sshfp {
+        algorithm: u8,
+        fingerprint_type: u8,
+        fingerprint: Vec<u8>,
+    },
+    Caa {
+        flags: u8,
+        tag: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Opt(Vec<(u16, Vec<u8>)>),
+    Raw(u16, Vec<u8>),
+}
+
+impl RData {
+    fn record_type(&self) -> u16 {
+        match self {
+            RData::A(_) => TYPE_A,
+            RData::Aaaa(_) => TYPE_AAAA,
+            RData::Cname(_) => TYPE_CNAME,
+            RData::Ns(_) => TYPE_NS,
+            RData::Ptr(_) => TYPE_PTR,
+            RData::Mx(_, _) => TYPE_MX,
+            RData::Txt(_) => TYPE_TXT,
+            RData::Soa { .. } => TYPE_SOA,
+            RData::Srv { .. } => TYPE_SRV,
+            RData::Sshfp { .. } => TYPE_SSHFP,
+            RData::Caa { .. } => TYPE_CAA,
+            RData::Opt(_) => TYPE_OPT,
+            RData::Raw(record_type, _) => *record_type,
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct Question {
+    pub name: Name,
+    pub record_type: u16,
+    pub record_class: u16,
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct Record {
+    pub name: Name,
+    pub record_class: u16,
+    pub ttl: u32,
+    pub data: RData,
+    /// Use this type instead of the one matching `data`.
+    pub record_type: Option<u16>,
+    /// Use this length instead of the encoded length of `data`.
+    pub data_length: Option<u16>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct Message {
+    pub transaction_id: u16,
+    pub flags: u16,
+    pub questions: Vec<Question>,
+    pub answers: Vec<Record>,
+    pub nameservers: Vec<Record>,
+    pub additional: Vec<Record>,
+    /// Use these section counts instead of the number of entries.
+    pub counts: Option<[u16; 4]>,
+    /// Cut the encoded message at this length.
+    pub truncate: Option<u16>,
+}
+
+impl Message {
+    /// Encode the message in wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let counts = self.counts.unwrap_or([
+            self.questions.len() as u16,
+            self.answers.len() as u16,
+            self.nameservers.len() as u16,
+            self.additional.len() as u16,
+        ]);
+
+        let mut encoder = Encoder::default();
+        encoder.u16(self.transaction_id);
+        encoder.u16(self.flags);
+        for count in &counts {
+            encoder.u16(*count);
+        }
+        for question in &self.questions {
+            encoder.name(&question.name);
+            encoder.u16(question.record_type);
+            encoder.u16(question.record_class);
+        }
+        for record in self
+            .answers
+            .iter()
+            .chain(&self.nameservers)
+            .chain(&self.additional)
+        {
+            encoder.record(record);
+        }
+
+        let mut bytes = encoder.buffer;
+        if let Some(length) = self.truncate {
+            bytes.truncate(length as usize);
+        }
+        bytes
+    }
+}
+
+#[derive(Default)]
+struct Encoder {
+    buffer: Vec<u8>,
+    /// Offsets of the names written so far, for compression pointers.
+    names: Vec<usize>,
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn pointer(&mut self, offset: usize) {
+        self.u16(0xc000 | (offset & MAX_POINTER) as u16);
+    }
+
+    fn labels(&mut self, labels: &[Vec<u8>]) {
+        for label in labels {
+            let label = &label[..label.len().min(63)];
+            self.u8(label.len() as u8);
+            self.buffer.extend_from_slice(label);
+        }
+    }
+
+    fn name(&mut self, name: &Name) {
+        let offset = self.buffer.len();
+        match name {
+            Name::Labels(labels) => {
+                self.labels(labels);
+                self.u8(0);
+            }
+            Name::Compressed(labels, index) => {
+                self.labels(labels);
+                if self.names.is_empty() {
+                    self.u8(0);
+                } else {
+                    let target = self.names[*index as usize % self.names.len()];
+                    self.pointer(target);
+                }
+            }
+            Name::Pointer(target) => self.pointer(*target as usize),
+            Name::Loop => self.pointer(offset),
+            Name::MutualLoop => {
+                self.pointer(offset + 2);
+                self.pointer(offset);
+            }
+            Name::Raw(bytes) => self.buffer.extend_from_slice(bytes),
+        }
+        if offset <= MAX_POINTER {
+            self.names.push(offset);
+        }
+    }
+
+    fn character_string(&mut self, bytes: &[u8]) {
+        let bytes = &bytes[..bytes.len().min(255)];
+        self.u8(bytes.len() as u8);
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn record(&mut self, record: &Record) {
+        self.name(&record.name);
+        self.u16(
+            record
+                .record_type
+                .unwrap_or_else(|| record.data.record_type()),
+        );
+        self.u16(record.record_class);
+        self.u32(record.ttl);
+
+        let length_offset = self.buffer.len();
+        self.u16(0);
+        self.rdata(&record.data);
+        let length = record
+            .data_length
+            .unwrap_or((self.buffer.len() - length_offset - 2) as u16);
+        self.buffer[length_offset..length_offset + 2].copy_from_slice(&length.to_be_bytes());
+    }
+
+    fn rdata(&mut self, data: &RData) {
+        match data {
+            RData::A(address) => self.buffer.extend_from_slice(address),
+            RData::Aaaa(address) => self.buffer.extend_from_slice(address),
+            RData::Cname(name) | RData::Ns(name) | RData::Ptr(name) => self.name(name),
+            RData::Mx(preference, name) => {
+                self.u16(*preference);
+                self.name(name);
+            }
+            RData::Txt(strings) => {
+                for string in strings {
+                    self.character_string(string);
+                }
+            }
+            RData::Soa {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => {
+                self.name(mname);
+                self.name(rname);
+                for value in &[serial, refresh, retry, expire, minimum] {
+                    self.u32(**value);
+                }
+            }
+            RData::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                self.u16(*priority);
+                self.u16(*weight);
+                self.u16(*port);
+                self.name(target);
+            }
+            RData::Sshfp {
+                algorithm,
+                fingerprint_type,
+                fingerprint,
+            } => {
+                self.u8(*algorithm);
+                self.u8(*fingerprint_type);
+                self.buffer.extend_from_slice(fingerprint);
+            }
+            RData::Caa { flags, tag, value } => {
+                self.u8(*flags);
+                self.character_string(tag);
+                self.buffer.extend_from_slice(value);
+            }
+            RData::Opt(options) => {
+                for (code, data) in options {
+                    self.u16(*code);
+                    self.u16(data.len() as u16);
+                    self.buffer.extend_from_slice(data);
+                }
+            }
+            RData::Raw(_, bytes) => self.buffer.extend_from_slice(bytes),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Structure-aware generators and consistency checks shared by the fuzz targets.
+//!
+//! The generators implement `Arbitrary` so libFuzzer mutates the structure of a
+//! message rather than its raw bytes. They produce mostly valid messages with
+//! targeted defects (bad counts, compression loops, deep nesting, truncation)
+//! to reach deeper parsing logic than random bytes do.
+
+pub mod check;
+pub mod dns;
+pub mod resp;
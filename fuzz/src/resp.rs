@@ -0,0 +1,98 @@
+//! Structure-aware RESP value generator.
+
+use arbitrary::Arbitrary;
+
+#[derive(Arbitrary, Debug)]
+pub enum Value {
+    SimpleString(Vec<u8>),
+    Error(Vec<u8>),
+    Integer(i64),
+    BulkString(Vec<u8>),
+    NullBulkString,
+    Array(Vec<Value>),
+    NullArray,
+    /// Value wrapped in this many single element arrays, possibly deeper
+    /// than the parser allows.
+    Nested(u8, Box<Value>),
+    /// Bulk string declaring a length different from its data.
+    BadLength(i64, Vec<u8>),
+    /// Array declaring a length different from its elements.
+    BadCount(i64, Vec<Value>),
+    /// Unknown type token followed by a line.
+    Invalid(u8, Vec<u8>),
+}
+
+/// Consecutive values sent on one connection.
+#[derive(Arbitrary, Debug)]
+pub struct Stream {
+    pub values: Vec<Value>,
+    /// Cut the encoded stream at this length.
+    pub truncate: Option<u16>,
+}
+
+impl Stream {
+    /// Encode the values in wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for value in &self.values {
+            value.encode(&mut bytes);
+        }
+        if let Some(length) = self.truncate {
+            bytes.truncate(length as usize);
+        }
+        bytes
+    }
+}
+
+impl Value {
+    /// Append the wire format of this value to `out`.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Value::SimpleString(line) => line_value(out, b'+', line),
+            Value::Error(line) => line_value(out, b'-', line),
+            Value::Integer(value) => header(out, b':', *value),
+            Value::BulkString(data) => bulk_string(out, data.len() as i64, data),
+            Value::NullBulkString => header(out, b'$', -1),
+            Value::Array(values) => array(out, values.len() as i64, values),
+            Value::NullArray => header(out, b'*', -1),
+            Value::Nested(depth, value) => {
+                for _ in 0..*depth {
+                    header(out, b'*', 1);
+                }
+                value.encode(out);
+            }
+            Value::BadLength(length, data) => bulk_string(out, *length, data),
+            Value::BadCount(count, values) => array(out, *count, values),
+            Value::Invalid(token, line) => line_value(out, *token, line),
+        }
+    }
+}
+
+fn header(out: &mut Vec<u8>, token: u8, value: i64) {
+    out.push(token);
+    out.extend_from_slice(value.to_string().as_bytes());
+    out.extend_from_slice(b"\r\n");
+}
+
+/// Line based values cannot contain CR or LF, replace them to stay valid.
+fn line_value(out: &mut Vec<u8>, token: u8, line: &[u8]) {
+    out.push(token);
+    out.extend(line.iter().map(|b| match b {
+        b'\r' | b'\n' => b' ',
+        b => *b,
+    }));
+    out.extend_from_slice(b"\r\n");
+}
+
+fn bulk_string(out: &mut Vec<u8>, length: i64, data: &[u8]) {
+    header(out, b'$', length);
+    out.extend_from_slice(data);
+    out.extend_from_slice(b"\r\n");
+}
+
+fn array(out: &mut Vec<u8>, count: i64, values: &[Value]) {
+    header(out, b'*', count);
+    for value in values {
+        value.encode(out);
+    }
+}
@@ -0,0 +1,185 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sawp::parser::{Direction, Parse};
+use sawp_diameter::Diameter;
+
+#[path = "support/alloc.rs"]
+mod alloc;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+const FLAG_REQUEST: u8 = 0x80;
+const FLAG_MANDATORY: u8 = 0x40;
+
+const CODE_CAPABILITIES_EXCHANGE: u32 = 257;
+const CODE_DEVICE_WATCHDOG: u32 = 280;
+
+const AVP_HOST_IP_ADDRESS: u32 = 257;
+const AVP_AUTH_APPLICATION_ID: u32 = 258;
+const AVP_VENDOR_SPECIFIC_APPLICATION_ID: u32 = 260;
+const AVP_ORIGIN_HOST: u32 = 264;
+const AVP_SUPPORTED_VENDOR_ID: u32 = 265;
+const AVP_VENDOR_ID: u32 = 266;
+const AVP_FIRMWARE_REVISION: u32 = 267;
+const AVP_RESULT_CODE: u32 = 268;
+const AVP_PRODUCT_NAME: u32 = 269;
+const AVP_ORIGIN_STATE_ID: u32 = 278;
+const AVP_FAILED_AVP: u32 = 279;
+const AVP_ORIGIN_REALM: u32 = 296;
+const AVP_EXPERIMENTAL_RESULT: u32 = 297;
+const AVP_EXPERIMENTAL_RESULT_CODE: u32 = 298;
+
+/// Encode an AVP without a vendor id, padded to 4 bytes.
+fn avp(code: u32, data: &[u8]) -> Vec<u8> {
+    let length = 8 + data.len() as u32;
+    let mut avp = code.to_be_bytes().to_vec();
+    avp.push(FLAG_MANDATORY);
+    avp.extend_from_slice(&length.to_be_bytes()[1..]);
+    avp.extend_from_slice(data);
+    // Pad to a multiple of four bytes
+    avp.resize(avp.len() + (4 - avp.len() % 4) % 4, 0);
+    avp
+}
+
+fn grouped(code: u32, avps: &[Vec<u8>]) -> Vec<u8> {
+    avp(code, &avps.concat())
+}
+
+fn unsigned32(code: u32, value: u32) -> Vec<u8> {
+    avp(code, &value.to_be_bytes())
+}
+
+/// Encode a message around the AVPs.
+fn message(flags: u8, code: u32, hop_id: u32, avps: &[Vec<u8>]) -> Vec<u8> {
+    let avps = avps.concat();
+    let length = 20 + avps.len() as u32;
+    let mut message = vec![1];
+    message.extend_from_slice(&length.to_be_bytes()[1..]);
+    message.push(flags);
+    message.extend_from_slice(&code.to_be_bytes()[1..]);
+    message.extend_from_slice(&0u32.to_be_bytes()); // Application ID: Common Messages
+    message.extend_from_slice(&hop_id.to_be_bytes());
+    message.extend_from_slice(&hop_id.rotate_left(16).to_be_bytes()); // End-to-End ID
+    message.extend_from_slice(&avps);
+    message
+}
+
+/// Capabilities exchange between two peers advertising vendor specific
+/// applications as grouped AVPs.
+fn capabilities_exchange() -> Vec<Vec<u8>> {
+    let vendor_application = |vendor: u32, application: u32| {
+        grouped(
+            AVP_VENDOR_SPECIFIC_APPLICATION_ID,
+            &[
+                unsigned32(AVP_VENDOR_ID, vendor),
+                unsigned32(AVP_AUTH_APPLICATION_ID, application),
+            ],
+        )
+    };
+    let request = message(
+        FLAG_REQUEST,
+        CODE_CAPABILITIES_EXCHANGE,
+        0x53ca_fe6a,
+        &[
+            avp(AVP_ORIGIN_HOST, b"mme01.epc.mnc001.mcc001.3gppnetwork.org"),
+            avp(AVP_ORIGIN_REALM, b"epc.mnc001.mcc001.3gppnetwork.org"),
+            avp(AVP_HOST_IP_ADDRESS, &[10, 20, 0, 11]),
+            unsigned32(AVP_VENDOR_ID, 10415),
+            avp(AVP_PRODUCT_NAME, b"sawp"),
+            unsigned32(AVP_ORIGIN_STATE_ID, 1_625_097_600),
+            unsigned32(AVP_SUPPORTED_VENDOR_ID, 10415),
+            unsigned32(AVP_SUPPORTED_VENDOR_ID, 5535),
+            vendor_application(10415, 16_777_251),
+            vendor_application(10415, 16_777_252),
+            unsigned32(AVP_FIRMWARE_REVISION, 1),
+        ],
+    );
+    let answer = message(
+        0,
+        CODE_CAPABILITIES_EXCHANGE,
+        0x53ca_fe6a,
+        &[
+            unsigned32(AVP_RESULT_CODE, 2001),
+            avp(AVP_ORIGIN_HOST, b"hss01.epc.mnc001.mcc001.3gppnetwork.org"),
+            avp(AVP_ORIGIN_REALM, b"epc.mnc001.mcc001.3gppnetwork.org"),
+            avp(AVP_HOST_IP_ADDRESS, &[10, 20, 0, 21]),
+            unsigned32(AVP_VENDOR_ID, 10415),
+            avp(AVP_PRODUCT_NAME, b"hss"),
+            vendor_application(10415, 16_777_251),
+            grouped(
+                AVP_EXPERIMENTAL_RESULT,
+                &[
+                    unsigned32(AVP_VENDOR_ID, 10415),
+                    unsigned32(AVP_EXPERIMENTAL_RESULT_CODE, 2001),
+                ],
+            ),
+            grouped(
+                AVP_FAILED_AVP,
+                &[grouped(
+                    AVP_VENDOR_SPECIFIC_APPLICATION_ID,
+                    &[
+                        unsigned32(AVP_VENDOR_ID, 5535),
+                        unsigned32(AVP_AUTH_APPLICATION_ID, 16_777_238),
+                    ],
+                )],
+            ),
+        ],
+    );
+    vec![request, answer]
+}
+
+/// Watchdog keepalives exchanged on an idle connection.
+fn device_watchdog() -> Vec<Vec<u8>> {
+    (0..10)
+        .map(|i| {
+            let flags = if i % 2 == 0 { FLAG_REQUEST } else { 0 };
+            let mut avps = vec![
+                avp(AVP_ORIGIN_HOST, b"mme01.epc.mnc001.mcc001.3gppnetwork.org"),
+                avp(AVP_ORIGIN_REALM, b"epc.mnc001.mcc001.3gppnetwork.org"),
+            ];
+            if flags == 0 {
+                avps.insert(0, unsigned32(AVP_RESULT_CODE, 2001));
+            }
+            message(flags, CODE_DEVICE_WATCHDOG, 0x1000 + i / 2, &avps)
+        })
+        .collect()
+}
+
+/// Parse every message in the corpus, returning how many were parsed.
+fn parse_corpus(diameter: &Diameter, corpus: &[Vec<u8>]) -> usize {
+    corpus
+        .iter()
+        .filter(|input| {
+            matches!(
+                diameter.parse(input, Direction::Unknown),
+                Ok((rest, Some(message))) if rest.is_empty() && message.error_flags.is_empty()
+            )
+        })
+        .count()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let corpora = vec![
+        ("grouped", capabilities_exchange()),
+        ("watchdog", device_watchdog()),
+    ];
+
+    let mut group = c.benchmark_group("diameter");
+    for (name, corpus) in &corpora {
+        // Assert every message parses cleanly before benchmarking
+        assert_eq!(corpus.len(), parse_corpus(&Diameter {}, corpus));
+        alloc::report(&format!("diameter/{}", name), || {
+            parse_corpus(&Diameter {}, corpus)
+        });
+
+        let bytes: usize = corpus.iter().map(Vec::len).sum();
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), corpus, |b, corpus| {
+            b.iter(|| parse_corpus(&Diameter {}, black_box(corpus)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
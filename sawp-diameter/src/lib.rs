@@ -22,9 +22,15 @@ use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use bitflags::bitflags;
 
+#[cfg(feature = "serialize")]
+use serde::{Serialize, Serializer};
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 #[derive(Debug)]
 pub struct Diameter {}
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Header {
     version: u8,
@@ -37,6 +43,7 @@ pub struct Header {
 }
 
 /// AVP Attribute Names as stated in the [protocol reference](https://tools.ietf.org/html/rfc6733#section-4.5)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq, TryFromPrimitive)]
 #[repr(u32)]
 pub enum AttributeCode {
@@ -92,6 +99,7 @@ pub enum AttributeCode {
     VendorSpecificApplicationId = 260,
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Attribute {
     /// Value of the code in AVP header
@@ -110,6 +118,7 @@ impl Attribute {
 }
 
 /// AVP Data Format as specified in the [protocol reference](https://tools.ietf.org/html/rfc6733#section-4.2)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub enum Value {
     Unhandled(Vec<u8>),
@@ -236,6 +245,7 @@ impl Value {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct AVP {
     attribute: Attribute,
@@ -260,6 +270,14 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serialize")]
+impl Serialize for ErrorFlags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.bits())
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Message {
     pub header: Header,
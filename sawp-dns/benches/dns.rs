@@ -0,0 +1,222 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sawp::parser::{Direction, Parse};
+use sawp_dns::Dns;
+
+#[path = "support/alloc.rs"]
+mod alloc;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+/// MX response where every name is compressed.
+const COMPRESSED_RESPONSE: &[u8] = &[
+    0xf7, 0x6f, // Transaction ID: 0xf76f
+    0x81, 0x80, // Flags: response, recursion desired, recursion available
+    0x00, 0x01, // QDCOUNT: 1
+    0x00, 0x06, // ANCOUNT: 6
+    0x00, 0x00, // NSCOUNT: 0
+    0x00, 0x06, // ARCOUNT: 6
+    0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+    // question: google.com
+    0x00, 0x0f, // RType: MX
+    0x00, 0x01, // RClass: IN
+    0xc0, 0x0c, // answer: google.com
+    0x00, 0x0f, // RType: MX
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x28, // TTL: 552
+    0x00, 0x0a, // Data length: 10
+    0x00, 0x28, // Preference: 40
+    0x05, 0x73, 0x6d, 0x74, 0x70, 0x34, 0xc0, 0x0c, // MX: smtp4.google.com
+    0xc0, 0x0c, // answer: google.com
+    0x00, 0x0f, // RType: MX
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x28, // TTL: 552
+    0x00, 0x0a, // Data length: 10
+    0x00, 0x0a, // Preference: 10
+    0x05, 0x73, 0x6d, 0x74, 0x70, 0x35, 0xc0, 0x0c, // MX: smtp5.google.com
+    0xc0, 0x0c, // answer: google.com
+    0x00, 0x0f, // RType: MX
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x28, // TTL: 552
+    0x00, 0x0a, // Data length: 10
+    0x00, 0x0a, // Preference: 10
+    0x05, 0x73, 0x6d, 0x74, 0x70, 0x36, 0xc0, 0x0c, // MX: smtp6.google.com
+    0xc0, 0x0c, // answer: google.com
+    0x00, 0x0f, // RType: MX
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x28, // TTL: 552
+    0x00, 0x0a, // Data length: 10
+    0x00, 0x0a, // Preference: 10
+    0x05, 0x73, 0x6d, 0x74, 0x70, 0x31, 0xc0, 0x0c, // MX: smtp1.google.com
+    0xc0, 0x0c, // answer: google.com
+    0x00, 0x0f, // RType: MX
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x28, // TTL: 552
+    0x00, 0x0a, // Data length: 10
+    0x00, 0x0a, // Preference: 10
+    0x05, 0x73, 0x6d, 0x74, 0x70, 0x32, 0xc0, 0x0c, // MX: smtp2.google.com
+    0xc0, 0x0c, // answer: google.com
+    0x00, 0x0f, // RType: MX
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x28, // TTL: 552
+    0x00, 0x0a, // Data length: 10
+    0x00, 0x28, // Preference: 10
+    0x05, 0x73, 0x6d, 0x74, 0x70, 0x33, 0xc0, 0x0c, // MX: smtp3.google.com
+    0xc0, 0x2a, // additional: smtp4.google.com
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x58, // TTL: 600
+    0x00, 0x04, // Data length: 4
+    0xd8, 0xef, 0x25, 0x1a, // 216.239.37.26
+    0xc0, 0x40, // additional: smtp5.google.com
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x58, // TTL: 600
+    0x00, 0x04, // Data length: 4
+    0x40, 0xe9, 0xa7, 0x19, // 64.233.167.25
+    0xc0, 0x56, // additional: smtp6.google.com
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x58, // TTL: 600
+    0x00, 0x04, // Data length: 4
+    0x42, 0x66, 0x09, 0x19, // 66.102.9.25
+    0xc0, 0x6c, // additional: smtp1.google.com
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x58, // TTL: 600
+    0x00, 0x04, // Data length: 4
+    0xd8, 0xef, 0x39, 0x19, // 216.239.57.25
+    0xc0, 0x82, // additional: smtp2.google.com
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x58, // TTL: 600
+    0x00, 0x04, // Data length: 4
+    0xd8, 0xef, 0x25, 0x19, // 216.239.37.25
+    0xc0, 0x98, // additional: smtp2.google.com
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x02, 0x58, // TTL: 600
+    0x00, 0x04, // Data length: 4
+    0xd8, 0xef, 0x39, 0x1a, // 216.239.57.26
+];
+
+/// Query advertising EDNS with the DNSSEC OK bit set.
+const EDNS_QUERY: &[u8] = &[
+    0x4a, 0x21, // Transaction ID: 0x4a21
+    0x01, 0x20, // Flags: recursion desired, authenticated data
+    0x00, 0x01, // QDCOUNT: 1
+    0x00, 0x00, // ANCOUNT: 0
+    0x00, 0x00, // NSCOUNT: 0
+    0x00, 0x01, // ARCOUNT: 1
+    0x03, 0x77, 0x77, 0x77, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d,
+    0x00, // question: www.example.com
+    0x00, 0x1c, // RType: AAAA
+    0x00, 0x01, // RClass: IN
+    0x00, // Additional: <Root>
+    0x00, 0x29, // RType: OPT
+    0x10, 0x00, // UDP payload size: 4096
+    0x00, // Higher bits in extended RCode: 0x00
+    0x00, // EDNS0 version: 0
+    0x80, 0x00, // Z: DNSSEC OK
+    0x00, 0x00, // Data length: 0
+];
+
+/// Query carrying a client cookie and an EDNS client subnet.
+const EDNS_OPTIONS_QUERY: &[u8] = &[
+    0x5a, 0x11, // Transaction ID: 0x5a11
+    0x01, 0x00, // Flags: recursion desired
+    0x00, 0x01, // QDCOUNT: 1
+    0x00, 0x00, // ANCOUNT: 0
+    0x00, 0x00, // NSCOUNT: 0
+    0x00, 0x01, // ARCOUNT: 1
+    0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+    // question: example.com
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0x00, // Additional: <Root>
+    0x00, 0x29, // RType: OPT
+    0x10, 0x00, // UDP payload size: 4096
+    0x00, // Higher bits in extended RCode: 0x00
+    0x00, // EDNS0 version: 0
+    0x00, 0x00, // Z: 0
+    0x00, 0x17, // Data length: 23
+    0x00, 0x0a, 0x00, 0x08, // Option: COOKIE, length: 8
+    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // Client cookie
+    0x00, 0x08, 0x00, 0x07, // Option: client subnet, length: 7
+    0x00, 0x01, 0x18, 0x00, 0xc0, 0x00, 0x02, // 192.0.2.0/24
+];
+
+/// NXDOMAIN response with an SOA authority record and an OPT record.
+const EDNS_RESPONSE: &[u8] = &[
+    0x82, 0x95, // Transaction ID: 0x8295
+    0x81, 0x83, // Flags: response, recursion desired, recursion available, NAMEERROR
+    0x00, 0x01, // QDCOUNT: 1
+    0x00, 0x00, // ANCOUNT: 0
+    0x00, 0x01, // NSCOUNT: 1
+    0x00, 0x01, // ARCOUNT: 1
+    0x03, 0x64, 0x6e, 0x65, 0x04, 0x6f, 0x69, 0x73, 0x66, 0x03, 0x6e, 0x65, 0x74, 0x00,
+    // question: dne.oisf.net
+    0x00, 0x01, // RType: A
+    0x00, 0x01, // RClass: IN
+    0xc0, 0x10, // Auth NS: dne.oisf.net
+    0x00, 0x06, // RTYPE: SOA
+    0x00, 0x01, // RClass: IN
+    0x00, 0x00, 0x03, 0x83, // TTL: 899
+    0x00, 0x45, // Data length: 69
+    0x06, 0x6e, 0x73, 0x2d, 0x31, 0x31, 0x30, 0x09, 0x61, 0x77, 0x73, 0x64, 0x6e, 0x73, 0x2d, 0x31,
+    0x33, 0x03, 0x63, 0x6f, 0x6d, 0x00, // ns-110.awsdns-13.com
+    0x11, 0x61, 0x77, 0x73, 0x64, 0x6e, 0x73, 0x2d, 0x68, 0x6f, 0x73, 0x74, 0x6d, 0x61, 0x73, 0x74,
+    0x65, 0x72, 0x06, 0x61, 0x6d, 0x61, 0x7a, 0x6f, 0x6e, 0xc0, 0x3b,
+    // Mailbox: awsdns-hostmaster.amazon.com
+    0x00, 0x00, 0x00, 0x01, // Serial number: 1
+    0x00, 0x00, 0x1c, 0x20, // Refresh interval: 7200
+    0x00, 0x00, 0x03, 0x84, // Retry interval: 900
+    0x00, 0x12, 0x75, 0x00, // Expire limit: 1209600
+    0x00, 0x01, 0x51, 0x80, // Minimum TTL: 86400
+    0x00, // Additional answer: <Root>
+    0x00, 0x29, // RType: OPT
+    0x02, 0x00, // UDP payload size: 512
+    0x00, 0x00, // Higher bits in extended RCode: 0x00
+    0x00, // EDNS0 version: 0
+    0x00, 0x00, // Z: 0
+    0x00, // Data length: 0
+];
+
+/// Parse every message in the corpus, returning how many were parsed.
+fn parse_corpus(dns: &Dns, corpus: &[&[u8]]) -> usize {
+    corpus
+        .iter()
+        .filter(|input| {
+            matches!(
+                dns.parse(input, Direction::Unknown),
+                Ok((rest, Some(_))) if rest.is_empty()
+            )
+        })
+        .count()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let corpora: &[(&str, &[&[u8]])] = &[
+        ("compressed", &[COMPRESSED_RESPONSE]),
+        ("edns", &[EDNS_QUERY, EDNS_OPTIONS_QUERY, EDNS_RESPONSE]),
+    ];
+
+    let mut group = c.benchmark_group("dns");
+    for (name, corpus) in corpora {
+        // Assert every message parses before benchmarking
+        assert_eq!(corpus.len(), parse_corpus(&Dns::default(), corpus));
+        alloc::report(&format!("dns/{}", name), || {
+            parse_corpus(&Dns::default(), corpus)
+        });
+
+        let bytes: usize = corpus.iter().map(|input| input.len()).sum();
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), corpus, |b, corpus| {
+            b.iter(|| parse_corpus(&Dns::default(), black_box(corpus)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
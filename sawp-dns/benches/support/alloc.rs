@@ -0,0 +1,43 @@
+//! Allocation counting for the benchmarks of this crate.
+//!
+//! Include this module with `#[path]` and install [`CountingAllocator`] as the
+//! `#[global_allocator]` of the benchmark.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// System allocator which counts every allocation and reallocation.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Print the average number of allocations `parse` makes per message.
+///
+/// `parse` must return the number of messages it parsed.
+pub fn report<F: FnOnce() -> usize>(name: &str, parse: F) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let messages = parse();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{}: {:.1} allocations per message ({} messages)",
+        name,
+        allocations as f64 / messages as f64,
+        messages
+    );
+}
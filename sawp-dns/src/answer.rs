@@ -1,3 +1,5 @@
+use alloc::{vec, vec::Vec};
+
 use nom::bytes::streaming::take;
 use nom::number::complete::be_u32;
 use nom::number::streaming::be_u16;
@@ -11,15 +13,21 @@ use crate::{custom_count, ErrorFlags, IResult, Name};
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 /// Per RFC1035/RFC4408: max RDATA len = 65535 octets. Since TXT RDATA includes a length byte before
 /// each TXT string, min size per TXT is 2 bytes, leaving maximum of 65535/2 parser runs needed.
 const MAX_TXT_PARSES: usize = 32767;
 /// First three bytes of an OPT AR - determines whether an AR should be parsed with special "OPT logic".
 const OPT_RR_START: [u8; 3] = [0, 0, 41];
+/// Length of the type, class and TTL following the name of a record
+const RECORD_FIXED_LEN: usize = 8;
 
 /// A parsed DNS answer
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Answer {
     pub name: Vec<u8>,
@@ -34,7 +42,13 @@ pub struct Answer {
 }
 
 impl Answer {
-    fn parse<'a>(
+    /// mDNS cache-flush bit, set when the record replaces cached records of the same
+    /// name, type and class. Only meaningful for messages parsed with [`crate::Mdns`].
+    pub fn cache_flush(&self) -> bool {
+        self.rtype != RecordType::OPT && self.rclass_raw & crate::MDNS_CLASS_FLAG != 0
+    }
+
+    pub(crate) fn parse<'a>(
         input: &'a [u8],
         reference_bytes: &'a [u8],
     ) -> IResult<'a, (Answer, Flags<ErrorFlags>)> {
@@ -67,9 +81,16 @@ impl Answer {
         let (input, data_len) = be_u16(input)?;
         let (rem, local_data) = take(data_len)(input)?;
 
-        // always call once
+        // UPDATE prerequisites and deletions use the ANY and NONE classes with no
+        // rdata, which would not parse as the record type.
+        // See https://tools.ietf.org/html/rfc2136#section-2.4
         let (mut local_data, (mut rdata, inner_error_flags)) =
-            RDataType::parse(local_data, reference_bytes, rtype)?;
+            if data_len == 0 && (rclass == RecordClass::ANY || rclass == RecordClass::NONE) {
+                (local_data, (RDataType::UNKNOWN(vec![]), ErrorFlags::none()))
+            } else {
+                // always call once
+                RDataType::parse(local_data, reference_bytes, rtype)?
+            };
         error_flags |= inner_error_flags;
 
         // get ref to buffer we will extend first, if TXT
@@ -93,7 +114,16 @@ impl Answer {
         Ok((rem, (answer, error_flags)))
     }
 
-    fn parse_additional<'a>(
+    /// Skip over a record without parsing it
+    pub(crate) fn skip(input: &[u8]) -> IResult<()> {
+        let (input, _) = Name::skip(input)?;
+        let (input, _) = take(RECORD_FIXED_LEN)(input)?;
+        let (input, data_len) = be_u16(input)?;
+        let (input, _) = take(data_len)(input)?;
+        Ok((input, ()))
+    }
+
+    pub(crate) fn parse_additional<'a>(
         input: &'a [u8],
         reference_bytes: &'a [u8],
     ) -> IResult<'a, (Answer, Flags<ErrorFlags>, bool)> {
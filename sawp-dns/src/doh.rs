@@ -0,0 +1,257 @@
+//! DNS over HTTPS
+//! [RFC8484](https://tools.ietf.org/html/rfc8484)
+//!
+//! Clients send a query either as the body of a POST request or base64url
+//! encoded in the `dns` parameter of a GET request. Responses carry the message
+//! in their body. Bodies of both are of the `application/dns-message` media
+//! type.
+//!
+//! These helpers extract the message from a decrypted exchange and parse it
+//! with [`crate::Dns`].
+
+use alloc::vec::Vec;
+
+use sawp::error::{Error, ErrorKind, Result};
+use sawp::parser::{Direction, Parse};
+
+use crate::{Dns, Message};
+
+/// Media type of DNS messages carried over HTTPS
+pub const DNS_MESSAGE_MEDIA_TYPE: &[u8] = b"application/dns-message";
+/// Name of the GET parameter holding the query
+const DNS_PARAMETER: &[u8] = b"dns";
+
+/// Whether a Content-Type or Accept header value is `application/dns-message`,
+/// ignoring case and parameters
+pub fn is_dns_message(content_type: &[u8]) -> bool {
+    let media_type = content_type.split(|c| *c == b';').next().unwrap_or(&[]);
+    trim(media_type).eq_ignore_ascii_case(DNS_MESSAGE_MEDIA_TYPE)
+}
+
+/// Decoded value of the `dns` parameter of a request URL. `url` may be an
+/// absolute URL, a request target such as `/dns-query?dns=...` or only the
+/// query string.
+///
+/// Returns None if the parameter is missing or is not valid base64url.
+pub fn dns_parameter(url: &[u8]) -> Option<Vec<u8>> {
+    let query = match url.iter().position(|c| *c == b'?') {
+        Some(position) => &url[position + 1..],
+        None => url,
+    };
+    let query = query.split(|c| *c == b'#').next().unwrap_or(&[]);
+    let value = query.split(|c| *c == b'&').find_map(|parameter| {
+        let mut parts = parameter.splitn(2, |c| *c == b'=');
+        if parts.next() == Some(DNS_PARAMETER) {
+            parts.next()
+        } else {
+            None
+        }
+    })?;
+    base64url_decode(&percent_decode(value)?)
+}
+
+/// Parse the query of a GET request from its URL
+pub fn parse_url(url: &[u8]) -> Result<Message> {
+    let message = dns_parameter(url).ok_or_else(|| Error::new(ErrorKind::InvalidData))?;
+    parse_message(&message, Direction::ToServer)
+}
+
+/// Parse the body of a POST request or of a response. The body is rejected if
+/// `content_type` is given and is not `application/dns-message`.
+pub fn parse_body(
+    content_type: Option<&[u8]>,
+    body: &[u8],
+    direction: Direction,
+) -> Result<Message> {
+    if let Some(content_type) = content_type {
+        if !is_dns_message(content_type) {
+            return Err(Error::new(ErrorKind::InvalidData));
+        }
+    }
+    parse_message(body, direction)
+}
+
+fn parse_message(input: &[u8], direction: Direction) -> Result<Message> {
+    let dns = Dns {
+        partial_messages: true,
+        ..Dns::default()
+    };
+    let (_, message) = dns.parse(input, direction)?;
+    message.ok_or_else(|| Error::new(ErrorKind::InvalidData))
+}
+
+fn trim(input: &[u8]) -> &[u8] {
+    let start = input
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(input.len());
+    let end = input
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(start, |position| position + 1);
+    &input[start..end]
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|value| value as u8)
+}
+
+/// Decode %XX escapes. Returns None for truncated or invalid escapes.
+fn percent_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut index = 0;
+    while index < input.len() {
+        if input[index] == b'%' {
+            let high = hex_value(*input.get(index + 1)?)?;
+            let low = hex_value(*input.get(index + 2)?)?;
+            output.push(high << 4 | low);
+            index += 3;
+        } else {
+            output.push(input[index]);
+            index += 1;
+        }
+    }
+    Some(output)
+}
+
+fn base64url_value(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'-' => Some(62),
+        b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decode base64url. Padding is not used by DoH but is accepted.
+fn base64url_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let end = input
+        .iter()
+        .rposition(|c| *c != b'=')
+        .map_or(0, |position| position + 1);
+    let input = &input[..end];
+    if input.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in input {
+        buffer = buffer << 6 | u32::from(base64url_value(*c)?);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::enums::RecordType;
+    use crate::ErrorFlags;
+    use rstest::rstest;
+    use sawp_flags::{Flag, Flags};
+
+    /// Query for www.example.com from RFC8484 section 4.1.1
+    const QUERY: &[u8] = &[
+        0x00, 0x00, // Transaction ID: 0
+        0x01, 0x00, // Flags: RD
+        0x00, 0x01, // QDCOUNT: 1
+        0x00, 0x00, // ANCOUNT: 0
+        0x00, 0x00, // NSCOUNT: 0
+        0x00, 0x00, // ARCOUNT: 0
+        0x03, 0x77, 0x77, 0x77, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f,
+        0x6d, 0x00, // Query: www.example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+    ];
+    const ENCODED_QUERY: &[u8] = b"AAABAAABAAAAAAAAA3d3dwdleGFtcGxlA2NvbQAAAQAB";
+
+    #[rstest(
+        content_type,
+        expected,
+        case::exact(b"application/dns-message", true),
+        case::case(b"Application/DNS-Message", true),
+        case::parameters(b"application/dns-message; charset=binary", true),
+        case::json(b"application/dns-json", false),
+        case::empty(b"", false)
+    )]
+    fn dns_message_media_type(content_type: &[u8], expected: bool) {
+        assert_eq!(is_dns_message(content_type), expected);
+    }
+
+    #[rstest(
+        url,
+        expected,
+        case::request_target(
+            &[b"/dns-query?dns=".as_ref(), ENCODED_QUERY].concat(),
+            Some(QUERY.to_vec())
+        ),
+        case::absolute(
+            &[b"https://dns.example.com/dns-query?ct&dns=".as_ref(), ENCODED_QUERY, b"#top"].concat(),
+            Some(QUERY.to_vec())
+        ),
+        case::query_string(&[b"dns=".as_ref(), ENCODED_QUERY].concat(), Some(QUERY.to_vec())),
+        case::padding(b"/dns-query?dns=AQI%3D", Some(vec![0x01, 0x02])),
+        case::url_safe(b"/dns-query?dns=-_8", Some(vec![0xfb, 0xff])),
+        case::empty(b"/dns-query?dns=", Some(vec![])),
+        case::missing(b"/dns-query?name=example.com", None),
+        case::standard_alphabet(b"/dns-query?dns=+/8", None),
+        case::invalid_length(b"/dns-query?dns=AAAAA", None),
+        case::invalid_escape(b"/dns-query?dns=AQI%3", None),
+    )]
+    fn dns_parameter(url: &[u8], expected: Option<Vec<u8>>) {
+        assert_eq!(super::dns_parameter(url), expected);
+    }
+
+    #[test]
+    fn parse_get() {
+        let message = parse_url(&[b"/dns-query?dns=".as_ref(), ENCODED_QUERY].concat()).unwrap();
+        assert_eq!(message.queries[0].name, b"www.example.com");
+        assert_eq!(message.queries[0].record_type, RecordType::A);
+        assert_eq!(
+            parse_url(b"/dns-query").unwrap_err(),
+            Error::new(ErrorKind::InvalidData)
+        );
+    }
+
+    #[rstest(
+        content_type,
+        body,
+        expected,
+        case::dns_message(Some(DNS_MESSAGE_MEDIA_TYPE), QUERY, Ok((1, ErrorFlags::none()))),
+        case::no_content_type(None, QUERY, Ok((1, ErrorFlags::none()))),
+        case::json(
+            Some(b"application/dns-json".as_ref()),
+            QUERY,
+            Err(Error::new(ErrorKind::InvalidData))
+        ),
+        case::truncated(
+            Some(DNS_MESSAGE_MEDIA_TYPE),
+            &QUERY[..20],
+            Ok((0, ErrorFlags::TruncatedSection.into()))
+        ),
+        case::truncated_header(
+            Some(DNS_MESSAGE_MEDIA_TYPE),
+            &QUERY[..8],
+            Err(Error::incomplete_needed(2))
+        ),
+    )]
+    fn parse_post(
+        content_type: Option<&[u8]>,
+        body: &[u8],
+        expected: Result<(usize, Flags<ErrorFlags>)>,
+    ) {
+        assert_eq!(
+            parse_body(content_type, body, Direction::ToServer)
+                .map(|message| (message.queries.len(), message.error_flags)),
+            expected
+        );
+    }
+}
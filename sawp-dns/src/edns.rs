@@ -10,19 +10,25 @@
 
 #![allow(clippy::upper_case_acronyms)]
 
-use nom::bytes::streaming::take;
-use nom::number::streaming::be_u16;
+use alloc::vec::Vec;
+
+use nom::bytes::complete::take;
+use nom::number::complete::be_u16;
 
 use num_enum::TryFromPrimitive;
 
 use sawp_flags::{Flag, Flags};
 
-use std::convert::TryFrom;
+use core::convert::TryFrom;
 
-use crate::{custom_many0, ErrorFlags, IResult};
+use crate::{ErrorFlags, IResult, Name};
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum OptionCode {
@@ -69,29 +75,277 @@ impl OptionCode {
     }
 }
 
+/// Address families used by the client subnet option
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum AddressFamily {
+    IPV4 = 1,
+    IPV6 = 2,
+    UNKNOWN,
+}
+
+impl AddressFamily {
+    pub fn from_raw(val: u16) -> Self {
+        AddressFamily::try_from(val).unwrap_or(AddressFamily::UNKNOWN)
+    }
+
+    /// Length of a full address in bits
+    fn max_prefix_len(self) -> Option<u8> {
+        match self {
+            AddressFamily::IPV4 => Some(32),
+            AddressFamily::IPV6 => Some(128),
+            AddressFamily::UNKNOWN => None,
+        }
+    }
+}
+
+/// Extended DNS Error info codes. See https://tools.ietf.org/html/rfc8914#section-4
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum ExtendedErrorCode {
+    OTHER = 0,
+    UNSUPPORTEDDNSKEYALGORITHM = 1,
+    UNSUPPORTEDDSDIGESTTYPE = 2,
+    STALEANSWER = 3,
+    FORGEDANSWER = 4,
+    DNSSECINDETERMINATE = 5,
+    DNSSECBOGUS = 6,
+    SIGNATUREEXPIRED = 7,
+    SIGNATURENOTYETVALID = 8,
+    DNSKEYMISSING = 9,
+    RRSIGSMISSING = 10,
+    NOZONEKEYBITSET = 11,
+    NSECMISSING = 12,
+    CACHEDERROR = 13,
+    NOTREADY = 14,
+    BLOCKED = 15,
+    CENSORED = 16,
+    FILTERED = 17,
+    PROHIBITED = 18,
+    STALENXDOMAINANSWER = 19,
+    NOTAUTHORITATIVE = 20,
+    NOTSUPPORTED = 21,
+    NOREACHABLEAUTHORITY = 22,
+    NETWORKERROR = 23,
+    INVALIDDATA = 24,
+    /// See https://tools.ietf.org/html/rfc9077
+    SIGNATUREEXPIREDBEFOREVALID = 25,
+    /// See https://tools.ietf.org/html/rfc9250
+    TOOEARLY = 26,
+    /// See https://tools.ietf.org/html/rfc9276
+    UNSUPPORTEDNSEC3ITERATIONSVALUE = 27,
+    UNKNOWN,
+}
+
+impl ExtendedErrorCode {
+    pub fn from_raw(val: u16) -> Self {
+        ExtendedErrorCode::try_from(val).unwrap_or(ExtendedErrorCode::UNKNOWN)
+    }
+}
+
+/// Origin network of a query. See https://tools.ietf.org/html/rfc7871#section-6
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct ClientSubnet {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub family: AddressFamily,
+    /// Leftmost bits of the address used in the query
+    pub source_prefix_len: u8,
+    /// Leftmost bits of the address the response covers, zero in queries
+    pub scope_prefix_len: u8,
+    /// Address truncated to the source prefix length
+    pub address: Vec<u8>,
+}
+
+/// See https://tools.ietf.org/html/rfc7873#section-4
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct Cookie {
+    pub client: Vec<u8>,
+    /// Empty until the server has replied with a cookie
+    pub server: Vec<u8>,
+}
+
+/// See https://tools.ietf.org/html/rfc8914#section-2
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct ExtendedError {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub info_code: ExtendedErrorCode,
+    pub info_code_raw: u16,
+    /// UTF-8 text for humans, may be empty
+    pub extra_text: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub enum EdnsOptionValue {
+    NSID(Vec<u8>),
+    EDNSCLIENTSUBNET(ClientSubnet),
+    /// Zone expiry in seconds, absent in queries
+    EDNSEXPIRE(Option<u32>),
+    COOKIE(Cookie),
+    /// Idle timeout in units of 100 milliseconds, absent in queries
+    EDNSTCPKEEPALIVE(Option<u16>),
+    /// Number of padding octets
+    PADDING(u16),
+    /// Closest trust point the resolver already has
+    CHAIN(Vec<u8>),
+    EDNSKEYTAG(Vec<u16>),
+    EDNSERROR(ExtendedError),
+    /// Options without a decoder and malformed payloads, see `EdnsOption::data`
+    UNKNOWN,
+}
+
+/// Client cookies are always eight bytes
+const CLIENT_COOKIE_LEN: usize = 8;
+const MIN_SERVER_COOKIE_LEN: usize = 8;
+const MAX_SERVER_COOKIE_LEN: usize = 32;
+
+impl EdnsOptionValue {
+    /// Decode the payload of an option, returns None if it is malformed.
+    fn parse(code: OptionCode, data: &[u8]) -> Option<EdnsOptionValue> {
+        match code {
+            OptionCode::NSID => Some(EdnsOptionValue::NSID(data.to_vec())),
+            OptionCode::EDNSCLIENTSUBNET => {
+                EdnsOptionValue::parse_client_subnet(data).map(EdnsOptionValue::EDNSCLIENTSUBNET)
+            }
+            OptionCode::EDNSEXPIRE => match data.len() {
+                0 => Some(EdnsOptionValue::EDNSEXPIRE(None)),
+                4 => Some(EdnsOptionValue::EDNSEXPIRE(Some(u32::from_be_bytes([
+                    data[0], data[1], data[2], data[3],
+                ])))),
+                _ => None,
+            },
+            OptionCode::COOKIE => EdnsOptionValue::parse_cookie(data).map(EdnsOptionValue::COOKIE),
+            OptionCode::EDNSTCPKEEPALIVE => match data.len() {
+                0 => Some(EdnsOptionValue::EDNSTCPKEEPALIVE(None)),
+                2 => Some(EdnsOptionValue::EDNSTCPKEEPALIVE(Some(u16::from_be_bytes(
+                    [data[0], data[1]],
+                )))),
+                _ => None,
+            },
+            OptionCode::PADDING => Some(EdnsOptionValue::PADDING(data.len() as u16)),
+            OptionCode::CHAIN => match Name::parse(data)(data) {
+                Ok((rem, (name, error_flags))) if rem.is_empty() && error_flags.is_empty() => {
+                    Some(EdnsOptionValue::CHAIN(name))
+                }
+                _ => None,
+            },
+            OptionCode::EDNSKEYTAG => {
+                let tags = data.chunks_exact(2);
+                if data.is_empty() || !tags.remainder().is_empty() {
+                    return None;
+                }
+                Some(EdnsOptionValue::EDNSKEYTAG(
+                    tags.map(|tag| u16::from_be_bytes([tag[0], tag[1]]))
+                        .collect(),
+                ))
+            }
+            OptionCode::EDNSERROR => {
+                if data.len() < 2 {
+                    return None;
+                }
+                let (info_code, extra_text) = data.split_at(2);
+                let info_code_raw = u16::from_be_bytes([info_code[0], info_code[1]]);
+                Some(EdnsOptionValue::EDNSERROR(ExtendedError {
+                    info_code: ExtendedErrorCode::from_raw(info_code_raw),
+                    info_code_raw,
+                    extra_text: extra_text.to_vec(),
+                }))
+            }
+            _ => Some(EdnsOptionValue::UNKNOWN),
+        }
+    }
+
+    fn parse_client_subnet(data: &[u8]) -> Option<ClientSubnet> {
+        if data.len() < 4 {
+            return None;
+        }
+        let family = AddressFamily::from_raw(u16::from_be_bytes([data[0], data[1]]));
+        let source_prefix_len = data[2];
+        let scope_prefix_len = data[3];
+        let address = &data[4..];
+
+        let max_prefix_len = family.max_prefix_len()?;
+        if source_prefix_len > max_prefix_len || scope_prefix_len > max_prefix_len {
+            return None;
+        }
+        // The address must use the fewest octets holding the source prefix
+        let address_bits = address.len() * 8;
+        let source_bits = usize::from(source_prefix_len);
+        if address_bits < source_bits || address_bits >= source_bits + 8 {
+            return None;
+        }
+
+        Some(ClientSubnet {
+            family,
+            source_prefix_len,
+            scope_prefix_len,
+            address: address.to_vec(),
+        })
+    }
+
+    fn parse_cookie(data: &[u8]) -> Option<Cookie> {
+        if data.len() < CLIENT_COOKIE_LEN {
+            return None;
+        }
+        let (client, server) = data.split_at(CLIENT_COOKIE_LEN);
+        if !server.is_empty()
+            && (server.len() < MIN_SERVER_COOKIE_LEN || server.len() > MAX_SERVER_COOKIE_LEN)
+        {
+            return None;
+        }
+
+        Some(Cookie {
+            client: client.to_vec(),
+            server: server.to_vec(),
+        })
+    }
+}
+
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct EdnsOption {
     #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
     pub code: OptionCode,
+    /// Raw option payload
     pub data: Vec<u8>,
+    pub value: EdnsOptionValue,
 }
 
 impl EdnsOption {
     pub fn parse(input: &[u8]) -> IResult<(EdnsOption, Flags<ErrorFlags>)> {
-        let (input, (code, inner_error_flags)) = EdnsOption::parse_option_code(input)?;
+        let (input, (code, mut error_flags)) = EdnsOption::parse_option_code(input)?;
         let (input, option_length) = be_u16(input)?;
         let (input, data) = take(option_length)(input)?;
 
+        let value = EdnsOptionValue::parse(code, data).unwrap_or_else(|| {
+            error_flags |= ErrorFlags::EdnsOptionMalformed;
+            EdnsOptionValue::UNKNOWN
+        });
+
         Ok((
             input,
             (
                 EdnsOption {
                     code,
                     data: data.to_vec(),
+                    value,
                 },
-                inner_error_flags,
+                error_flags,
             ),
         ))
     }
@@ -107,21 +361,295 @@ impl EdnsOption {
         Ok((input, (code, error_flags)))
     }
 
-    pub fn parse_options(
-        input: &[u8],
-        data_len: u16,
-    ) -> IResult<(Vec<EdnsOption>, Flags<ErrorFlags>)> {
+    /// Parse the options filling the RDATA of an OPT record.
+    ///
+    /// Parsing stops at the first option which does not fit in the RDATA.
+    pub fn parse_options(input: &[u8]) -> IResult<(Vec<EdnsOption>, Flags<ErrorFlags>)> {
+        let mut options = Vec::new();
         let mut error_flags = ErrorFlags::none();
-        if data_len < 4 {
-            return Ok((input, (vec![], error_flags)));
-        }
+        let mut input = input;
 
-        let (input, options) = custom_many0(|input| {
-            let (input, (option, inner_error_flags)) = EdnsOption::parse(input)?;
-            error_flags |= inner_error_flags;
-            Ok((input, option))
-        })(input)?;
+        while !input.is_empty() {
+            match EdnsOption::parse(input) {
+                Ok((rem, (option, inner_error_flags))) => {
+                    error_flags |= inner_error_flags;
+                    options.push(option);
+                    input = rem;
+                }
+                Err(_) => {
+                    error_flags |= ErrorFlags::EdnsOptionMalformed;
+                    break;
+                }
+            }
+        }
 
         Ok((input, (options, error_flags)))
     }
 }
+
+#[cfg(test)]
+mod test {
+    #![allow(clippy::type_complexity)]
+
+    use crate::edns::{
+        AddressFamily, ClientSubnet, Cookie, EdnsOption, EdnsOptionValue, ExtendedError,
+        ExtendedErrorCode, OptionCode,
+    };
+    use crate::{ErrorFlags, IResult};
+    use alloc::vec::Vec;
+    use rstest::rstest;
+    use sawp_flags::{Flag, Flags};
+
+    #[rstest(
+    input,
+    expected,
+    case::empty(b"", Ok((b"".as_ref(), (vec![], ErrorFlags::none())))),
+    case::client_subnet_ipv6(
+        &[
+            0x00, 0x08, 0x00, 0x0b, // option: client subnet, length: 11
+            0x00, 0x02, // family: IPv6
+            0x38, // source prefix length: 56
+            0x30, // scope prefix length: 48
+            0x20, 0x01, 0x0d, 0xb8, 0x12, 0x34, 0x56, // address: 2001:db8:1234:5600::/56
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::EDNSCLIENTSUBNET,
+                    data: vec![0x00, 0x02, 0x38, 0x30, 0x20, 0x01, 0x0d, 0xb8, 0x12, 0x34, 0x56],
+                    value: EdnsOptionValue::EDNSCLIENTSUBNET(ClientSubnet {
+                        family: AddressFamily::IPV6,
+                        source_prefix_len: 56,
+                        scope_prefix_len: 48,
+                        address: vec![0x20, 0x01, 0x0d, 0xb8, 0x12, 0x34, 0x56],
+                    }),
+                }],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::client_subnet_long_address(
+        &[
+            0x00, 0x08, 0x00, 0x08, // option: client subnet, length: 8
+            0x00, 0x01, // family: IPv4
+            0x10, // source prefix length: 16
+            0x00, // scope prefix length: 0
+            0xc0, 0x00, 0x02, 0x01, // address: more octets than the prefix needs
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::EDNSCLIENTSUBNET,
+                    data: vec![0x00, 0x01, 0x10, 0x00, 0xc0, 0x00, 0x02, 0x01],
+                    value: EdnsOptionValue::UNKNOWN,
+                }],
+                ErrorFlags::EdnsOptionMalformed.into(),
+            )
+        ))
+    ),
+    case::client_subnet_unknown_family(
+        &[0x00, 0x08, 0x00, 0x04, 0x00, 0x03, 0x00, 0x00],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::EDNSCLIENTSUBNET,
+                    data: vec![0x00, 0x03, 0x00, 0x00],
+                    value: EdnsOptionValue::UNKNOWN,
+                }],
+                ErrorFlags::EdnsOptionMalformed.into(),
+            )
+        ))
+    ),
+    case::server_cookie(
+        &[
+            0x00, 0x0a, 0x00, 0x10, // option: COOKIE, length: 16
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // client cookie
+            0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, // server cookie
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::COOKIE,
+                    data: vec![
+                        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x11, 0x12, 0x13, 0x14,
+                        0x15, 0x16, 0x17, 0x18,
+                    ],
+                    value: EdnsOptionValue::COOKIE(Cookie {
+                        client: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+                        server: vec![0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18],
+                    }),
+                }],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::short_server_cookie(
+        &[
+            0x00, 0x0a, 0x00, 0x0a, // option: COOKIE, length: 10
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // client cookie
+            0x11, 0x12, // server cookie: shorter than 8 bytes
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::COOKIE,
+                    data: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x11, 0x12],
+                    value: EdnsOptionValue::UNKNOWN,
+                }],
+                ErrorFlags::EdnsOptionMalformed.into(),
+            )
+        ))
+    ),
+    case::extended_error(
+        &[
+            0x00, 0x0f, 0x00, 0x09, // option: extended error, length: 9
+            0x00, 0x0f, // info code: blocked
+            0x6d, 0x61, 0x6c, 0x77, 0x61, 0x72, 0x65, // extra text: malware
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::EDNSERROR,
+                    data: vec![0x00, 0x0f, 0x6d, 0x61, 0x6c, 0x77, 0x61, 0x72, 0x65],
+                    value: EdnsOptionValue::EDNSERROR(ExtendedError {
+                        info_code: ExtendedErrorCode::BLOCKED,
+                        info_code_raw: 15,
+                        extra_text: b"malware".to_vec(),
+                    }),
+                }],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::keepalive_nsid_padding(
+        &[
+            0x00, 0x0b, 0x00, 0x00, // option: TCP keepalive, length: 0
+            0x00, 0x0b, 0x00, 0x02, 0x01, 0x2c, // option: TCP keepalive, timeout: 30s
+            0x00, 0x03, 0x00, 0x02, 0x6e, 0x31, // option: NSID, n1
+            0x00, 0x0c, 0x00, 0x03, 0x00, 0x00, 0x00, // option: padding, length: 3
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    EdnsOption {
+                        code: OptionCode::EDNSTCPKEEPALIVE,
+                        data: vec![],
+                        value: EdnsOptionValue::EDNSTCPKEEPALIVE(None),
+                    },
+                    EdnsOption {
+                        code: OptionCode::EDNSTCPKEEPALIVE,
+                        data: vec![0x01, 0x2c],
+                        value: EdnsOptionValue::EDNSTCPKEEPALIVE(Some(300)),
+                    },
+                    EdnsOption {
+                        code: OptionCode::NSID,
+                        data: b"n1".to_vec(),
+                        value: EdnsOptionValue::NSID(b"n1".to_vec()),
+                    },
+                    EdnsOption {
+                        code: OptionCode::PADDING,
+                        data: vec![0x00, 0x00, 0x00],
+                        value: EdnsOptionValue::PADDING(3),
+                    },
+                ],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::expire_chain_key_tag(
+        &[
+            0x00, 0x09, 0x00, 0x04, 0x00, 0x09, 0x3a, 0x80, // option: expire, 604800
+            0x00, 0x0d, 0x00, 0x05, 0x03, 0x6f, 0x72, 0x67, 0x00, // option: chain, org
+            0x00, 0x0e, 0x00, 0x04, 0x4f, 0x66, 0x9d, 0x0c, // option: key tag, 20326, 40204
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    EdnsOption {
+                        code: OptionCode::EDNSEXPIRE,
+                        data: vec![0x00, 0x09, 0x3a, 0x80],
+                        value: EdnsOptionValue::EDNSEXPIRE(Some(604_800)),
+                    },
+                    EdnsOption {
+                        code: OptionCode::CHAIN,
+                        data: vec![0x03, 0x6f, 0x72, 0x67, 0x00],
+                        value: EdnsOptionValue::CHAIN(b"org".to_vec()),
+                    },
+                    EdnsOption {
+                        code: OptionCode::EDNSKEYTAG,
+                        data: vec![0x4f, 0x66, 0x9d, 0x0c],
+                        value: EdnsOptionValue::EDNSKEYTAG(vec![20326, 40204]),
+                    },
+                ],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::malformed_expire_and_key_tag(
+        &[
+            0x00, 0x09, 0x00, 0x02, 0x00, 0x09, // option: expire, 2 bytes
+            0x00, 0x0e, 0x00, 0x03, 0x4f, 0x66, 0x9d, // option: key tag, odd length
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    EdnsOption {
+                        code: OptionCode::EDNSEXPIRE,
+                        data: vec![0x00, 0x09],
+                        value: EdnsOptionValue::UNKNOWN,
+                    },
+                    EdnsOption {
+                        code: OptionCode::EDNSKEYTAG,
+                        data: vec![0x4f, 0x66, 0x9d],
+                        value: EdnsOptionValue::UNKNOWN,
+                    },
+                ],
+                ErrorFlags::EdnsOptionMalformed.into(),
+            )
+        ))
+    ),
+    case::truncated(
+        &[
+            0x00, 0x03, 0x00, 0x00, // option: NSID, length: 0
+            0x00, 0x0a, 0x00, 0x08, 0x01, 0x02, // option: COOKIE, cut short
+        ],
+        Ok((
+            [0x00, 0x0a, 0x00, 0x08, 0x01, 0x02].as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::NSID,
+                    data: vec![],
+                    value: EdnsOptionValue::NSID(vec![]),
+                }],
+                ErrorFlags::EdnsOptionMalformed.into(),
+            )
+        ))
+    ),
+    case::unknown_code(
+        &[0xff, 0xfe, 0x00, 0x01, 0x00],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![EdnsOption {
+                    code: OptionCode::UNKNOWN,
+                    data: vec![0x00],
+                    value: EdnsOptionValue::UNKNOWN,
+                }],
+                ErrorFlags::EdnsParseFail.into(),
+            )
+        ))
+    ),
+    )]
+    fn parse_options(input: &[u8], expected: IResult<(Vec<EdnsOption>, Flags<ErrorFlags>)>) {
+        assert_eq!(EdnsOption::parse_options(input), expected);
+    }
+}
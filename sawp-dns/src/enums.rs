@@ -2,8 +2,12 @@
 
 use num_enum::TryFromPrimitive;
 
-use std::convert::TryFrom;
+use core::convert::TryFrom;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum RecordType {
@@ -194,6 +198,7 @@ impl RecordType {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum RecordClass {
@@ -211,6 +216,7 @@ impl RecordClass {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum OpCode {
@@ -229,6 +235,7 @@ impl OpCode {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum ResponseCode {
@@ -263,6 +270,7 @@ impl ResponseCode {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum OptResponseCode {
@@ -301,6 +309,7 @@ impl OptResponseCode {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum TSigResponseCode {
@@ -352,6 +361,7 @@ impl TSigResponseCode {
 }
 
 /// Indicates whether the message is a query or response.
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(u8)]
 pub enum QueryResponse {
@@ -360,6 +370,7 @@ pub enum QueryResponse {
 }
 
 /// Helper enum for determining where to store parsed answers in the message
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum AnswerType {
@@ -368,6 +379,7 @@ pub enum AnswerType {
     Additional = 2,
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum SshfpAlgorithm {
@@ -385,6 +397,7 @@ impl SshfpAlgorithm {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum SshfpFingerprint {
@@ -400,6 +413,7 @@ impl SshfpFingerprint {
     }
 }
 
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum TkeyMode {
@@ -417,3 +431,201 @@ impl TkeyMode {
         TkeyMode::try_from(val).unwrap_or(TkeyMode::UNKNOWN)
     }
 }
+
+/// DNSSEC algorithm numbers used by DNSKEY, RRSIG and DS records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum DnssecAlgorithm {
+    /// Only valid in CDS and CDNSKEY records, requests removal of the DS RRset
+    DELETE = 0,
+    /// Deprecated
+    RSAMD5 = 1,
+    DH = 2,
+    DSA = 3,
+    RSASHA1 = 5,
+    DSANSEC3SHA1 = 6,
+    RSASHA1NSEC3SHA1 = 7,
+    RSASHA256 = 8,
+    RSASHA512 = 10,
+    ECCGOST = 12,
+    ECDSAP256SHA256 = 13,
+    ECDSAP384SHA384 = 14,
+    ED25519 = 15,
+    ED448 = 16,
+    INDIRECT = 252,
+    PRIVATEDNS = 253,
+    PRIVATEOID = 254,
+    UNKNOWN,
+}
+
+impl DnssecAlgorithm {
+    pub fn from_raw(val: u8) -> Self {
+        DnssecAlgorithm::try_from(val).unwrap_or(DnssecAlgorithm::UNKNOWN)
+    }
+}
+
+/// Digest algorithms used by DS and CDS records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum DigestType {
+    RESERVED = 0,
+    SHA1 = 1,
+    SHA256 = 2,
+    GOST = 3,
+    SHA384 = 4,
+    UNKNOWN,
+}
+
+impl DigestType {
+    pub fn from_raw(val: u8) -> Self {
+        DigestType::try_from(val).unwrap_or(DigestType::UNKNOWN)
+    }
+}
+
+/// Hash algorithms used by NSEC3 and NSEC3PARAM records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum Nsec3HashAlgorithm {
+    RESERVED = 0,
+    SHA1 = 1,
+    UNKNOWN,
+}
+
+impl Nsec3HashAlgorithm {
+    pub fn from_raw(val: u8) -> Self {
+        Nsec3HashAlgorithm::try_from(val).unwrap_or(Nsec3HashAlgorithm::UNKNOWN)
+    }
+}
+
+/// Certificate usages used by TLSA and SMIMEA records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum TlsaCertUsage {
+    /// CA constraint
+    PKIXTA = 0,
+    /// Service certificate constraint
+    PKIXEE = 1,
+    /// Trust anchor assertion
+    DANETA = 2,
+    /// Domain-issued certificate
+    DANEEE = 3,
+    UNKNOWN,
+    /// Reserved for private use
+    PRIVCERT = 255,
+}
+
+impl TlsaCertUsage {
+    pub fn from_raw(val: u8) -> Self {
+        TlsaCertUsage::try_from(val).unwrap_or(TlsaCertUsage::UNKNOWN)
+    }
+}
+
+/// Part of the certificate matched by TLSA and SMIMEA records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum TlsaSelector {
+    /// Full certificate
+    CERT = 0,
+    /// SubjectPublicKeyInfo
+    SPKI = 1,
+    UNKNOWN,
+    /// Reserved for private use
+    PRIVSEL = 255,
+}
+
+impl TlsaSelector {
+    pub fn from_raw(val: u8) -> Self {
+        TlsaSelector::try_from(val).unwrap_or(TlsaSelector::UNKNOWN)
+    }
+}
+
+/// How the certificate association data is presented in TLSA and SMIMEA records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum TlsaMatchingType {
+    /// Exact match on the selected content
+    FULL = 0,
+    SHA256 = 1,
+    SHA512 = 2,
+    UNKNOWN,
+    /// Reserved for private use
+    PRIVMATCH = 255,
+}
+
+impl TlsaMatchingType {
+    pub fn from_raw(val: u8) -> Self {
+        TlsaMatchingType::try_from(val).unwrap_or(TlsaMatchingType::UNKNOWN)
+    }
+}
+
+/// Certificate formats used by CERT records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum CertType {
+    RESERVED = 0,
+    /// X.509
+    PKIX = 1,
+    /// SPKI
+    SPKI = 2,
+    /// OpenPGP packet
+    PGP = 3,
+    /// URL of an X.509 data object
+    IPKIX = 4,
+    /// URL of an SPKI certificate
+    ISPKI = 5,
+    /// Fingerprint and URL of an OpenPGP packet
+    IPGP = 6,
+    /// Attribute certificate
+    ACPKIX = 7,
+    /// URL of an attribute certificate
+    IACPKIX = 8,
+    URI = 253,
+    OID = 254,
+    UNKNOWN,
+}
+
+impl CertType {
+    pub fn from_raw(val: u16) -> Self {
+        CertType::try_from(val).unwrap_or(CertType::UNKNOWN)
+    }
+}
+
+/// Zone collation schemes used by ZONEMD records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum ZonemdScheme {
+    RESERVED = 0,
+    SIMPLE = 1,
+    UNKNOWN,
+}
+
+impl ZonemdScheme {
+    pub fn from_raw(val: u8) -> Self {
+        ZonemdScheme::try_from(val).unwrap_or(ZonemdScheme::UNKNOWN)
+    }
+}
+
+/// Hash algorithms used by ZONEMD records
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum ZonemdHashAlgorithm {
+    RESERVED = 0,
+    SHA384 = 1,
+    SHA512 = 2,
+    UNKNOWN,
+}
+
+impl ZonemdHashAlgorithm {
+    pub fn from_raw(val: u8) -> Self {
+        ZonemdHashAlgorithm::try_from(val).unwrap_or(ZonemdHashAlgorithm::UNKNOWN)
+    }
+}
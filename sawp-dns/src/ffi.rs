@@ -1,8 +1,12 @@
 #![allow(dead_code)]
 use super::*;
+use nbns::{Nbns, NbnsMessage};
 use sawp::error::Error;
 use sawp::parser::Parse;
 use sawp_ffi::*;
+#[cfg(feature = "tsig")]
+use tsig::{TsigAlgorithm, TsigKey, TsigStatus};
+use xfr::ZoneTransfer;
 
 #[repr(C)]
 pub struct ParseResult {
@@ -11,9 +15,25 @@ pub struct ParseResult {
     error: *mut Error,
 }
 
+#[repr(C)]
+pub struct NbnsParseResult {
+    message: *mut NbnsMessage,
+    size_read: usize,
+    error: *mut Error,
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn sawp_dns_create() -> *mut Dns {
-    let parser = Dns {};
+    let parser = Dns::default();
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_create_with_options(
+    max_records: usize,
+    partial_messages: bool,
+) -> *mut Dns {
+    let parser = Dns::new(max_records, partial_messages);
     parser.into_ffi_ptr()
 }
 
@@ -25,6 +45,86 @@ pub unsafe extern "C" fn sawp_dns_destroy(d: *mut Dns) {
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_tcp_create() -> *mut DnsTcp {
+    let parser = DnsTcp::default();
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_tcp_create_with_options(max_records: usize) -> *mut DnsTcp {
+    let parser = DnsTcp::new(max_records);
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_tcp_destroy(d: *mut DnsTcp) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_mdns_create() -> *mut Mdns {
+    let parser = Mdns::default();
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_mdns_create_with_options(
+    max_records: usize,
+    partial_messages: bool,
+) -> *mut Mdns {
+    let parser = Mdns::new(max_records, partial_messages);
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_mdns_destroy(d: *mut Mdns) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_llmnr_create() -> *mut Llmnr {
+    let parser = Llmnr::default();
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_llmnr_create_with_options(
+    max_records: usize,
+    partial_messages: bool,
+) -> *mut Llmnr {
+    let parser = Llmnr::new(max_records, partial_messages);
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_llmnr_destroy(d: *mut Llmnr) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_nbns_create() -> *mut Nbns {
+    let parser = Nbns {};
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_nbns_destroy(d: *mut Nbns) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
 /// # Safety
 /// function will panic if called with null
 #[no_mangle]
@@ -34,8 +134,79 @@ pub unsafe extern "C" fn sawp_dns_parse(
     data: *const u8,
     length: usize,
 ) -> *mut ParseResult {
+    parse(&*parser, direction, data, length)
+}
+
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_tcp_parse(
+    parser: *const DnsTcp,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
+) -> *mut ParseResult {
+    parse(&*parser, direction, data, length)
+}
+
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_mdns_parse(
+    parser: *const Mdns,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
+) -> *mut ParseResult {
+    parse(&*parser, direction, data, length)
+}
+
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_llmnr_parse(
+    parser: *const Llmnr,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
+) -> *mut ParseResult {
+    parse(&*parser, direction, data, length)
+}
+
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_nbns_parse(
+    parser: *const Nbns,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
+) -> *mut NbnsParseResult {
     let input = std::slice::from_raw_parts(data, length);
     match (*parser).parse(input, direction) {
+        Ok((sl, message)) => NbnsParseResult {
+            message: message.into_ffi_ptr(),
+            size_read: length - sl.len(),
+            error: std::ptr::null_mut(),
+        }
+        .into_ffi_ptr(),
+        Err(e) => NbnsParseResult {
+            message: std::ptr::null_mut(),
+            size_read: 0,
+            error: e.into_ffi_ptr(),
+        }
+        .into_ffi_ptr(),
+    }
+}
+
+unsafe fn parse<'a, P: Parse<'a, Message = Message>>(
+    parser: &P,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
+) -> *mut ParseResult {
+    let input = std::slice::from_raw_parts(data, length);
+    match parser.parse(input, direction) {
         Ok((sl, message)) => ParseResult {
             message: message.into_ffi_ptr(),
             size_read: length - sl.len(),
@@ -72,6 +243,27 @@ pub unsafe extern "C" fn sawp_dns_parse_result_destroy(d: *mut ParseResult) {
     }
 }
 
+impl Drop for NbnsParseResult {
+    fn drop(&mut self) {
+        unsafe {
+            sawp_dns_nbns_message_destroy(self.message);
+            if !self.error.is_null() {
+                Box::from_raw(self.error);
+            }
+        }
+    }
+}
+
+/// Free NbnsParseResult
+/// Will also destroy contained message and error
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_nbns_parse_result_destroy(d: *mut NbnsParseResult) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn sawp_dns_message_destroy(d: *mut Message) {
     if !d.is_null() {
@@ -79,3 +271,93 @@ pub unsafe extern "C" fn sawp_dns_message_destroy(d: *mut Message) {
         Box::from_raw(d);
     }
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_nbns_message_destroy(d: *mut NbnsMessage) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
+/// Start following a zone transfer from its query or first response.
+/// Returns null if the message does not ask for a zone transfer.
+///
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_zone_transfer_create(
+    message: *const Message,
+) -> *mut ZoneTransfer {
+    ZoneTransfer::new(&*message).into_ffi_ptr()
+}
+
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_zone_transfer_add_message(
+    transfer: *mut ZoneTransfer,
+    message: *const Message,
+) {
+    (*transfer).add_message(&*message);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_zone_transfer_destroy(d: *mut ZoneTransfer) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
+/// Key for verifying TSIG signed messages. `name` is the dotted key name and
+/// `secret` the decoded key material.
+///
+/// # Safety
+/// function will panic if called with null
+#[cfg(feature = "tsig")]
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_tsig_key_create(
+    name: *const u8,
+    name_length: usize,
+    algorithm: TsigAlgorithm,
+    secret: *const u8,
+    secret_length: usize,
+) -> *mut TsigKey {
+    let name = std::slice::from_raw_parts(name, name_length);
+    let secret = std::slice::from_raw_parts(secret, secret_length);
+    TsigKey::new(name, algorithm, secret).into_ffi_ptr()
+}
+
+/// Verify the TSIG record of a raw message. `request_mac` may be null when
+/// verifying a request.
+///
+/// # Safety
+/// function will panic if called with null key or data
+#[cfg(feature = "tsig")]
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_tsig_verify(
+    key: *const TsigKey,
+    data: *const u8,
+    length: usize,
+    now: u64,
+    request_mac: *const u8,
+    request_mac_length: usize,
+) -> TsigStatus {
+    let input = std::slice::from_raw_parts(data, length);
+    let request_mac = if request_mac.is_null() {
+        None
+    } else {
+        Some(std::slice::from_raw_parts(request_mac, request_mac_length))
+    };
+    (*key).verify(input, now, request_mac)
+}
+
+#[cfg(feature = "tsig")]
+#[no_mangle]
+pub unsafe extern "C" fn sawp_dns_tsig_key_destroy(d: *mut TsigKey) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
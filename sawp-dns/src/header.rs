@@ -12,6 +12,9 @@ use crate::ErrorFlags;
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 /// Masks for extracting DNS header flags
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, BitFlags)]
@@ -32,6 +35,7 @@ pub enum header_masks {
 /// A parsed DNS header
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Header {
     /// Transaction ID
@@ -73,6 +77,17 @@ pub struct Header {
 }
 
 impl Header {
+    /// LLMNR conflict bit, sent in place of the authoritative answer bit.
+    /// See https://tools.ietf.org/html/rfc4795#section-2.1.1
+    pub fn conflict(&self) -> bool {
+        self.authoritative
+    }
+
+    /// LLMNR tentative bit, sent in place of the recursion desired bit.
+    pub fn tentative(&self) -> bool {
+        self.recursion_desired
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn parse(input: &[u8]) -> Result<(&[u8], (Header, Flags<ErrorFlags>))> {
         let mut error_flags = ErrorFlags::none();
@@ -85,7 +100,7 @@ impl Header {
         } else {
             QueryResponse::Query
         };
-        let opcode: OpCode = OpCode::from_raw((wrapped_flags & header_masks::OPCODE).bits() >> 10);
+        let opcode: OpCode = OpCode::from_raw((wrapped_flags & header_masks::OPCODE).bits() >> 11);
         if opcode == OpCode::UNKNOWN {
             error_flags |= ErrorFlags::UnknownOpcode;
         }
@@ -183,7 +198,7 @@ mod test {
         case::parse_header_bad_opcode(
             & [
                 0x31, 0x21, // Transaction ID: 0x3121
-                0xb1, 0x00, // Flags: invalid opcode, recursion desired, authenticated data, format error
+                0xb9, 0x00, // Flags: response, invalid opcode, recursion desired
                 0x00, 0x01, // QDCOUNT: 1
                 0x00, 0x01, // ANCOUNT: 1
                 0x00, 0x00, // NSCOUNT: 0
@@ -193,7 +208,7 @@ mod test {
                 b"".as_ref(),
                 (Header {
                     transaction_id: 0x3121,
-                    flags: 0b1011_0001_0000_0000,
+                    flags: 0b1011_1001_0000_0000,
                     query_response: QueryResponse::Response,
                     opcode: OpCode::UNKNOWN,
                     authoritative: false,
@@ -212,6 +227,38 @@ mod test {
                 ErrorFlags::UnknownOpcode.into())
             ))
         ),
+        case::parse_header_update(
+            & [
+                0x1d, 0x2a, // Transaction ID: 0x1d2a
+                0x28, 0x00, // Flags: UPDATE
+                0x00, 0x01, // ZOCOUNT: 1
+                0x00, 0x01, // PRCOUNT: 1
+                0x00, 0x03, // UPCOUNT: 3
+                0x00, 0x00, // ADCOUNT: 0
+            ],
+            Ok((
+                b"".as_ref(),
+                (Header {
+                    transaction_id: 0x1d2a,
+                    flags: 0b0010_1000_0000_0000,
+                    query_response: QueryResponse::Query,
+                    opcode: OpCode::UPDATE,
+                    authoritative: false,
+                    truncated: false,
+                    recursion_desired: false,
+                    recursion_available: false,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NOERROR,
+                    qdcount: 1,
+                    ancount: 1,
+                    nscount: 3,
+                    arcount: 0,
+                },
+                ErrorFlags::none())
+            ))
+        ),
         case::parse_header_bad_rcode(
             & [
                 0x31, 0x21, // Transaction ID: 0x3121
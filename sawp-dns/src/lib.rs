@@ -3,8 +3,19 @@
 //! inform the caller about what went wrong if no message is returned (see [`sawp::parser::Parse`]
 //! for details on possible return types).
 //!
+//! [`Dns`] parses bare messages as carried over UDP. Use [`DnsTcp`] for DNS over
+//! TCP, where each message is preceded by a two byte length. Set
+//! [`Dns::partial_messages`] when each input is a whole datagram to keep the
+//! records of truncated responses. [`Mdns`] and
+//! [`Llmnr`] parse the multicast variants of the format and [`nbns::Nbns`] the
+//! NetBIOS Name Service. [`doh`] extracts messages from DNS over HTTPS
+//! requests and responses. With the `tsig` feature, [`tsig::TsigKey`] verifies
+//! TSIG signed messages and, with the `std` feature, [`tunnel::TunnelDetector`]
+//! scores queries for signs of tunneling.
+//!
 //! The following protocol references were used to create this module:
 //!
+//! [RFC1002](https://tools.ietf.org/html/rfc1002)
 //! [RFC1035](https://tools.ietf.org/html/rfc1035)
 //! [RFC1123](https://tools.ietf.org/html/rfc1123)
 //! [RFC2065](https://tools.ietf.org/html/rfc2065)
@@ -12,26 +23,33 @@
 //! [RFC2535](https://tools.ietf.org/html/rfc2535)
 //! [RFC2845](https://tools.ietf.org/html/rfc2845)
 //! [RFC2930](https://tools.ietf.org/html/rfc2930)
+//! [RFC3492](https://tools.ietf.org/html/rfc3492)
 //! [RFC3655](https://tools.ietf.org/html/rfc3655)
 //! [RFC4255](https://tools.ietf.org/html/rfc4255)
+//! [RFC4343](https://tools.ietf.org/html/rfc4343)
 //! [RFC4408](https://tools.ietf.org/html/rfc4408)
 //! [RFC4635](https://tools.ietf.org/html/rfc4635)
+//! [RFC4795](https://tools.ietf.org/html/rfc4795)
 //! [RFC5001](https://tools.ietf.org/html/rfc5001)
 //! [RFC6742](https://tools.ietf.org/html/rfc6742)
+//! [RFC6762](https://tools.ietf.org/html/rfc6762)
 //! [RFC6891](https://tools.ietf.org/html/rfc6891)
 //! [RFC6975](https://tools.ietf.org/html/rfc6975)
 //! [RFC7314](https://tools.ietf.org/html/rfc7314)
 //! [RFC7828](https://tools.ietf.org/html/rfc7828)
+//! [RFC7766](https://tools.ietf.org/html/rfc7766)
 //! [RFC7830](https://tools.ietf.org/html/rfc7830)
 //! [RFC7871](https://tools.ietf.org/html/rfc7871)
 //! [RFC7873](https://tools.ietf.org/html/rfc7873)
 //! [RFC7901](https://tools.ietf.org/html/rfc7901)
 //! [RFC8145](https://tools.ietf.org/html/rfc8145)
+//! [RFC8484](https://tools.ietf.org/html/rfc8484)
 //! [RFC8764](https://tools.ietf.org/html/rfc8764)
+//! [RFC8945](https://tools.ietf.org/html/rfc8945)
 //! [RFC8914](https://tools.ietf.org/html/rfc8914)
+//! [RFC9460](https://tools.ietf.org/html/rfc9460)
 //! [Cisco - Identifying DNS Traffic](https://docs.umbrella.com/umbrella-api/docs/identifying-dns-traffic2)
 //! [Draft DNSOP Zone Digest](https://tools.ietf.org/html/draft-ietf-dnsop-dns-zone-digest-14)
-//! [Draft DNSOP SVCB](https://datatracker.ietf.org/doc/draft-ietf-dnsop-svcb-https/00)
 //! [Draft EDNS Tags](https://datatracker.ietf.org/doc/draft-bellis-dnsop-edns-tags/)
 //! [Eastlake Kitchen Sink](https://tools.ietf.org/html/draft-eastlake-kitchen-sink)
 //! [NIMROD DNS](https://tools.ietf.org/html/draft-ietf-nimrod-dns-00)
@@ -46,7 +64,7 @@
 //! use sawp_dns::{Dns, Message};
 //!
 //! fn parse_bytes(input: &[u8]) -> std::result::Result<&[u8], Error> {
-//!     let dns = Dns {};
+//!     let dns = Dns::default();
 //!     let mut bytes = input;
 //!     while bytes.len() > 0 {
 //!         // If we know that this is a request or response, change the Direction
@@ -72,7 +90,13 @@
 //! }
 //! ```
 
-use sawp::error::Result;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{vec, vec::Vec};
+
+use sawp::error::{Error, ErrorKind, Result};
 use sawp::parser::{Direction, Parse};
 use sawp::probe::Probe;
 use sawp::protocol::Protocol;
@@ -85,9 +109,14 @@ mod ffi;
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 pub mod answer;
 use answer::*;
 
+pub mod doh;
+
 pub mod edns;
 
 pub mod enums;
@@ -99,44 +128,81 @@ use header::*;
 pub mod name;
 use name::*;
 
+pub mod nbns;
+
 pub mod question;
 use question::*;
 
 pub mod rdata;
 
-use nom::error::ErrorKind;
+pub mod svcb;
+
+#[cfg(feature = "tsig")]
+pub mod tsig;
+
+#[cfg(feature = "std")]
+pub mod tunnel;
+
+pub mod update;
+
+pub mod xfr;
 
 // This is a helper type for the module since the input will always be
 // &'a [u8] and the error will always be (&'a [i8], nom::error::Errorkind)
 type IResult<'a, O> = nom::IResult<&'a [u8], O, sawp::error::NomError<&'a [u8]>>;
 
-/// Future: replace with nom's many0 when we migrate to a version with FnMut combinators.
-fn custom_many0<O, F>(mut func: F) -> impl FnMut(&[u8]) -> IResult<Vec<O>>
+/// Records of a message section
+struct Section<T> {
+    records: Vec<T>,
+    error_flags: Flags<ErrorFlags>,
+    /// The input ended before the record count
+    truncated: bool,
+}
+
+/// Parse `count` records with `parse_record`, keeping the first `max_records` and
+/// skipping the others with `skip_record`. If the input ends first and `partial`
+/// is set, the records parsed so far are returned with
+/// [`ErrorFlags::TruncatedSection`] and no input remains.
+fn parse_section<'a, T, P, S>(
+    mut input: &'a [u8],
+    count: u16,
+    max_records: usize,
+    partial: bool,
+    mut parse_record: P,
+    skip_record: S,
+) -> Result<(&'a [u8], Section<T>)>
 where
-    F: FnMut(&[u8]) -> IResult<O>,
+    P: FnMut(&'a [u8]) -> IResult<'a, (T, Flags<ErrorFlags>)>,
+    S: Fn(&'a [u8]) -> IResult<'a, ()>,
 {
-    move |mut input| {
-        // We don't expect more than one EDNS option usually. Since this fn is exclusively used there for now let's keep this small.
-        let mut acc = Vec::with_capacity(1);
-        loop {
-            match func(input) {
-                Err(nom::Err::Error((_, nom::error::ErrorKind::LengthValue))) => {
-                    return Ok((input, acc))
-                }
-                Ok((rem, out)) => {
-                    if rem == input {
-                        return Err(nom::Err::Error((input, ErrorKind::Many0)));
-                    }
-
-                    input = rem;
-                    acc.push(out);
-                }
-                Err(e) => {
-                    return Err(e);
-                }
+    let count = usize::from(count);
+    let mut section = Section {
+        records: Vec::with_capacity(core::cmp::min(count, max_records)),
+        error_flags: ErrorFlags::none(),
+        truncated: false,
+    };
+    for index in 0..count {
+        let result = if index < max_records {
+            parse_record(input).map(|(rem, (record, error_flags))| {
+                section.records.push(record);
+                section.error_flags |= error_flags;
+                rem
+            })
+        } else {
+            section.error_flags |= ErrorFlags::RecordLimitExceeded;
+            skip_record(input).map(|(rem, _)| rem)
+        };
+        match result {
+            Ok(rem) => input = rem,
+            Err(nom::Err::Incomplete(_)) if partial => {
+                section.error_flags |= ErrorFlags::TruncatedSection;
+                section.truncated = true;
+                return Ok((&input[input.len()..], section));
             }
+            Err(err) => return Err(err.into()),
         }
     }
+    Ok((input, section))
 }
 
 /// Future: replace with nom's count when we migrate to a version with FnMut combinators.
@@ -172,7 +238,7 @@ where
 
 /// Error flags raised while parsing DNS - to be used in the returned Message
 #[allow(clippy::upper_case_acronyms)]
-#[repr(u16)]
+#[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq, BitFlags)]
 pub enum ErrorFlags {
     /// more than one pseudo-RR exists - EDNS specs limit OPT RRs to <=1
@@ -193,11 +259,28 @@ pub enum ErrorFlags {
     DnsNameExceedsMaxLen = 0b0000_0000_1000_0000,
     /// a ptr either points to an invalid location or is self-referential
     DnsNameInvalidCompression = 0b0000_0001_0000_0000,
+    /// an NSEC or NSEC3 type bitmap window is malformed or out of order
+    InvalidTypeBitmap = 0b0000_0010_0000_0000,
+    /// an SVCB or HTTPS parameter is truncated, malformed or invalid
+    SvcParamMalformed = 0b0000_0100_0000_0000,
+    /// SVCB or HTTPS parameter keys are not in strictly increasing order
+    SvcParamKeysUnsorted = 0b0000_1000_0000_0000,
+    /// an EDNS option is truncated or its payload is malformed
+    EdnsOptionMalformed = 0b0001_0000_0000_0000,
+    /// a DNS over TCP message is shorter than its length prefix
+    TcpLengthMismatch = 0b0010_0000_0000_0000,
+    /// a NetBIOS name is not a valid first-level encoded name
+    InvalidNetbiosName = 0b0100_0000_0000_0000,
+    /// a section ends before its record count, the records parsed so far are kept
+    TruncatedSection = 0b1000_0000_0000_0000,
+    /// a section has more records than the parser keeps, the others are skipped
+    RecordLimitExceeded = 0b0001_0000_0000_0000_0000,
 }
 
 /// Breakdown of the parsed dns bytes
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Message {
     pub header: Header,
@@ -205,14 +288,41 @@ pub struct Message {
     pub answers: Vec<Answer>,
     pub nameservers: Vec<Answer>,
     pub additional: Vec<Answer>,
-    #[cfg_attr(feature = "ffi", sawp_ffi(flag = "u16"))]
+    #[cfg_attr(feature = "ffi", sawp_ffi(flag = "u32"))]
     pub error_flags: Flags<ErrorFlags>,
 }
 
 impl Message {}
 
+/// Records kept per section by default, more than a 65535 byte message of
+/// distinct names can hold
+pub const DEFAULT_MAX_RECORDS: usize = 4096;
+
 #[derive(Debug)]
-pub struct Dns {}
+pub struct Dns {
+    /// Records parsed per section. Further records are skipped and flagged
+    /// with [`ErrorFlags::RecordLimitExceeded`].
+    pub max_records: usize,
+    /// Treat the input as a whole message, such as a UDP datagram. A section
+    /// ending early then gives the records parsed so far flagged with
+    /// [`ErrorFlags::TruncatedSection`] instead of ErrorKind::Incomplete.
+    pub partial_messages: bool,
+}
+
+impl Dns {
+    pub fn new(max_records: usize, partial_messages: bool) -> Self {
+        Dns {
+            max_records,
+            partial_messages,
+        }
+    }
+}
+
+impl Default for Dns {
+    fn default() -> Self {
+        Dns::new(DEFAULT_MAX_RECORDS, false)
+    }
+}
 
 impl<'a> Protocol<'a> for Dns {
     type Message = Message;
@@ -227,6 +337,10 @@ impl<'a> Probe<'a> for Dns {}
 /// Returns ErrorKind::Incomplete if more data is needed.
 /// If part of the message was parsed successfully will attempt to return a partial message
 /// with an appropriate error_flags field indicating what went wrong.
+///
+/// With [`Dns::partial_messages`], only an incomplete header returns ErrorKind::Incomplete.
+/// A section ending before its record count gives the records parsed so far: the header
+/// counts hold the counts of the message and the section lengths the counts actually parsed.
 impl<'a> Parse<'a> for Dns {
     fn parse(
         &self,
@@ -264,43 +378,288 @@ impl<'a> Parse<'a> for Dns {
         message.header = header;
         message.error_flags |= error_flags;
 
-        let (input, (questions, error_flags)) =
-            Question::parse_questions(input, reference_bytes, message.header.qdcount.into())?;
-        message.queries = questions;
-        message.error_flags |= error_flags;
+        let (input, questions) = parse_section(
+            input,
+            message.header.qdcount,
+            self.max_records,
+            self.partial_messages,
+            |input| Question::parse(input, reference_bytes),
+            Question::skip,
+        )?;
+        message.queries = questions.records;
+        message.error_flags |= questions.error_flags;
+        if questions.truncated {
+            return Ok((input, Some(message)));
+        }
 
-        let (input, (answers, error_flags)) =
-            Answer::parse_answers(input, reference_bytes, message.header.ancount.into())?;
-        message.answers = answers;
-        message.error_flags |= error_flags;
+        let (input, answers) = parse_section(
+            input,
+            message.header.ancount,
+            self.max_records,
+            self.partial_messages,
+            |input| Answer::parse(input, reference_bytes),
+            Answer::skip,
+        )?;
+        message.answers = answers.records;
+        message.error_flags |= answers.error_flags;
+        if answers.truncated {
+            return Ok((input, Some(message)));
+        }
 
-        let (input, (nameservers, error_flags)) =
-            Answer::parse_answers(input, reference_bytes, message.header.nscount.into())?;
-        message.nameservers = nameservers;
-        message.error_flags |= error_flags;
+        let (input, nameservers) = parse_section(
+            input,
+            message.header.nscount,
+            self.max_records,
+            self.partial_messages,
+            |input| Answer::parse(input, reference_bytes),
+            Answer::skip,
+        )?;
+        message.nameservers = nameservers.records;
+        message.error_flags |= nameservers.error_flags;
+        if nameservers.truncated {
+            return Ok((input, Some(message)));
+        }
 
-        let (input, (additionals, error_flags)) =
-            Answer::parse_additionals(input, reference_bytes, message.header.arcount.into())?;
-        message.additional = additionals;
-        message.error_flags |= error_flags;
+        let mut opt_rr_present = false;
+        let (input, additional) = parse_section(
+            input,
+            message.header.arcount,
+            self.max_records,
+            self.partial_messages,
+            |input| {
+                let (input, (answer, mut error_flags, inner_opt_rr_present)) =
+                    Answer::parse_additional(input, reference_bytes)?;
+                if inner_opt_rr_present {
+                    if opt_rr_present {
+                        error_flags |= ErrorFlags::ExtraOptRr;
+                    }
+                    opt_rr_present = true;
+                }
+                Ok((input, (answer, error_flags)))
+            },
+            Answer::skip,
+        )?;
+        message.additional = additional.records;
+        message.error_flags |= additional.error_flags;
 
         Ok((input, Some(message)))
     }
 }
 
+/// Length of the prefix preceding each DNS over TCP message
+const TCP_LENGTH_LEN: usize = 2;
+
+/// DNS over TCP, where each message is preceded by its length as a two byte integer.
+/// See https://tools.ietf.org/html/rfc7766#section-8
+///
+/// Several messages may share a segment; the remaining input starts at the next length prefix.
+/// Each frame holds a whole message, so it is always parsed as with [`Dns::partial_messages`].
+#[derive(Debug)]
+pub struct DnsTcp {
+    /// Records parsed per section, see [`Dns::max_records`].
+    pub max_records: usize,
+}
+
+impl DnsTcp {
+    pub fn new(max_records: usize) -> Self {
+        DnsTcp { max_records }
+    }
+}
+
+impl Default for DnsTcp {
+    fn default() -> Self {
+        DnsTcp::new(DEFAULT_MAX_RECORDS)
+    }
+}
+
+impl<'a> Protocol<'a> for DnsTcp {
+    type Message = Message;
+
+    fn name() -> &'static str {
+        "dns-tcp"
+    }
+}
+
+impl<'a> Probe<'a> for DnsTcp {}
+
+/// Returns ErrorKind::Incomplete with the number of missing bytes until the
+/// length prefix and the whole message are available.
+/// Returns ErrorKind::InvalidData if the header does not fit in its length prefix
+/// and flags [`ErrorFlags::TruncatedSection`] if the records do not.
+impl<'a> Parse<'a> for DnsTcp {
+    fn parse(
+        &self,
+        input: &'a [u8],
+        direction: Direction,
+    ) -> Result<(&'a [u8], Option<Self::Message>)> {
+        if input.len() < TCP_LENGTH_LEN {
+            return Err(Error::incomplete_needed(TCP_LENGTH_LEN - input.len()));
+        }
+        let (length, input) = input.split_at(TCP_LENGTH_LEN);
+        let length = usize::from(u16::from_be_bytes([length[0], length[1]]));
+        if input.len() < length {
+            return Err(Error::incomplete_needed(length - input.len()));
+        }
+        let (frame, input) = input.split_at(length);
+
+        let dns = Dns::new(self.max_records, true);
+        match dns.parse(frame, direction) {
+            Ok((trailing, message)) => Ok((
+                input,
+                message.map(|mut message| {
+                    if !trailing.is_empty() {
+                        message.error_flags |= ErrorFlags::TcpLengthMismatch;
+                    }
+                    message
+                }),
+            )),
+            Err(Error {
+                kind: ErrorKind::Incomplete(_),
+            }) => Err(Error::new(ErrorKind::InvalidData)),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Bit of the class field used by mDNS for the unicast-response bit of
+/// questions and the cache-flush bit of records.
+/// See https://tools.ietf.org/html/rfc6762#section-18.12
+pub const MDNS_CLASS_FLAG: u16 = 0b1000_0000_0000_0000;
+
+/// Multicast DNS, which uses the top bit of the class field of questions and
+/// records. Record classes are decoded without that bit, see
+/// [`Question::unicast_response`] and [`Answer::cache_flush`].
+#[derive(Debug)]
+pub struct Mdns {
+    /// Records parsed per section, see [`Dns::max_records`].
+    pub max_records: usize,
+    /// Treat the input as a whole datagram, see [`Dns::partial_messages`].
+    pub partial_messages: bool,
+}
+
+impl Mdns {
+    pub fn new(max_records: usize, partial_messages: bool) -> Self {
+        Mdns {
+            max_records,
+            partial_messages,
+        }
+    }
+}
+
+impl Default for Mdns {
+    fn default() -> Self {
+        Mdns::new(DEFAULT_MAX_RECORDS, false)
+    }
+}
+
+impl<'a> Protocol<'a> for Mdns {
+    type Message = Message;
+
+    fn name() -> &'static str {
+        "mdns"
+    }
+}
+
+impl<'a> Probe<'a> for Mdns {}
+
+impl<'a> Parse<'a> for Mdns {
+    fn parse(
+        &self,
+        input: &'a [u8],
+        direction: Direction,
+    ) -> Result<(&'a [u8], Option<Self::Message>)> {
+        let dns = Dns::new(self.max_records, self.partial_messages);
+        let (input, message) = dns.parse(input, direction)?;
+        Ok((
+            input,
+            message.map(|mut message| {
+                let mut unknown_rclass = false;
+                for question in &mut message.queries {
+                    question.record_class =
+                        RecordClass::from_raw(question.record_class_raw & !MDNS_CLASS_FLAG);
+                    unknown_rclass |= question.record_class == RecordClass::UNKNOWN;
+                }
+                for answer in message
+                    .answers
+                    .iter_mut()
+                    .chain(message.nameservers.iter_mut())
+                    .chain(message.additional.iter_mut())
+                    .filter(|answer| answer.rtype != RecordType::OPT)
+                {
+                    answer.rclass = RecordClass::from_raw(answer.rclass_raw & !MDNS_CLASS_FLAG);
+                    unknown_rclass |= answer.rclass == RecordClass::UNKNOWN;
+                }
+                if !unknown_rclass {
+                    message.error_flags &= !Flags::from(ErrorFlags::UnknownRclass);
+                }
+                message
+            }),
+        ))
+    }
+}
+
+/// Link-Local Multicast Name Resolution, which shares the DNS format. The
+/// conflict and tentative bits replace the authoritative answer and recursion
+/// desired bits, see [`Header::conflict`] and [`Header::tentative`].
+#[derive(Debug)]
+pub struct Llmnr {
+    /// Records parsed per section, see [`Dns::max_records`].
+    pub max_records: usize,
+    /// Treat the input as a whole datagram, see [`Dns::partial_messages`].
+    pub partial_messages: bool,
+}
+
+impl Llmnr {
+    pub fn new(max_records: usize, partial_messages: bool) -> Self {
+        Llmnr {
+            max_records,
+            partial_messages,
+        }
+    }
+}
+
+impl Default for Llmnr {
+    fn default() -> Self {
+        Llmnr::new(DEFAULT_MAX_RECORDS, false)
+    }
+}
+
+impl<'a> Protocol<'a> for Llmnr {
+    type Message = Message;
+
+    fn name() -> &'static str {
+        "llmnr"
+    }
+}
+
+impl<'a> Probe<'a> for Llmnr {}
+
+impl<'a> Parse<'a> for Llmnr {
+    fn parse(
+        &self,
+        input: &'a [u8],
+        direction: Direction,
+    ) -> Result<(&'a [u8], Option<Self::Message>)> {
+        let dns = Dns::new(self.max_records, self.partial_messages);
+        dns.parse(input, direction)
+    }
+}
+
 #[cfg(test)]
 mod test {
     #![allow(clippy::type_complexity)]
+    use crate::edns::*;
     use crate::enums::*;
     use crate::rdata::*;
+    use crate::svcb::*;
     use crate::{
-        Answer, Dns, ErrorFlags, Header, Message, OpCode, Parse, QueryResponse, Question,
-        RecordClass, RecordType, ResponseCode,
+        Answer, Dns, DnsTcp, ErrorFlags, Header, Llmnr, Mdns, Message, OpCode, Parse,
+        QueryResponse, Question, RecordClass, RecordType, ResponseCode, DEFAULT_MAX_RECORDS,
     };
     use rstest::rstest;
-    use sawp::error::{Error, Result};
+    use sawp::error::{Error, ErrorKind, Result};
     use sawp::parser::Direction;
-    use sawp_flags::Flag;
+    use sawp_flags::{Flag, Flags};
 
     #[rstest(
     input,
@@ -2013,9 +2372,9 @@ mod test {
             0x00, // Higher bits in extended RCode: 0x00
             0x00, // EDNS0 version: 0
             0x00, 0x00, // Z: 0
-            0x00, 0x10, // Data length: 10
+            0x00, 0x10, // Data length: 16
         ],
-        Err(Error::incomplete_needed(2))
+        Err(Error::incomplete_needed(16))
     ),
     case::parse_srv_response(
         &[
@@ -2631,13 +2990,1118 @@ mod test {
             }
         )))
     ),
-    )]
-    fn dns(input: &[u8], expected: Result<(usize, Option<Message>)>) {
-        let dns = Dns {};
-        assert_eq!(
-            dns.parse(input, Direction::Unknown)
-                .map(|(rem, msg)| (rem.len(), msg)),
-            expected
+    case::parse_dnskey_response(
+        &[
+            0x2c, 0x4a, // transaction id: 0x2c4a
+            0x81, 0xa0, // flags: response, recursion desired, recursion available, authenticated
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x02, // ANCOUNT: 2
+            0x00, 0x00, // NSCOUNT: 0
+            0x00, 0x00, // ARCOUNT: 0
+            0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, // question: example.com
+            0x00, 0x30, // RType: DNSKEY
+            0x00, 0x01, // RClass: IN
+            0xc0, 0x0c, // answer: example.com
+            0x00, 0x30, // RType: DNSKEY
+            0x00, 0x01, // RClass: IN
+            0x00, 0x01, 0x51, 0x80, // TTL: 86400
+            0x00, 0x86, // data length: 134
+            0x01, 0x00, // flags: zone key
+            0x03, // protocol: 3
+            0x05, // algorithm: RSASHA1
+            0x01, 0x03, 0x9e, 0x8a, 0x24, 0x74, 0x18, 0xe3, 0x18, 0x90, 0x3b, 0x21, 0x5a, 0x84,
+            0x8a, 0xcf, 0xd5, 0xf3, 0x7f, 0x02, 0x6b, 0xd4, 0x06, 0x2d, 0xb2, 0x6c, 0x77, 0x4c,
+            0x69, 0x09, 0x68, 0xd5, 0xd5, 0x6d, 0xf8, 0xbf, 0xda, 0x91, 0xe6, 0xf3, 0x6d, 0x9a,
+            0x27, 0x98, 0x88, 0xf4, 0x13, 0x33, 0x35, 0x7c, 0x5e, 0x60, 0x29, 0x99, 0x0d, 0x10,
+            0xfd, 0xf5, 0x66, 0x30, 0x62, 0xa5, 0x12, 0x76, 0x33, 0x26, 0x98, 0x0a, 0x61, 0x5d,
+            0xdb, 0xf1, 0x7a, 0x05, 0xdd, 0xfc, 0xce, 0x7e, 0x5f, 0xb3, 0xab, 0xcc, 0xa0, 0x5a,
+            0x31, 0xb0, 0x95, 0x74, 0x52, 0xd4, 0x52, 0x1e, 0x83, 0x87, 0x07, 0x89, 0x06, 0x31,
+            0x15, 0xbf, 0x97, 0xf6, 0xc3, 0x08, 0xcc, 0xf5, 0x7c, 0xdc, 0x9c, 0xe7, 0xfe, 0x10,
+            0xf6, 0xed, 0x1b, 0xd0, 0xcc, 0x06, 0x60, 0x03, 0x8c, 0x50, 0xdc, 0xdb, 0x0f, 0xeb,
+            0x96, 0x3c, 0x2f, 0x17,
+            // public key
+            0xc0, 0x0c, // answer: example.com
+            0x00, 0x30, // RType: DNSKEY
+            0x00, 0x01, // RClass: IN
+            0x00, 0x01, 0x51, 0x80, // TTL: 86400
+            0x00, 0x44, // data length: 68
+            0x01, 0x01, // flags: zone key, secure entry point
+            0x03, // protocol: 3
+            0x0d, // algorithm: ECDSAP256SHA256
+            0x0b, 0x30, 0x55, 0x7a, 0x9f, 0xc4, 0xe9, 0x0e, 0x33, 0x58, 0x7d, 0xa2, 0xc7, 0xec,
+            0x11, 0x36, 0x5b, 0x80, 0xa5, 0xca, 0xef, 0x14, 0x39, 0x5e, 0x83, 0xa8, 0xcd, 0xf2,
+            0x17, 0x3c, 0x61, 0x86, 0xab, 0xd0, 0xf5, 0x1a, 0x3f, 0x64, 0x89, 0xae, 0xd3, 0xf8,
+            0x1d, 0x42, 0x67, 0x8c, 0xb1, 0xd6, 0xfb, 0x20, 0x45, 0x6a, 0x8f, 0xb4, 0xd9, 0xfe,
+            0x23, 0x48, 0x6d, 0x92, 0xb7, 0xdc, 0x01, 0x26,
+            // public key
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x2c4a,
+                    flags: 0b1000_0001_1010_0000,
+                    query_response: QueryResponse::Response,
+                    opcode: OpCode::QUERY,
+                    authoritative: false,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: true,
+                    zflag: false,
+                    authenticated_data: true,
+                    check_disabled: false,
+                    rcode: ResponseCode::NOERROR,
+                    qdcount: 1,
+                    ancount: 2,
+                    nscount: 0,
+                    arcount: 0
+                },
+                queries: vec![Question {
+                    name: "example.com".as_bytes().to_vec(),
+                    record_type: RecordType::DNSKEY,
+                    record_type_raw: 48,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![
+                    Answer {
+                        name: "example.com".as_bytes().to_vec(),
+                        rtype: RecordType::DNSKEY,
+                        rtype_raw: 48,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 86400,
+                        data: RDataType::DNSKEY(RDataDNSKEY {
+                            flags: 256,
+                            zone_key: true,
+                            revoked: false,
+                            secure_entry_point: false,
+                            protocol: 3,
+                            algorithm: DnssecAlgorithm::RSASHA1,
+                            public_key: vec![
+                                0x01, 0x03, 0x9e, 0x8a, 0x24, 0x74, 0x18, 0xe3, 0x18, 0x90, 0x3b, 0x21, 0x5a, 0x84,
+                                0x8a, 0xcf, 0xd5, 0xf3, 0x7f, 0x02, 0x6b, 0xd4, 0x06, 0x2d, 0xb2, 0x6c, 0x77, 0x4c,
+                                0x69, 0x09, 0x68, 0xd5, 0xd5, 0x6d, 0xf8, 0xbf, 0xda, 0x91, 0xe6, 0xf3, 0x6d, 0x9a,
+                                0x27, 0x98, 0x88, 0xf4, 0x13, 0x33, 0x35, 0x7c, 0x5e, 0x60, 0x29, 0x99, 0x0d, 0x10,
+                                0xfd, 0xf5, 0x66, 0x30, 0x62, 0xa5, 0x12, 0x76, 0x33, 0x26, 0x98, 0x0a, 0x61, 0x5d,
+                                0xdb, 0xf1, 0x7a, 0x05, 0xdd, 0xfc, 0xce, 0x7e, 0x5f, 0xb3, 0xab, 0xcc, 0xa0, 0x5a,
+                                0x31, 0xb0, 0x95, 0x74, 0x52, 0xd4, 0x52, 0x1e, 0x83, 0x87, 0x07, 0x89, 0x06, 0x31,
+                                0x15, 0xbf, 0x97, 0xf6, 0xc3, 0x08, 0xcc, 0xf5, 0x7c, 0xdc, 0x9c, 0xe7, 0xfe, 0x10,
+                                0xf6, 0xed, 0x1b, 0xd0, 0xcc, 0x06, 0x60, 0x03, 0x8c, 0x50, 0xdc, 0xdb, 0x0f, 0xeb,
+                                0x96, 0x3c, 0x2f, 0x17,
+                            ],
+                            key_tag: 60485,
+                        }),
+                    },
+                    Answer {
+                        name: "example.com".as_bytes().to_vec(),
+                        rtype: RecordType::DNSKEY,
+                        rtype_raw: 48,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 86400,
+                        data: RDataType::DNSKEY(RDataDNSKEY {
+                            flags: 257,
+                            zone_key: true,
+                            revoked: false,
+                            secure_entry_point: true,
+                            protocol: 3,
+                            algorithm: DnssecAlgorithm::ECDSAP256SHA256,
+                            public_key: vec![
+                                0x0b, 0x30, 0x55, 0x7a, 0x9f, 0xc4, 0xe9, 0x0e, 0x33, 0x58, 0x7d, 0xa2, 0xc7, 0xec,
+                                0x11, 0x36, 0x5b, 0x80, 0xa5, 0xca, 0xef, 0x14, 0x39, 0x5e, 0x83, 0xa8, 0xcd, 0xf2,
+                                0x17, 0x3c, 0x61, 0x86, 0xab, 0xd0, 0xf5, 0x1a, 0x3f, 0x64, 0x89, 0xae, 0xd3, 0xf8,
+                                0x1d, 0x42, 0x67, 0x8c, 0xb1, 0xd6, 0xfb, 0x20, 0x45, 0x6a, 0x8f, 0xb4, 0xd9, 0xfe,
+                                0x23, 0x48, 0x6d, 0x92, 0xb7, 0xdc, 0x01, 0x26,
+                            ],
+                            key_tag: 54397,
+                        }),
+                    }
+                ],
+                nameservers: vec![],
+                additional: vec![],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    case::parse_rrsig_response(
+        &[
+            0x5a, 0x1d, // transaction id: 0x5a1d
+            0x81, 0x80, // flags: response, recursion desired, recursion available
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x02, // ANCOUNT: 2
+            0x00, 0x00, // NSCOUNT: 0
+            0x00, 0x00, // ARCOUNT: 0
+            0x04, 0x68, 0x6f, 0x73, 0x74, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03,
+            0x63, 0x6f, 0x6d, 0x00,
+            // question: host.example.com
+            0x00, 0x01, // RType: A
+            0x00, 0x01, // RClass: IN
+            0xc0, 0x0c, // answer: host.example.com
+            0x00, 0x01, // RType: A
+            0x00, 0x01, // RClass: IN
+            0x00, 0x01, 0x51, 0x80, // TTL: 86400
+            0x00, 0x04, // data length: 4
+            0xc0, 0x00, 0x02, 0x01, // 192.0.2.1
+            0xc0, 0x0c, // answer: host.example.com
+            0x00, 0x2e, // RType: RRSIG
+            0x00, 0x01, // RClass: IN
+            0x00, 0x01, 0x51, 0x80, // TTL: 86400
+            0x00, 0x94, // data length: 148
+            0x00, 0x01, // type covered: A
+            0x05, // algorithm: RSASHA1
+            0x03, // labels: 3
+            0x00, 0x01, 0x51, 0x80, // original TTL: 86400
+            0x3e, 0x7c, 0x9d, 0xd7, // expiration: 20030322173103
+            0x3e, 0x55, 0x10, 0xd7, // inception: 20030220173103
+            0x0a, 0x52, // key tag: 2642
+            0xc0, 0x11, // signer name: example.com
+            0xa0, 0x90, 0x75, 0x5b, 0xa5, 0x8d, 0x1a, 0xff, 0xa5, 0x76, 0xf4, 0x37, 0x58, 0x31,
+            0xb4, 0x31, 0x09, 0x20, 0xe4, 0x81, 0x21, 0x8d, 0x18, 0xa9, 0xf1, 0x64, 0xeb, 0x3d,
+            0x81, 0xaf, 0xd3, 0xb8, 0x75, 0xd3, 0xc7, 0x54, 0x28, 0x63, 0x1e, 0x0c, 0xf2, 0xa2,
+            0x8d, 0x50, 0x87, 0x5f, 0x70, 0xc3, 0x29, 0xd7, 0xdb, 0xfa, 0xfe, 0xa8, 0x07, 0xdc,
+            0x1f, 0xba, 0x1d, 0xc3, 0x4c, 0x95, 0xd4, 0x01, 0xf2, 0x3f, 0x33, 0x4c, 0xe6, 0x3b,
+            0xfc, 0xf3, 0xf1, 0xb5, 0xb4, 0x47, 0x39, 0xe5, 0xf0, 0xed, 0xed, 0x18, 0xd6, 0xb3,
+            0x3f, 0x04, 0x0a, 0x91, 0x13, 0x76, 0xd1, 0x73, 0xd7, 0x57, 0xa9, 0xf0, 0xc1, 0xfa,
+            0x17, 0x98, 0x94, 0x1b, 0xb0, 0xb3, 0x6b, 0x2d, 0xf9, 0x06, 0x27, 0x90, 0xfa, 0x7f,
+            0x01, 0x66, 0xf2, 0x73, 0x7e, 0xea, 0x90, 0x73, 0x78, 0x34, 0x1f, 0xb1, 0x2d, 0xc0,
+            0xa7, 0x7a,
+            // signature
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x5a1d,
+                    flags: 0b1000_0001_1000_0000,
+                    query_response: QueryResponse::Response,
+                    opcode: OpCode::QUERY,
+                    authoritative: false,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: true,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NOERROR,
+                    qdcount: 1,
+                    ancount: 2,
+                    nscount: 0,
+                    arcount: 0
+                },
+                queries: vec![Question {
+                    name: "host.example.com".as_bytes().to_vec(),
+                    record_type: RecordType::A,
+                    record_type_raw: 1,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![
+                    Answer {
+                        name: "host.example.com".as_bytes().to_vec(),
+                        rtype: RecordType::A,
+                        rtype_raw: 1,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 86400,
+                        data: RDataType::A(vec![192, 0, 2, 1]),
+                    },
+                    Answer {
+                        name: "host.example.com".as_bytes().to_vec(),
+                        rtype: RecordType::RRSIG,
+                        rtype_raw: 46,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 86400,
+                        data: RDataType::RRSIG(RDataRRSIG {
+                            type_covered: RecordType::A,
+                            algorithm: DnssecAlgorithm::RSASHA1,
+                            labels: 3,
+                            original_ttl: 86400,
+                            expiration: 1048354263,
+                            inception: 1045762263,
+                            key_tag: 2642,
+                            signer_name: "example.com".as_bytes().to_vec(),
+                            signature: vec![
+                                0xa0, 0x90, 0x75, 0x5b, 0xa5, 0x8d, 0x1a, 0xff, 0xa5, 0x76, 0xf4, 0x37, 0x58, 0x31,
+                                0xb4, 0x31, 0x09, 0x20, 0xe4, 0x81, 0x21, 0x8d, 0x18, 0xa9, 0xf1, 0x64, 0xeb, 0x3d,
+                                0x81, 0xaf, 0xd3, 0xb8, 0x75, 0xd3, 0xc7, 0x54, 0x28, 0x63, 0x1e, 0x0c, 0xf2, 0xa2,
+                                0x8d, 0x50, 0x87, 0x5f, 0x70, 0xc3, 0x29, 0xd7, 0xdb, 0xfa, 0xfe, 0xa8, 0x07, 0xdc,
+                                0x1f, 0xba, 0x1d, 0xc3, 0x4c, 0x95, 0xd4, 0x01, 0xf2, 0x3f, 0x33, 0x4c, 0xe6, 0x3b,
+                                0xfc, 0xf3, 0xf1, 0xb5, 0xb4, 0x47, 0x39, 0xe5, 0xf0, 0xed, 0xed, 0x18, 0xd6, 0xb3,
+                                0x3f, 0x04, 0x0a, 0x91, 0x13, 0x76, 0xd1, 0x73, 0xd7, 0x57, 0xa9, 0xf0, 0xc1, 0xfa,
+                                0x17, 0x98, 0x94, 0x1b, 0xb0, 0xb3, 0x6b, 0x2d, 0xf9, 0x06, 0x27, 0x90, 0xfa, 0x7f,
+                                0x01, 0x66, 0xf2, 0x73, 0x7e, 0xea, 0x90, 0x73, 0x78, 0x34, 0x1f, 0xb1, 0x2d, 0xc0,
+                                0xa7, 0x7a,
+                            ],
+                        }),
+                    }
+                ],
+                nameservers: vec![],
+                additional: vec![],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    case::parse_ds_response(
+        &[
+            0x0b, 0x7e, // transaction id: 0x0b7e
+            0x81, 0x80, // flags: response, recursion desired, recursion available
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x02, // ANCOUNT: 2
+            0x00, 0x00, // NSCOUNT: 0
+            0x00, 0x00, // ARCOUNT: 0
+            0x05, 0x64, 0x73, 0x6b, 0x65, 0x79, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65,
+            0x03, 0x63, 0x6f, 0x6d, 0x00,
+            // question: dskey.example.com
+            0x00, 0xff, // RType: ANY
+            0x00, 0x01, // RClass: IN
+            0xc0, 0x0c, // answer: dskey.example.com
+            0x00, 0x2b, // RType: DS
+            0x00, 0x01, // RClass: IN
+            0x00, 0x01, 0x51, 0x80, // TTL: 86400
+            0x00, 0x18, // data length: 24
+            0xec, 0x45, // key tag: 60485
+            0x05, // algorithm: RSASHA1
+            0x01, // digest type: SHA1
+            0x2b, 0xb1, 0x83, 0xaf, 0x5f, 0x22, 0x58, 0x81, 0x79, 0xa5, 0x3b, 0x0a, 0x98, 0x63,
+            0x1f, 0xad, 0x1a, 0x29, 0x21, 0x18,
+            // digest: 2bb183af5f22588179a53b0a98631fad1a292118
+            0xc0, 0x0c, // answer: dskey.example.com
+            0x00, 0x3b, // RType: CDS
+            0x00, 0x01, // RClass: IN
+            0x00, 0x00, 0x0e, 0x10, // TTL: 3600
+            0x00, 0x05, // data length: 5
+            0x00, 0x00, // key tag: 0
+            0x00, // algorithm: DELETE
+            0x00, // digest type: 0
+            0x00, // digest: 00
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x0b7e,
+                    flags: 0b1000_0001_1000_0000,
+                    query_response: QueryResponse::Response,
+                    opcode: OpCode::QUERY,
+                    authoritative: false,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: true,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NOERROR,
+                    qdcount: 1,
+                    ancount: 2,
+                    nscount: 0,
+                    arcount: 0
+                },
+                queries: vec![Question {
+                    name: "dskey.example.com".as_bytes().to_vec(),
+                    record_type: RecordType::ANY,
+                    record_type_raw: 255,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![
+                    Answer {
+                        name: "dskey.example.com".as_bytes().to_vec(),
+                        rtype: RecordType::DS,
+                        rtype_raw: 43,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 86400,
+                        data: RDataType::DS(RDataDS {
+                            key_tag: 60485,
+                            algorithm: DnssecAlgorithm::RSASHA1,
+                            digest_type: DigestType::SHA1,
+                            digest: vec![
+                                0x2b, 0xb1, 0x83, 0xaf, 0x5f, 0x22, 0x58, 0x81, 0x79, 0xa5, 0x3b, 0x0a, 0x98, 0x63,
+                                0x1f, 0xad, 0x1a, 0x29, 0x21, 0x18,
+                            ],
+                        }),
+                    },
+                    Answer {
+                        name: "dskey.example.com".as_bytes().to_vec(),
+                        rtype: RecordType::CDS,
+                        rtype_raw: 59,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 3600,
+                        data: RDataType::CDS(RDataDS {
+                            key_tag: 0,
+                            algorithm: DnssecAlgorithm::DELETE,
+                            digest_type: DigestType::RESERVED,
+                            digest: vec![0x00],
+                        }),
+                    }
+                ],
+                nameservers: vec![],
+                additional: vec![],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    case::parse_nsec_response(
+        &[
+            0x71, 0xc3, // transaction id: 0x71c3
+            0x85, 0x83, // flags: response, authoritative, recursion desired, recursion available, no such name
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x00, // ANCOUNT: 0
+            0x00, 0x01, // NSCOUNT: 1
+            0x00, 0x00, // ARCOUNT: 0
+            0x05, 0x62, 0x72, 0x61, 0x76, 0x6f, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65,
+            0x03, 0x63, 0x6f, 0x6d, 0x00,
+            // question: bravo.example.com
+            0x00, 0x01, // RType: A
+            0x00, 0x01, // RClass: IN
+            0x04, 0x61, 0x6c, 0x66, 0x61, 0xc0, 0x12, // authority: alfa.example.com
+            0x00, 0x2f, // RType: NSEC
+            0x00, 0x01, // RClass: IN
+            0x00, 0x01, 0x51, 0x80, // TTL: 86400
+            0x00, 0x37, // data length: 55
+            0x04, 0x68, 0x6f, 0x73, 0x74, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03,
+            0x63, 0x6f, 0x6d, 0x00,
+            // next domain name: host.example.com
+            0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, // window 0: A, MX, RRSIG, NSEC
+            0x04, 0x1b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x20,
+            // window 4: TYPE1234
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x71c3,
+                    flags: 0b1000_0101_1000_0011,
+                    query_response: QueryResponse::Response,
+                    opcode: OpCode::QUERY,
+                    authoritative: true,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: true,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NAMEERROR,
+                    qdcount: 1,
+                    ancount: 0,
+                    nscount: 1,
+                    arcount: 0
+                },
+                queries: vec![Question {
+                    name: "bravo.example.com".as_bytes().to_vec(),
+                    record_type: RecordType::A,
+                    record_type_raw: 1,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![],
+                nameservers: vec![
+                    Answer {
+                        name: "alfa.example.com".as_bytes().to_vec(),
+                        rtype: RecordType::NSEC,
+                        rtype_raw: 47,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 86400,
+                        data: RDataType::NSEC(RDataNSEC {
+                            next_domain_name: "host.example.com".as_bytes().to_vec(),
+                            types: vec![
+                                RecordType::A,
+                                RecordType::MX,
+                                RecordType::RRSIG,
+                                RecordType::NSEC,
+                                RecordType::UNKNOWN,
+                            ],
+                            types_raw: vec![1, 15, 46, 47, 1234],
+                        }),
+                    }
+                ],
+                additional: vec![],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    case::parse_nsec3_response(
+        &[
+            0x3d, 0x05, // transaction id: 0x3d05
+            0x85, 0x03, // flags: response, authoritative, recursion desired, no such name
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x00, // ANCOUNT: 0
+            0x00, 0x01, // NSCOUNT: 1
+            0x00, 0x00, // ARCOUNT: 0
+            0x01, 0x61, 0x01, 0x63, 0x01, 0x78, 0x01, 0x77, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70,
+            0x6c, 0x65, 0x00,
+            // question: a.c.x.w.example
+            0x00, 0x01, // RType: A
+            0x00, 0x01, // RClass: IN
+            0x20, 0x30, 0x70, 0x39, 0x6d, 0x68, 0x61, 0x76, 0x65, 0x71, 0x76, 0x6d, 0x36, 0x74,
+            0x37, 0x76, 0x62, 0x6c, 0x35, 0x6c, 0x6f, 0x70, 0x32, 0x75, 0x33, 0x74, 0x32, 0x72,
+            0x70, 0x33, 0x74, 0x6f, 0x6d, 0xc0, 0x14,
+            // authority: 0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example
+            0x00, 0x32, // RType: NSEC3
+            0x00, 0x01, // RClass: IN
+            0x00, 0x00, 0x0e, 0x10, // TTL: 3600
+            0x00, 0x27, // data length: 39
+            0x01, // hash algorithm: SHA1
+            0x01, // flags: opt-out
+            0x00, 0x0c, // iterations: 12
+            0x04, // salt length: 4
+            0xaa, 0xbb, 0xcc, 0xdd, // salt: aabbccdd
+            0x14, // hash length: 20
+            0x17, 0x4e, 0xb2, 0x40, 0x9f, 0xe2, 0x8b, 0xcb, 0x48, 0x87, 0xa1, 0x83, 0x6f, 0x95,
+            0x7f, 0x0a, 0x84, 0x25, 0xe2, 0x7b,
+            // next hashed owner name: 2t7b4g4vsa5smi47k61mv5bv1a22bojr
+            0x00, 0x07, 0x22, 0x01, 0x00, 0x00, 0x00, 0x02, 0x90, // window 0: NS, SOA, MX, RRSIG, DNSKEY, NSEC3PARAM
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x3d05,
+                    flags: 0b1000_0101_0000_0011,
+                    query_response: QueryResponse::Response,
+                    opcode: OpCode::QUERY,
+                    authoritative: true,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: false,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NAMEERROR,
+                    qdcount: 1,
+                    ancount: 0,
+                    nscount: 1,
+                    arcount: 0
+                },
+                queries: vec![Question {
+                    name: "a.c.x.w.example".as_bytes().to_vec(),
+                    record_type: RecordType::A,
+                    record_type_raw: 1,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![],
+                nameservers: vec![
+                    Answer {
+                        name: "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example".as_bytes().to_vec(),
+                        rtype: RecordType::NSEC3,
+                        rtype_raw: 50,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 3600,
+                        data: RDataType::NSEC3(RDataNSEC3 {
+                            hash_algorithm: Nsec3HashAlgorithm::SHA1,
+                            flags: 1,
+                            opt_out: true,
+                            iterations: 12,
+                            salt: vec![0xaa, 0xbb, 0xcc, 0xdd],
+                            next_hashed_owner_name: vec![
+                                0x17, 0x4e, 0xb2, 0x40, 0x9f, 0xe2, 0x8b, 0xcb, 0x48, 0x87, 0xa1, 0x83, 0x6f, 0x95,
+                                0x7f, 0x0a, 0x84, 0x25, 0xe2, 0x7b,
+                            ],
+                            types: vec![
+                                RecordType::NS,
+                                RecordType::SOA,
+                                RecordType::MX,
+                                RecordType::RRSIG,
+                                RecordType::DNSKEY,
+                                RecordType::NSEC3PARAM,
+                            ],
+                            types_raw: vec![2, 6, 15, 46, 48, 51],
+                        }),
+                    }
+                ],
+                additional: vec![],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    case::parse_https_response(
+        &[
+            0x1c, 0x2d, // transaction id: 0x1c2d
+            0x81, 0x80, // flags: response, recursion desired, recursion available
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x01, // ANCOUNT: 1
+            0x00, 0x00, // NSCOUNT: 0
+            0x00, 0x00, // ARCOUNT: 0
+            0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+            // question: example.com
+            0x00, 0x41, // RType: HTTPS
+            0x00, 0x01, // RClass: IN
+            0xc0, 0x0c, // answer: example.com
+            0x00, 0x41, // RType: HTTPS
+            0x00, 0x01, // RClass: IN
+            0x00, 0x00, 0x01, 0x2c, // TTL: 300
+            0x00, 0x35, // data length: 53
+            0x00, 0x01, // priority: 1
+            0x00, // target name: root
+            0x00, 0x01, 0x00, 0x06, 0x02, 0x68, 0x33, 0x02, 0x68, 0x32, // alpn: h3, h2
+            0x00, 0x04, 0x00, 0x04, 0xc0, 0x00, 0x02, 0x01, // ipv4hint: 192.0.2.1
+            0x00, 0x05, 0x00, 0x08, 0x00, 0x06, 0xfe, 0x0d, 0x00, 0x02, 0xab, 0xcd, // ech
+            0x00, 0x06, 0x00, 0x10, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x01, // ipv6hint: 2001:db8::1
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x1c2d,
+                    flags: 0b1000_0001_1000_0000,
+                    query_response: QueryResponse::Response,
+                    opcode: OpCode::QUERY,
+                    authoritative: false,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: true,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NOERROR,
+                    qdcount: 1,
+                    ancount: 1,
+                    nscount: 0,
+                    arcount: 0
+                },
+                queries: vec![Question {
+                    name: "example.com".as_bytes().to_vec(),
+                    record_type: RecordType::HTTPS,
+                    record_type_raw: 65,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![
+                    Answer {
+                        name: "example.com".as_bytes().to_vec(),
+                        rtype: RecordType::HTTPS,
+                        rtype_raw: 65,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 300,
+                        data: RDataType::HTTPS(RDataSVCB {
+                            priority: 1,
+                            target_name: vec![],
+                            params: vec![
+                                SvcParam {
+                                    key: SvcParamKey::ALPN,
+                                    key_raw: 1,
+                                    value: SvcParamValue::ALPN(vec![b"h3".to_vec(), b"h2".to_vec()]),
+                                },
+                                SvcParam {
+                                    key: SvcParamKey::IPV4HINT,
+                                    key_raw: 4,
+                                    value: SvcParamValue::IPV4HINT(vec![vec![192, 0, 2, 1]]),
+                                },
+                                SvcParam {
+                                    key: SvcParamKey::ECH,
+                                    key_raw: 5,
+                                    value: SvcParamValue::ECH(vec![
+                                        0x00, 0x06, 0xfe, 0x0d, 0x00, 0x02, 0xab, 0xcd,
+                                    ]),
+                                },
+                                SvcParam {
+                                    key: SvcParamKey::IPV6HINT,
+                                    key_raw: 6,
+                                    value: SvcParamValue::IPV6HINT(vec![vec![
+                                        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                                        0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+                                    ]]),
+                                },
+                            ],
+                        }),
+                    }
+                ],
+                nameservers: vec![],
+                additional: vec![],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    case::parse_naptr_response(
+        &[
+            0x2b, 0x7a, // transaction id: 0x2b7a
+            0x81, 0x80, // flags: response, recursion desired, recursion available
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x01, // ANCOUNT: 1
+            0x00, 0x00, // NSCOUNT: 0
+            0x00, 0x00, // ARCOUNT: 0
+            0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+            // question: example.com
+            0x00, 0x23, // RType: NAPTR
+            0x00, 0x01, // RClass: IN
+            0xc0, 0x0c, // answer: example.com
+            0x00, 0x23, // RType: NAPTR
+            0x00, 0x01, // RClass: IN
+            0x00, 0x00, 0x0e, 0x10, // TTL: 3600
+            0x00, 0x1b, // data length: 27
+            0x00, 0x64, // order: 100
+            0x00, 0x0a, // preference: 10
+            0x01, 0x53, // flags: S
+            0x07, 0x53, 0x49, 0x50, 0x2b, 0x44, 0x32, 0x55, // services: SIP+D2U
+            0x00, // regexp: empty
+            0x04, 0x5f, 0x73, 0x69, 0x70, 0x04, 0x5f, 0x75, 0x64, 0x70, 0xc0, 0x0c,
+            // replacement: _sip._udp.example.com
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x2b7a,
+                    flags: 0b1000_0001_1000_0000,
+                    query_response: QueryResponse::Response,
+                    opcode: OpCode::QUERY,
+                    authoritative: false,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: true,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NOERROR,
+                    qdcount: 1,
+                    ancount: 1,
+                    nscount: 0,
+                    arcount: 0
+                },
+                queries: vec![Question {
+                    name: "example.com".as_bytes().to_vec(),
+                    record_type: RecordType::NAPTR,
+                    record_type_raw: 35,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![
+                    Answer {
+                        name: "example.com".as_bytes().to_vec(),
+                        rtype: RecordType::NAPTR,
+                        rtype_raw: 35,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 3600,
+                        data: RDataType::NAPTR(RDataNAPTR {
+                            order: 100,
+                            preference: 10,
+                            flags: b"S".to_vec(),
+                            services: b"SIP+D2U".to_vec(),
+                            regexp: vec![],
+                            replacement: b"_sip._udp.example.com".to_vec(),
+                        }),
+                    }
+                ],
+                nameservers: vec![],
+                additional: vec![],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    case::parse_edns_options_query(
+        &[
+            0x5a, 0x11, // transaction id: 0x5a11
+            0x01, 0x00, // flags: recursion desired
+            0x00, 0x01, // QDCOUNT: 1
+            0x00, 0x00, // ANCOUNT: 0
+            0x00, 0x00, // NSCOUNT: 0
+            0x00, 0x01, // ARCOUNT: 1
+            0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+            // question: example.com
+            0x00, 0x01, // RType: A
+            0x00, 0x01, // RClass: IN
+            0x00, // additional: <root>
+            0x00, 0x29, // RType: OPT
+            0x10, 0x00, // UDP payload size: 4096
+            0x00, // extended rcode: 0
+            0x00, // version: 0
+            0x00, 0x00, // flags: 0
+            0x00, 0x17, // data length: 23
+            0x00, 0x0a, 0x00, 0x08, // option: COOKIE, length: 8
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // client cookie
+            0x00, 0x08, 0x00, 0x07, // option: client subnet, length: 7
+            0x00, 0x01, // family: IPv4
+            0x18, // source prefix length: 24
+            0x00, // scope prefix length: 0
+            0xc0, 0x00, 0x02, // address: 192.0.2.0/24
+        ],
+        Ok((
+            0,
+            Some(Message {
+                header: Header {
+                    transaction_id: 0x5a11,
+                    flags: 0b0000_0001_0000_0000,
+                    query_response: QueryResponse::Query,
+                    opcode: OpCode::QUERY,
+                    authoritative: false,
+                    truncated: false,
+                    recursion_desired: true,
+                    recursion_available: false,
+                    zflag: false,
+                    authenticated_data: false,
+                    check_disabled: false,
+                    rcode: ResponseCode::NOERROR,
+                    qdcount: 1,
+                    ancount: 0,
+                    nscount: 0,
+                    arcount: 1
+                },
+                queries: vec![Question {
+                    name: "example.com".as_bytes().to_vec(),
+                    record_type: RecordType::A,
+                    record_type_raw: 1,
+                    record_class: RecordClass::IN,
+                    record_class_raw: 1,
+                }],
+                answers: vec![],
+                nameservers: vec![],
+                additional: vec![
+                    Answer {
+                        name: vec![0x00],
+                        rtype: RecordType::OPT,
+                        rtype_raw: 41,
+                        rclass: RecordClass::NONE,
+                        rclass_raw: 254,
+                        ttl: 0,
+                        data: RDataType::OPT(RDataOPT {
+                            udp_payload_size: 4096,
+                            extended_rcode: 0x00,
+                            version: 0,
+                            flags: 0,
+                            data: vec![
+                                EdnsOption {
+                                    code: OptionCode::COOKIE,
+                                    data: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+                                    value: EdnsOptionValue::COOKIE(Cookie {
+                                        client: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+                                        server: vec![],
+                                    }),
+                                },
+                                EdnsOption {
+                                    code: OptionCode::EDNSCLIENTSUBNET,
+                                    data: vec![0x00, 0x01, 0x18, 0x00, 0xc0, 0x00, 0x02],
+                                    value: EdnsOptionValue::EDNSCLIENTSUBNET(ClientSubnet {
+                                        family: AddressFamily::IPV4,
+                                        source_prefix_len: 24,
+                                        scope_prefix_len: 0,
+                                        address: vec![0xc0, 0x00, 0x02],
+                                    }),
+                                },
+                            ],
+                        }),
+                    }
+                ],
+                error_flags: ErrorFlags::none(),
+            })
+        ))
+    ),
+    )]
+    fn dns(input: &[u8], expected: Result<(usize, Option<Message>)>) {
+        let dns = Dns::default();
+        assert_eq!(
+            dns.parse(input, Direction::Unknown)
+                .map(|(rem, msg)| (rem.len(), msg)),
+            expected
+        );
+    }
+
+    /// Query for example.com A records, 29 bytes long
+    const TCP_QUERY: [u8; 29] = [
+        0x71, 0x0e, // transaction id: 0x710e
+        0x01, 0x00, // flags: recursion desired
+        0x00, 0x01, // QDCOUNT: 1
+        0x00, 0x00, // ANCOUNT: 0
+        0x00, 0x00, // NSCOUNT: 0
+        0x00, 0x00, // ARCOUNT: 0
+        0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+        // question: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+    ];
+
+    fn tcp_query(error_flags: Flags<ErrorFlags>) -> Message {
+        Message {
+            header: Header {
+                transaction_id: 0x710e,
+                flags: 0b0000_0001_0000_0000,
+                query_response: QueryResponse::Query,
+                opcode: OpCode::QUERY,
+                authoritative: false,
+                truncated: false,
+                recursion_desired: true,
+                recursion_available: false,
+                zflag: false,
+                authenticated_data: false,
+                check_disabled: false,
+                rcode: ResponseCode::NOERROR,
+                qdcount: 1,
+                ancount: 0,
+                nscount: 0,
+                arcount: 0,
+            },
+            queries: vec![Question {
+                name: "example.com".as_bytes().to_vec(),
+                record_type: RecordType::A,
+                record_type_raw: 1,
+                record_class: RecordClass::IN,
+                record_class_raw: 1,
+            }],
+            answers: vec![],
+            nameservers: vec![],
+            additional: vec![],
+            error_flags,
+        }
+    }
+
+    /// Frame the query with `length` and append `extra` bytes after it
+    fn tcp_frame(length: u16, extra: &[u8]) -> Vec<u8> {
+        let mut frame = length.to_be_bytes().to_vec();
+        frame.extend_from_slice(&TCP_QUERY);
+        frame.extend_from_slice(extra);
+        frame
+    }
+
+    #[rstest(
+    input,
+    expected,
+    case::empty(vec![], Err(Error::incomplete_needed(2))),
+    case::partial_length(vec![0x00], Err(Error::incomplete_needed(1))),
+    case::partial_message(tcp_frame(29, b"")[..12].to_vec(), Err(Error::incomplete_needed(19))),
+    case::message(tcp_frame(29, b""), Ok((0, Some(tcp_query(ErrorFlags::none()))))),
+    case::two_messages(
+        tcp_frame(29, &tcp_frame(29, b"")),
+        Ok((31, Some(tcp_query(ErrorFlags::none()))))
+    ),
+    case::trailing_bytes(
+        tcp_frame(31, &[0xff, 0xff]),
+        Ok((0, Some(tcp_query(ErrorFlags::TcpLengthMismatch.into()))))
+    ),
+    case::length_too_short(
+        tcp_frame(20, b""),
+        Ok((9, Some(Message { queries: vec![], ..tcp_query(ErrorFlags::TruncatedSection.into()) })))
+    ),
+    case::header_too_short(tcp_frame(10, b""), Err(Error::new(ErrorKind::InvalidData))),
+    case::zero_length(vec![0x00, 0x00], Err(Error::new(ErrorKind::InvalidData))),
+    )]
+    fn dns_tcp(input: Vec<u8>, expected: Result<(usize, Option<Message>)>) {
+        let dns = DnsTcp::default();
+        assert_eq!(
+            dns.parse(&input, Direction::Unknown)
+                .map(|(rem, msg)| (rem.len(), msg)),
+            expected
+        );
+    }
+
+    /// Response with three addresses for example.com and an OPT record, 88 bytes long
+    const SECTIONS_RESPONSE: [u8; 88] = [
+        0x3a, 0x5c, // transaction id: 0x3a5c
+        0x81, 0x80, // flags: response, recursion desired, recursion available
+        0x00, 0x01, // QDCOUNT: 1
+        0x00, 0x03, // ANCOUNT: 3
+        0x00, 0x00, // NSCOUNT: 0
+        0x00, 0x01, // ARCOUNT: 1
+        0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+        // question: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0xc0, 0x0c, // answer: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0x00, 0x00, 0x00, 0x3c, // TTL: 60
+        0x00, 0x04, // Data length: 4
+        0xc0, 0x00, 0x02, 0x01, // 192.0.2.1
+        0xc0, 0x0c, // answer: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0x00, 0x00, 0x00, 0x3c, // TTL: 60
+        0x00, 0x04, // Data length: 4
+        0xc0, 0x00, 0x02, 0x02, // 192.0.2.2
+        0xc0, 0x0c, // answer: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0x00, 0x00, 0x00, 0x3c, // TTL: 60
+        0x00, 0x04, // Data length: 4
+        0xc0, 0x00, 0x02, 0x03, // 192.0.2.3
+        0x00, // additional: <Root>
+        0x00, 0x29, // RType: OPT
+        0x04, 0xd0, // UDP payload size: 1232
+        0x00, // Higher bits in extended RCode: 0x00
+        0x00, // EDNS0 version: 0
+        0x00, 0x00, // Z: 0
+        0x00, 0x00, // Data length: 0
+    ];
+
+    #[rstest(
+    length,
+    max_records,
+    partial_messages,
+    expected,
+    case::complete(88, DEFAULT_MAX_RECORDS, true, Ok((0, 1, 3, 1, ErrorFlags::none()))),
+    case::header_only(12, DEFAULT_MAX_RECORDS, true, Ok((0, 0, 0, 0, ErrorFlags::TruncatedSection.into()))),
+    case::truncated_question(20, DEFAULT_MAX_RECORDS, true, Ok((0, 0, 0, 0, ErrorFlags::TruncatedSection.into()))),
+    case::truncated_answer(65, DEFAULT_MAX_RECORDS, true, Ok((0, 1, 2, 0, ErrorFlags::TruncatedSection.into()))),
+    case::truncated_additional(85, DEFAULT_MAX_RECORDS, true, Ok((0, 1, 3, 0, ErrorFlags::TruncatedSection.into()))),
+    case::limit(88, 2, true, Ok((0, 1, 2, 1, ErrorFlags::RecordLimitExceeded.into()))),
+    case::limit_truncated(70, 1, true, Ok((0, 1, 1, 0, ErrorFlags::RecordLimitExceeded | ErrorFlags::TruncatedSection))),
+    case::no_records(88, 0, true, Ok((0, 0, 0, 0, ErrorFlags::RecordLimitExceeded.into()))),
+    case::truncated_header(10, DEFAULT_MAX_RECORDS, true, Err(Error::incomplete_needed(2))),
+    case::streaming(65, DEFAULT_MAX_RECORDS, false, Err(Error::incomplete_needed(2))),
+    case::streaming_limit(88, 2, false, Ok((0, 1, 2, 1, ErrorFlags::RecordLimitExceeded.into()))),
+    )]
+    fn dns_sections(
+        length: usize,
+        max_records: usize,
+        partial_messages: bool,
+        expected: Result<(usize, usize, usize, usize, Flags<ErrorFlags>)>,
+    ) {
+        let dns = Dns::new(max_records, partial_messages);
+        assert_eq!(
+            dns.parse(&SECTIONS_RESPONSE[..length], Direction::Unknown)
+                .map(|(rem, message)| {
+                    let message = message.unwrap();
+                    (
+                        rem.len(),
+                        message.queries.len(),
+                        message.answers.len(),
+                        message.additional.len(),
+                        message.error_flags,
+                    )
+                }),
+            expected
+        );
+    }
+
+    /// mDNS response asking for unicast replies and flushing cached records
+    const MDNS_RESPONSE: [u8; 47] = [
+        0x00, 0x00, // transaction id: 0x0000
+        0x84, 0x00, // flags: response, authoritative
+        0x00, 0x01, // QDCOUNT: 1
+        0x00, 0x01, // ANCOUNT: 1
+        0x00, 0x00, // NSCOUNT: 0
+        0x00, 0x00, // ARCOUNT: 0
+        0x07, 0x70, 0x72, 0x69, 0x6e, 0x74, 0x65, 0x72, 0x05, 0x6c, 0x6f, 0x63, 0x61, 0x6c,
+        0x00, // question: printer.local
+        0x00, 0x01, // RType: A
+        0x80, 0x01, // RClass: IN, unicast response
+        0xc0, 0x0c, // answer: printer.local
+        0x00, 0x01, // RType: A
+        0x80, 0x01, // RClass: IN, cache flush
+        0x00, 0x00, 0x00, 0x78, // TTL: 120
+        0x00, 0x04, // Data length: 4
+        0xc0, 0xa8, 0x01, 0x14, // 192.168.1.20
+    ];
+
+    #[test]
+    fn mdns() {
+        let dns = Dns::default();
+        let (_, message) = dns.parse(&MDNS_RESPONSE, Direction::Unknown).unwrap();
+        let message = message.unwrap();
+        assert_eq!(RecordClass::UNKNOWN, message.answers[0].rclass);
+        assert_eq!(Flags::from(ErrorFlags::UnknownRclass), message.error_flags);
+
+        let mdns = Mdns::default();
+        let (rest, message) = mdns.parse(&MDNS_RESPONSE, Direction::Unknown).unwrap();
+        assert!(rest.is_empty());
+        let message = message.unwrap();
+        assert_eq!(ErrorFlags::none(), message.error_flags);
+        assert_eq!(RecordClass::IN, message.queries[0].record_class);
+        assert_eq!(0x8001, message.queries[0].record_class_raw);
+        assert!(message.queries[0].unicast_response());
+        assert_eq!(RecordClass::IN, message.answers[0].rclass);
+        assert!(message.answers[0].cache_flush());
+    }
+
+    #[test]
+    fn dns_tcp_max_records() {
+        let dns = DnsTcp::new(0);
+        let (_, message) = dns.parse(&tcp_frame(29, b""), Direction::Unknown).unwrap();
+        assert_eq!(
+            Some(Message {
+                queries: vec![],
+                ..tcp_query(ErrorFlags::RecordLimitExceeded.into())
+            }),
+            message
+        );
+    }
+
+    #[test]
+    fn mdns_options() {
+        let input = &MDNS_RESPONSE[..MDNS_RESPONSE.len() - 4];
+        assert!(Mdns::default().parse(input, Direction::Unknown).is_err());
+
+        let mdns = Mdns::new(0, true);
+        let (rest, message) = mdns.parse(input, Direction::Unknown).unwrap();
+        assert!(rest.is_empty());
+        let message = message.unwrap();
+        assert!(message.queries.is_empty());
+        assert!(message.answers.is_empty());
+        assert_eq!(
+            ErrorFlags::RecordLimitExceeded | ErrorFlags::TruncatedSection,
+            message.error_flags
+        );
+
+        let mdns = Mdns::new(DEFAULT_MAX_RECORDS, true);
+        let (_, message) = mdns.parse(input, Direction::Unknown).unwrap();
+        let message = message.unwrap();
+        assert_eq!(1, message.queries.len());
+        assert!(message.answers.is_empty());
+        assert_eq!(
+            Flags::from(ErrorFlags::TruncatedSection),
+            message.error_flags
+        );
+    }
+
+    #[test]
+    fn mdns_unknown_class() {
+        let mut input = MDNS_RESPONSE;
+        // Class 0x0002 is unassigned with or without the cache-flush bit
+        input[36] = 0x02;
+        let mdns = Mdns::default();
+        let (_, message) = mdns.parse(&input, Direction::Unknown).unwrap();
+        let message = message.unwrap();
+        assert_eq!(RecordClass::UNKNOWN, message.answers[0].rclass);
+        assert_eq!(Flags::from(ErrorFlags::UnknownRclass), message.error_flags);
+    }
+
+    #[rstest(
+        flags,
+        conflict,
+        tentative,
+        case::none(0x8000, false, false),
+        case::conflict(0x8400, true, false),
+        case::tentative(0x8100, false, true)
+    )]
+    fn llmnr(flags: u16, conflict: bool, tentative: bool) {
+        let mut input = MDNS_RESPONSE;
+        input[2..4].copy_from_slice(&flags.to_be_bytes());
+        // LLMNR has no class bits
+        input[29] = 0x00;
+        input[35] = 0x00;
+        let llmnr = Llmnr::default();
+        let (_, message) = llmnr.parse(&input, Direction::Unknown).unwrap();
+        let message = message.unwrap();
+        assert_eq!(conflict, message.header.conflict());
+        assert_eq!(tentative, message.header.tentative());
+        assert_eq!(ErrorFlags::none(), message.error_flags);
+    }
+
+    #[test]
+    fn llmnr_options() {
+        let mut input = MDNS_RESPONSE;
+        input[29] = 0x00;
+        input[35] = 0x00;
+        let input = &input[..input.len() - 4];
+        assert!(Llmnr::default().parse(input, Direction::Unknown).is_err());
+
+        let llmnr = Llmnr::new(DEFAULT_MAX_RECORDS, true);
+        let (_, message) = llmnr.parse(input, Direction::Unknown).unwrap();
+        let message = message.unwrap();
+        assert_eq!(1, message.queries.len());
+        assert!(message.answers.is_empty());
+        assert_eq!(
+            Flags::from(ErrorFlags::TruncatedSection),
+            message.error_flags
         );
     }
 }
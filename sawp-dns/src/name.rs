@@ -1,9 +1,19 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
 use crate::ErrorFlags::DnsNameInvalidCompression;
 use crate::{ErrorFlags, IResult};
+use nom::bytes::streaming::take;
 use nom::multi::length_data;
 use nom::number::streaming::{be_u16, be_u8};
 use sawp_flags::{Flag, Flags};
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 // From RFC 1035: DNS labels cannot exceed 63 characters in length
 const DNS_MAX_LABEL_LEN: usize = 63;
 // Similar: DNS names cannot exceed length 255.
@@ -50,84 +60,420 @@ impl Name {
     pub fn parse<'b: 'i + 'r, 'i: 'r, 'r>(
         reference_bytes: &'b [u8],
     ) -> impl FnMut(&'i [u8]) -> IResult<(Vec<u8>, Flags<ErrorFlags>)> + 'r {
-        move |mut input| {
-            let mut current_position = input;
-            let mut current_position_is_base = true;
-            let mut error_flags = ErrorFlags::none();
+        move |input| {
             let mut name: Vec<u8> = Vec::new();
-
-            for _ in 0..MAX_LABEL_PARSES {
-                if current_position.is_empty() || current_position[0] == b'\0' {
-                    break;
+            let (input, error_flags) = Name::parse_labels(input, reference_bytes, |label| {
+                if name.len() >= DNS_MAX_DOMAIN_LEN {
+                    return ErrorFlags::none();
+                }
+                // Truncate the label so the name won't exceed the max length
+                let length = core::cmp::min(label.len(), (DNS_MAX_DOMAIN_LEN - 1) - name.len());
+                // Check if we truncated
+                let error_flags = if name.len() + label.len() + 1 > DNS_MAX_DOMAIN_LEN {
+                    ErrorFlags::DnsNameExceedsMaxLen.into()
+                } else {
+                    ErrorFlags::none()
+                };
+                // always extend
+                if !name.is_empty() {
+                    name.push(b'.');
                 }
+                name.extend_from_slice(&label[..length]);
+                error_flags
+            })?;
+            Ok((input, (name, error_flags)))
+        }
+    }
 
-                if Name::name_is_compressed(current_position[0]) {
-                    match Name::follow_compressed_segment(current_position, reference_bytes) {
-                        Ok((rem, offset)) => {
-                            if offset == current_position {
-                                // If the pointer points to itself, bail out and flag to avoid using MAX_LABEL_PARSES cycles
-                                error_flags |= DnsNameInvalidCompression;
-                                return Ok((rem, (name, error_flags)));
-                            }
-
-                            if current_position_is_base {
-                                input = rem;
-                                current_position_is_base = false;
-                            }
-                            current_position = offset;
-                        }
-                        Err(nom::Err::Error((pos, nom::error::ErrorKind::Verify))) => {
-                            error_flags |= ErrorFlags::DnsNameInvalidCompression;
-                            return Ok((pos, (name, error_flags)));
+    /// Skip over a name without following compression pointers or collecting its
+    /// labels
+    pub(crate) fn skip(mut input: &[u8]) -> IResult<()> {
+        loop {
+            let (rem, len) = be_u8(input)?;
+            if len == 0 {
+                return Ok((rem, ()));
+            }
+            if Name::name_is_compressed(len) {
+                let (rem, _) = be_u8(rem)?;
+                return Ok((rem, ()));
+            }
+            let (rem, _) = take(len)(rem)?;
+            input = rem;
+        }
+    }
+
+    /// Walk the labels of a name, following compression pointers, and pass each
+    /// label to `on_label` which may return further error flags.
+    fn parse_labels<'a, F>(
+        mut input: &'a [u8],
+        reference_bytes: &'a [u8],
+        mut on_label: F,
+    ) -> IResult<'a, Flags<ErrorFlags>>
+    where
+        F: FnMut(&[u8]) -> Flags<ErrorFlags>,
+    {
+        let mut current_position = input;
+        let mut current_position_is_base = true;
+        let mut error_flags = ErrorFlags::none();
+
+        // The root name is a single zero length label
+        if input.first() == Some(&b'\0') {
+            return Ok((&input[1..], error_flags));
+        }
+
+        for _ in 0..MAX_LABEL_PARSES {
+            if current_position.is_empty() || current_position[0] == b'\0' {
+                break;
+            }
+
+            if Name::name_is_compressed(current_position[0]) {
+                match Name::follow_compressed_segment(current_position, reference_bytes) {
+                    Ok((rem, offset)) => {
+                        if offset == current_position {
+                            // If the pointer points to itself, bail out and flag to avoid using MAX_LABEL_PARSES cycles
+                            error_flags |= DnsNameInvalidCompression;
+                            return Ok((rem, error_flags));
                         }
-                        Err(e) => {
-                            return Err(e);
+
+                        if current_position_is_base {
+                            input = rem;
+                            current_position_is_base = false;
                         }
+                        current_position = offset;
                     }
-                } else {
-                    let (mut rem, (label, inner_error_flags)) =
-                        Name::parse_label(current_position)?;
-                    error_flags |= inner_error_flags;
-
-                    if name.len() < DNS_MAX_DOMAIN_LEN {
-                        // Truncate the label so the name won't exceed the max length
-                        let length =
-                            std::cmp::min(label.len(), (DNS_MAX_DOMAIN_LEN - 1) - name.len());
-                        // Check if we truncated
-                        if name.len() + label.len() + 1 > DNS_MAX_DOMAIN_LEN {
-                            error_flags |= ErrorFlags::DnsNameExceedsMaxLen;
-                        };
-                        // always extend
-                        if !name.is_empty() {
-                            name.push(b'.');
-                        }
-                        name.extend_from_slice(&label[..length]);
+                    Err(nom::Err::Error((pos, nom::error::ErrorKind::Verify))) => {
+                        error_flags |= ErrorFlags::DnsNameInvalidCompression;
+                        return Ok((pos, error_flags));
+                    }
+                    Err(e) => {
+                        return Err(e);
                     }
+                }
+            } else {
+                let (mut rem, (label, inner_error_flags)) = Name::parse_label(current_position)?;
+                error_flags |= inner_error_flags;
+                error_flags |= on_label(label);
 
-                    current_position = rem;
+                current_position = rem;
 
-                    if rem.get(0) == Some(&b'\0') {
-                        rem = &rem[1..];
-                    }
+                if rem.get(0) == Some(&b'\0') {
+                    rem = &rem[1..];
+                }
 
-                    if current_position_is_base {
-                        input = rem;
-                    }
+                if current_position_is_base {
+                    input = rem;
                 }
             }
-            Ok((input, (name, error_flags)))
         }
+        Ok((input, error_flags))
+    }
+}
+
+/// A domain name kept as its labels.
+///
+/// Names compare and hash ignoring ASCII case as described in
+/// [RFC4343](https://tools.ietf.org/html/rfc4343).
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Debug, Default)]
+pub struct DnsName {
+    labels: Vec<Vec<u8>>,
+}
+
+impl DnsName {
+    pub fn new(labels: Vec<Vec<u8>>) -> Self {
+        DnsName { labels }
+    }
+
+    /// Split a dotted name such as the ones returned by [`Name::parse`].
+    ///
+    /// A trailing dot is ignored. Labels containing a literal dot cannot be
+    /// represented this way.
+    pub fn from_dotted(name: &[u8]) -> Self {
+        let name = match name.split_last() {
+            Some((b'.', rest)) => rest,
+            _ => name,
+        };
+        if name.is_empty() {
+            return DnsName::default();
+        }
+        DnsName {
+            labels: name
+                .split(|c| *c == b'.')
+                .map(|label| label.to_vec())
+                .collect(),
+        }
+    }
+
+    /// Parse a name keeping its labels, following compression pointers into
+    /// `reference_bytes`.
+    pub fn parse<'b: 'i + 'r, 'i: 'r, 'r>(
+        reference_bytes: &'b [u8],
+    ) -> impl FnMut(&'i [u8]) -> IResult<(DnsName, Flags<ErrorFlags>)> + 'r {
+        move |input| {
+            let mut labels: Vec<Vec<u8>> = Vec::new();
+            // Account for the terminating root label
+            let mut wire_len = 1;
+            let (input, error_flags) = Name::parse_labels(input, reference_bytes, |label| {
+                wire_len += label.len() + 1;
+                labels.push(label.to_vec());
+                if wire_len > DNS_MAX_DOMAIN_LEN {
+                    ErrorFlags::DnsNameExceedsMaxLen.into()
+                } else {
+                    ErrorFlags::none()
+                }
+            })?;
+            Ok((input, (DnsName { labels }, error_flags)))
+        }
+    }
+
+    pub fn labels(&self) -> &[Vec<u8>] {
+        &self.labels
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Copy of the name with ASCII letters in lower case
+    pub fn to_lowercase(&self) -> DnsName {
+        DnsName {
+            labels: self
+                .labels
+                .iter()
+                .map(|label| label.to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Whether the name is `suffix` or below it
+    pub fn ends_with(&self, suffix: &DnsName) -> bool {
+        self.labels.len() >= suffix.labels.len()
+            && self
+                .labels
+                .iter()
+                .rev()
+                .zip(suffix.labels.iter().rev())
+                .all(|(label, other)| label.eq_ignore_ascii_case(other))
+    }
+
+    /// Whether the name is strictly below `zone`
+    pub fn is_subdomain_of(&self, zone: &DnsName) -> bool {
+        self.labels.len() > zone.labels.len() && self.ends_with(zone)
+    }
+
+    /// Address octets of an in-addr.arpa or ip6.arpa name, in the same form as
+    /// A and AAAA record data. Returns None for other names, including partial
+    /// and classless reverse names.
+    pub fn reverse_address(&self) -> Option<Vec<u8>> {
+        let (address, suffix) = self.labels.split_at(self.labels.len().checked_sub(2)?);
+        if suffix[1].eq_ignore_ascii_case(b"arpa") {
+            if suffix[0].eq_ignore_ascii_case(b"in-addr") && address.len() == 4 {
+                return address
+                    .iter()
+                    .rev()
+                    .map(|label| parse_octet(label))
+                    .collect();
+            }
+            if suffix[0].eq_ignore_ascii_case(b"ip6") && address.len() == 32 {
+                let nibbles: Option<Vec<u8>> = address
+                    .iter()
+                    .rev()
+                    .map(|label| parse_nibble(label))
+                    .collect();
+                return nibbles.map(|nibbles| {
+                    nibbles
+                        .chunks_exact(2)
+                        .map(|pair| pair[0] << 4 | pair[1])
+                        .collect()
+                });
+            }
+        }
+        None
+    }
+
+    /// Whether any label is an internationalized A-label
+    pub fn is_idn(&self) -> bool {
+        self.labels.iter().any(|label| is_a_label(label))
+    }
+
+    /// Dotted name with `xn--` labels decoded from punycode, for display and
+    /// comparison with lookalike names. Labels that fail to decode are kept as
+    /// is and invalid UTF-8 is replaced.
+    pub fn to_unicode(&self) -> String {
+        let mut name = String::new();
+        for (index, label) in self.labels.iter().enumerate() {
+            if index > 0 {
+                name.push('.');
+            }
+            match decode_a_label(label) {
+                Some(decoded) => name.push_str(&decoded),
+                None => name.push_str(&String::from_utf8_lossy(label)),
+            }
+        }
+        name
+    }
+}
+
+impl PartialEq for DnsName {
+    fn eq(&self, other: &Self) -> bool {
+        self.labels.len() == other.labels.len() && self.ends_with(other)
+    }
+}
+
+impl Eq for DnsName {}
+
+impl Hash for DnsName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for label in &self.labels {
+            state.write_usize(label.len());
+            for c in label {
+                state.write_u8(c.to_ascii_lowercase());
+            }
+        }
+    }
+}
+
+/// Presentation format from RFC1035 section 5.1, escaping dots within labels
+impl fmt::Display for DnsName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.labels.is_empty() {
+            return f.write_str(".");
+        }
+        for (index, label) in self.labels.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            for c in label {
+                match c {
+                    b'.' | b'\\' => write!(f, "\\{}", *c as char)?,
+                    0x21..=0x7e => write!(f, "{}", *c as char)?,
+                    _ => write!(f, "\\{:03}", c)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_octet(label: &[u8]) -> Option<u8> {
+    if label.is_empty() || label.len() > 3 || !label.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let value = label
+        .iter()
+        .fold(0u16, |value, c| value * 10 + u16::from(c - b'0'));
+    u8::try_from(value).ok()
+}
+
+fn parse_nibble(label: &[u8]) -> Option<u8> {
+    match label {
+        [c @ b'0'..=b'9'] => Some(c - b'0'),
+        [c @ b'a'..=b'f'] => Some(c - b'a' + 10),
+        [c @ b'A'..=b'F'] => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+const IDN_PREFIX: &[u8] = b"xn--";
+
+fn is_a_label(label: &[u8]) -> bool {
+    label.len() > IDN_PREFIX.len() && label[..IDN_PREFIX.len()].eq_ignore_ascii_case(IDN_PREFIX)
+}
+
+fn decode_a_label(label: &[u8]) -> Option<String> {
+    if is_a_label(label) {
+        decode_punycode(&label[IDN_PREFIX.len()..])
+    } else {
+        None
+    }
+}
+
+// Bootstring parameters for punycode from RFC3492 section 5
+const PUNYCODE_BASE: u32 = 36;
+const PUNYCODE_TMIN: u32 = 1;
+const PUNYCODE_TMAX: u32 = 26;
+const PUNYCODE_SKEW: u32 = 38;
+const PUNYCODE_DAMP: u32 = 700;
+const PUNYCODE_INITIAL_BIAS: u32 = 72;
+const PUNYCODE_INITIAL_N: u32 = 0x80;
+
+/// Decode a punycode string as described in RFC3492 section 6.2
+fn decode_punycode(input: &[u8]) -> Option<String> {
+    // Basic code points are copied up to the last delimiter
+    let (basic, encoded) = match input.iter().rposition(|c| *c == b'-') {
+        Some(position) => (&input[..position], &input[position + 1..]),
+        None => (&input[..0], input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+    let mut output: Vec<char> = basic.iter().map(|c| *c as char).collect();
+
+    let mut n = PUNYCODE_INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = PUNYCODE_INITIAL_BIAS;
+    let mut encoded = encoded.iter();
+    while encoded.len() > 0 {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = PUNYCODE_BASE;
+        loop {
+            let digit = match encoded.next()? {
+                c @ b'a'..=b'z' => c - b'a',
+                c @ b'A'..=b'Z' => c - b'A',
+                c @ b'0'..=b'9' => c - b'0' + 26,
+                _ => return None,
+            };
+            let digit = u32::from(digit);
+            i = i.checked_add(digit.checked_mul(w)?)?;
+            let t = if k <= bias {
+                PUNYCODE_TMIN
+            } else if k >= bias + PUNYCODE_TMAX {
+                PUNYCODE_TMAX
+            } else {
+                k - bias
+            };
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(PUNYCODE_BASE - t)?;
+            k += PUNYCODE_BASE;
+        }
+        let length = output.len() as u32 + 1;
+        bias = punycode_adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+        output.insert(i as usize, core::char::from_u32(n)?);
+        i += 1;
+    }
+    Some(output.into_iter().collect())
+}
+
+fn punycode_adapt(delta: u32, length: u32, first: bool) -> u32 {
+    let mut delta = if first {
+        delta / PUNYCODE_DAMP
+    } else {
+        delta / 2
+    };
+    delta += delta / length;
+    let mut k = 0;
+    while delta > ((PUNYCODE_BASE - PUNYCODE_TMIN) * PUNYCODE_TMAX) / 2 {
+        delta /= PUNYCODE_BASE - PUNYCODE_TMIN;
+        k += PUNYCODE_BASE;
     }
+    k + (PUNYCODE_BASE - PUNYCODE_TMIN + 1) * delta / (delta + PUNYCODE_SKEW)
 }
 
 #[cfg(test)]
 mod test {
     #![allow(clippy::type_complexity)]
 
-    use crate::name::Name;
+    use crate::name::{DnsName, Name};
     use crate::{ErrorFlags, IResult};
     use rstest::rstest;
     use sawp_flags::{Flag, Flags};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
 
     #[rstest(
     input,
@@ -466,8 +812,183 @@ mod test {
             )
         ))
     ),
+    case::root_name(
+        & [
+            0x00, // <root>
+            0x00, 0x02, // RType: NS
+        ],
+        & [],
+        Ok((
+            [
+                0x00, 0x02, // RType: NS
+            ].as_ref(),
+            (
+                vec![],
+                ErrorFlags::none()
+            )
+        ))
+    ),
     )]
     fn name(input: &[u8], reference_bytes: &[u8], expected: IResult<(Vec<u8>, Flags<ErrorFlags>)>) {
         assert_eq!(Name::parse(reference_bytes)(input), expected);
     }
+
+    fn dns_name(name: &str) -> DnsName {
+        DnsName::from_dotted(name.as_bytes())
+    }
+
+    #[rstest(
+        input,
+        reference_bytes,
+        expected,
+        case::literal_dot(
+            &[
+                0x03, 0x61, 0x2e, 0x62, // a.b
+                0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d,
+                0x00, // example.com
+            ],
+            &[],
+            Ok((
+                b"".as_ref(),
+                (
+                    DnsName::new(vec![b"a.b".to_vec(), b"example".to_vec(), b"com".to_vec()]),
+                    ErrorFlags::none()
+                )
+            ))
+        ),
+        case::compressed(
+            &[
+                0x03, 0x77, 0x77, 0x77, 0xc0, 0x00, // www, pointer to example.com
+                0x00, 0x01, // RType: A
+            ],
+            &[
+                0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d,
+                0x00, // example.com
+            ],
+            Ok((
+                [0x00, 0x01].as_ref(),
+                (dns_name("www.example.com"), ErrorFlags::none())
+            ))
+        ),
+        case::root(&[0x00], &[], Ok((b"".as_ref(), (DnsName::default(), ErrorFlags::none())))),
+        case::invalid_compression(
+            &[0xc0, 0xfc],
+            &[],
+            Ok((b"".as_ref(), (DnsName::default(), ErrorFlags::DnsNameInvalidCompression.into())))
+        ),
+    )]
+    fn dns_name_parse(
+        input: &[u8],
+        reference_bytes: &[u8],
+        expected: IResult<(DnsName, Flags<ErrorFlags>)>,
+    ) {
+        assert_eq!(DnsName::parse(reference_bytes)(input), expected);
+    }
+
+    #[test]
+    fn dns_name_too_long() {
+        let mut input = Vec::new();
+        for _ in 0..5 {
+            input.push(63);
+            input.extend_from_slice(&[0x61; 63]);
+        }
+        input.push(0);
+        let (rest, (name, error_flags)) = DnsName::parse(&[])(&input).unwrap();
+        assert!(rest.is_empty());
+        // Labels are kept whole unlike the flat name
+        assert_eq!(5, name.labels().len());
+        assert_eq!(Flags::from(ErrorFlags::DnsNameExceedsMaxLen), error_flags);
+    }
+
+    #[test]
+    fn dns_name_case_insensitive() {
+        fn hash(name: &DnsName) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            name.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let name = dns_name("WWW.Example.com");
+        assert_eq!(dns_name("www.example.com"), name);
+        assert_eq!(hash(&dns_name("www.example.com")), hash(&name));
+        assert_eq!(dns_name("www.example.com."), name);
+        assert_ne!(dns_name("www.example.org"), name);
+        assert_ne!(dns_name("example.com"), name);
+        assert_eq!(
+            vec![b"www".to_vec(), b"example".to_vec(), b"com".to_vec()],
+            name.to_lowercase().labels()
+        );
+    }
+
+    #[rstest(
+        name,
+        zone,
+        ends_with,
+        is_subdomain,
+        case::same("example.com", "Example.COM", true, false),
+        case::below("www.example.com", "example.com", true, true),
+        case::root("example.com", "", true, true),
+        case::sibling("www.example.org", "example.com", false, false),
+        case::partial_label("www.badexample.com", "example.com", false, false),
+        case::above("com", "example.com", false, false)
+    )]
+    fn dns_name_zone(name: &str, zone: &str, ends_with: bool, is_subdomain: bool) {
+        let name = dns_name(name);
+        let zone = dns_name(zone);
+        assert_eq!(ends_with, name.ends_with(&zone));
+        assert_eq!(is_subdomain, name.is_subdomain_of(&zone));
+    }
+
+    #[rstest(
+        name,
+        expected,
+        case::ipv4("1.2.0.192.in-addr.arpa", Some(vec![192, 0, 2, 1])),
+        case::ipv4_upper_case("1.2.0.192.IN-ADDR.ARPA", Some(vec![192, 0, 2, 1])),
+        case::ipv6(
+            "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa",
+            Some(vec![0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0x05, 0x67, 0x89, 0xab])
+        ),
+        case::ipv4_partial("2.0.192.in-addr.arpa", None),
+        case::ipv4_octet_too_large("1.2.0.256.in-addr.arpa", None),
+        case::ipv4_classless("1.0/25.2.0.192.in-addr.arpa", None),
+        case::ipv6_label_too_long("b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.20.ip6.arpa", None),
+        case::not_reverse("www.example.com", None),
+        case::arpa("arpa", None),
+    )]
+    fn dns_name_reverse_address(name: &str, expected: Option<Vec<u8>>) {
+        assert_eq!(expected, dns_name(name).reverse_address());
+    }
+
+    #[rstest(
+        name,
+        is_idn,
+        expected,
+        case::ascii("www.example.com", false, "www.example.com"),
+        case::mixed("xn--bcher-kva.example", true, "bücher.example"),
+        case::no_basic("xn--e1afmkfd.xn--p1ai", true, "пример.рф"),
+        case::homograph("xn--80ak6aa92e.com", true, "аррӏе.com"),
+        case::upper_case_prefix("XN--zca.de", true, "ß.de"),
+        case::invalid_digit("xn--bad!.com", true, "xn--bad!.com"),
+        case::truncated("xn--mnchen-3y.de", true, "xn--mnchen-3y.de"),
+        case::prefix_only("xn--.com", false, "xn--.com")
+    )]
+    fn dns_name_unicode(name: &str, is_idn: bool, expected: &str) {
+        let name = dns_name(name);
+        assert_eq!(is_idn, name.is_idn());
+        assert_eq!(expected, name.to_unicode());
+    }
+
+    #[rstest(
+        name,
+        expected,
+        case::root(DnsName::default(), "."),
+        case::simple(DnsName::from_dotted(b"www.example.com"), "www.example.com"),
+        case::escaped(
+            DnsName::new(vec![b"a.b\\c d".to_vec(), b"com".to_vec()]),
+            "a\\.b\\\\c\\032d.com"
+        ),
+    )]
+    fn dns_name_display(name: DnsName, expected: &str) {
+        assert_eq!(expected, name.to_string());
+    }
 }
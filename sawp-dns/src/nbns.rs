@@ -0,0 +1,994 @@
+//! NetBIOS Name Service
+//! [RFC1001](https://tools.ietf.org/html/rfc1001)
+//! [RFC1002](https://tools.ietf.org/html/rfc1002)
+//!
+//! Name queries, registrations and releases on UDP port 137 share the DNS
+//! message format. A NetBIOS name is 16 bytes, 15 bytes of name padded with
+//! spaces followed by a suffix identifying the service, and is carried as a
+//! single label using first-level encoding where each half byte is added to
+//! 'A'. Any further labels are the NetBIOS scope.
+
+#![allow(clippy::upper_case_acronyms)]
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use nom::bytes::streaming::take;
+use nom::number::streaming::{be_u16, be_u32};
+use num_enum::TryFromPrimitive;
+
+use sawp::error::Result;
+use sawp::parser::{Direction, Parse};
+use sawp::probe::Probe;
+use sawp::protocol::Protocol;
+use sawp_flags::{BitFlags, Flag, Flags};
+
+use crate::enums::{QueryResponse, RecordClass};
+use crate::name::DnsName;
+use crate::{custom_count, ErrorFlags, IResult};
+
+#[cfg(feature = "ffi")]
+use sawp_ffi::GenerateFFI;
+
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Length of an encoded NetBIOS name label
+const ENCODED_NAME_LEN: usize = 32;
+/// Length of a NetBIOS name including its suffix
+const NAME_LEN: usize = 16;
+/// Length of an NB address entry
+const NB_ADDRESS_LEN: usize = 6;
+/// Length of the unit id (MAC address) leading node status statistics
+const UNIT_ID_LEN: usize = 6;
+
+/// Masks for extracting NetBIOS name service header flags
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, BitFlags)]
+#[repr(u16)]
+pub enum nbns_header_masks {
+    RESPONSE = 0b1000_0000_0000_0000,
+    OPCODE = 0b0111_1000_0000_0000,
+    AUTH = 0b0000_0100_0000_0000,
+    TRUNC = 0b0000_0010_0000_0000,
+    RECUR_DESIRED = 0b0000_0001_0000_0000,
+    RECUR_AVAIL = 0b0000_0000_1000_0000,
+    BROADCAST = 0b0000_0000_0001_0000,
+    RCODE = 0b0000_0000_0000_1111,
+}
+
+/// Masks for extracting the flags of NB addresses and node status names
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, BitFlags)]
+#[repr(u16)]
+pub enum name_flags_masks {
+    GROUP = 0b1000_0000_0000_0000,
+    NODE_TYPE = 0b0110_0000_0000_0000,
+    DEREGISTER = 0b0001_0000_0000_0000,
+    CONFLICT = 0b0000_1000_0000_0000,
+    ACTIVE = 0b0000_0100_0000_0000,
+    PERMANENT = 0b0000_0010_0000_0000,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum NbnsOpCode {
+    QUERY = 0,
+    REGISTRATION = 5,
+    RELEASE = 6,
+    /// Wait for acknowledgement
+    WACK = 7,
+    REFRESH = 8,
+    /// Refresh opcode sent by Windows
+    REFRESHALT = 9,
+    MULTIHOMEDREGISTRATION = 15,
+    UNKNOWN,
+}
+
+impl NbnsOpCode {
+    pub fn from_raw(val: u16) -> Self {
+        NbnsOpCode::try_from(val).unwrap_or(NbnsOpCode::UNKNOWN)
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum NbnsResponseCode {
+    NOERROR = 0,
+    /// Format error
+    FMTERR = 1,
+    /// Server failure
+    SRVERR = 2,
+    /// Name does not exist
+    NAMERR = 3,
+    /// Unsupported request
+    IMPERR = 4,
+    /// Refused
+    RFSERR = 5,
+    /// Name is owned by another node
+    ACTERR = 6,
+    /// Name is in conflict
+    CFTERR = 7,
+    UNKNOWN,
+}
+
+impl NbnsResponseCode {
+    pub fn from_raw(val: u16) -> Self {
+        NbnsResponseCode::try_from(val).unwrap_or(NbnsResponseCode::UNKNOWN)
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum NbnsRecordType {
+    A = 1,
+    NS = 2,
+    NULL = 10,
+    /// NetBIOS general name service
+    NB = 32,
+    /// NetBIOS node status
+    NBSTAT = 33,
+    UNKNOWN,
+}
+
+impl NbnsRecordType {
+    pub fn from_raw(val: u16) -> Self {
+        NbnsRecordType::try_from(val).unwrap_or(NbnsRecordType::UNKNOWN)
+    }
+}
+
+/// How the owner of a name resolves names
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum NodeType {
+    /// Broadcast
+    B = 0,
+    /// Point-to-point
+    P = 1,
+    /// Mixed
+    M = 2,
+    /// Hybrid
+    H = 3,
+}
+
+impl NodeType {
+    fn from_flags(flags: Flags<name_flags_masks>) -> Self {
+        match (flags & name_flags_masks::NODE_TYPE).bits() >> 13 {
+            0 => NodeType::B,
+            1 => NodeType::P,
+            2 => NodeType::M,
+            _ => NodeType::H,
+        }
+    }
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NbnsHeader {
+    pub transaction_id: u16,
+    pub flags: u16,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub query_response: QueryResponse,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub opcode: NbnsOpCode,
+    pub authoritative: bool,
+    pub truncated: bool,
+    pub recursion_desired: bool,
+    pub recursion_available: bool,
+    /// Sent as a broadcast rather than to a name server
+    pub broadcast: bool,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub rcode: NbnsResponseCode,
+    pub qdcount: u16,
+    pub ancount: u16,
+    pub nscount: u16,
+    pub arcount: u16,
+}
+
+impl NbnsHeader {
+    #[allow(clippy::type_complexity)]
+    pub fn parse(input: &[u8]) -> Result<(&[u8], (NbnsHeader, Flags<ErrorFlags>))> {
+        let mut error_flags = ErrorFlags::none();
+
+        let (input, transaction_id) = be_u16(input)?;
+        let (input, flags) = be_u16(input)?;
+        let wrapped_flags = Flags::<nbns_header_masks>::from_bits(flags);
+        let query_response = if wrapped_flags.intersects(nbns_header_masks::RESPONSE) {
+            QueryResponse::Response
+        } else {
+            QueryResponse::Query
+        };
+        let opcode = NbnsOpCode::from_raw((wrapped_flags & nbns_header_masks::OPCODE).bits() >> 11);
+        if opcode == NbnsOpCode::UNKNOWN {
+            error_flags |= ErrorFlags::UnknownOpcode;
+        }
+        let rcode = NbnsResponseCode::from_raw((wrapped_flags & nbns_header_masks::RCODE).bits());
+        if rcode == NbnsResponseCode::UNKNOWN {
+            error_flags |= ErrorFlags::UnknownRcode;
+        }
+        let (input, qdcount) = be_u16(input)?;
+        let (input, ancount) = be_u16(input)?;
+        let (input, nscount) = be_u16(input)?;
+        let (input, arcount) = be_u16(input)?;
+
+        Ok((
+            input,
+            (
+                NbnsHeader {
+                    transaction_id,
+                    flags,
+                    query_response,
+                    opcode,
+                    authoritative: wrapped_flags.intersects(nbns_header_masks::AUTH),
+                    truncated: wrapped_flags.intersects(nbns_header_masks::TRUNC),
+                    recursion_desired: wrapped_flags.intersects(nbns_header_masks::RECUR_DESIRED),
+                    recursion_available: wrapped_flags.intersects(nbns_header_masks::RECUR_AVAIL),
+                    broadcast: wrapped_flags.intersects(nbns_header_masks::BROADCAST),
+                    rcode,
+                    qdcount,
+                    ancount,
+                    nscount,
+                    arcount,
+                },
+                error_flags,
+            ),
+        ))
+    }
+}
+
+/// A decoded NetBIOS name
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NetbiosName {
+    /// Name without its padding. Holds the raw first label if it is not a valid
+    /// encoded name.
+    pub name: Vec<u8>,
+    /// Service identifier, the 16th byte of the name
+    pub suffix: u8,
+    /// Dotted scope following the name, usually empty
+    pub scope: Vec<u8>,
+}
+
+impl NetbiosName {
+    /// Split a 16 byte name into its trimmed name and suffix
+    fn from_bytes(name: &[u8]) -> (Vec<u8>, u8) {
+        let (padded, suffix) = name.split_at(NAME_LEN - 1);
+        let length = padded
+            .iter()
+            .rposition(|c| *c != b' ' && *c != b'\0')
+            .map_or(0, |position| position + 1);
+        (padded[..length].to_vec(), suffix[0])
+    }
+
+    /// Reverse the first-level encoding of RFC1001 section 14.1
+    fn decode(encoded: &[u8]) -> Option<(Vec<u8>, u8)> {
+        if encoded.len() != ENCODED_NAME_LEN || !encoded.iter().all(|c| (b'A'..=b'P').contains(c)) {
+            return None;
+        }
+        let decoded: Vec<u8> = encoded
+            .chunks_exact(2)
+            .map(|pair| (pair[0] - b'A') << 4 | (pair[1] - b'A'))
+            .collect();
+        Some(NetbiosName::from_bytes(&decoded))
+    }
+
+    pub fn parse<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (NetbiosName, Flags<ErrorFlags>)> {
+        let (input, (name, mut error_flags)) = DnsName::parse(reference_bytes)(input)?;
+        let labels = name.labels();
+        let encoded = labels.first().map_or(&[][..], |label| label.as_slice());
+        let (name, suffix) = match NetbiosName::decode(encoded) {
+            Some(decoded) => decoded,
+            None => {
+                error_flags |= ErrorFlags::InvalidNetbiosName;
+                (encoded.to_vec(), 0)
+            }
+        };
+        let scope = labels.get(1..).unwrap_or(&[]).join(&b'.');
+        Ok((
+            input,
+            (
+                NetbiosName {
+                    name,
+                    suffix,
+                    scope,
+                },
+                error_flags,
+            ),
+        ))
+    }
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NbnsQuestion {
+    pub name: NetbiosName,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub record_type: NbnsRecordType,
+    pub record_type_raw: u16,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub record_class: RecordClass,
+    pub record_class_raw: u16,
+}
+
+impl NbnsQuestion {
+    fn parse<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (NbnsQuestion, Flags<ErrorFlags>)> {
+        let (input, (name, mut error_flags)) = NetbiosName::parse(input, reference_bytes)?;
+        let (input, record_type_raw) = be_u16(input)?;
+        let record_type = NbnsRecordType::from_raw(record_type_raw);
+        if record_type == NbnsRecordType::UNKNOWN {
+            error_flags |= ErrorFlags::UnknownRtype;
+        }
+        let (input, record_class_raw) = be_u16(input)?;
+        let record_class = RecordClass::from_raw(record_class_raw);
+        if record_class == RecordClass::UNKNOWN {
+            error_flags |= ErrorFlags::UnknownRclass;
+        }
+        Ok((
+            input,
+            (
+                NbnsQuestion {
+                    name,
+                    record_type,
+                    record_type_raw,
+                    record_class,
+                    record_class_raw,
+                },
+                error_flags,
+            ),
+        ))
+    }
+}
+
+/// An address owning a name
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NbAddress {
+    /// Group name rather than a unique name
+    pub group: bool,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub node_type: NodeType,
+    pub address: Vec<u8>,
+}
+
+/// A name registered on the answering node
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NodeName {
+    pub name: Vec<u8>,
+    pub suffix: u8,
+    pub group: bool,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub node_type: NodeType,
+    /// Being deregistered
+    pub deregister: bool,
+    pub conflict: bool,
+    pub active: bool,
+    pub permanent: bool,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NodeStatus {
+    pub names: Vec<NodeName>,
+    /// MAC address of the answering node
+    pub unit_id: Vec<u8>,
+    /// Remaining statistics, mostly left zero by current implementations
+    pub statistics: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub enum NbnsRData {
+    A(Vec<u8>),
+    NS(Vec<u8>),
+    NULL(Vec<u8>),
+    NB(Vec<NbAddress>),
+    NBSTAT(NodeStatus),
+    /// Unknown types and malformed data, including the header flags echoed by
+    /// WACK responses
+    UNKNOWN(Vec<u8>),
+}
+
+impl NbnsRData {
+    fn parse<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+        rtype: NbnsRecordType,
+    ) -> IResult<'a, (NbnsRData, Flags<ErrorFlags>)> {
+        match rtype {
+            NbnsRecordType::A => Ok((&[], (NbnsRData::A(input.to_vec()), ErrorFlags::none()))),
+            NbnsRecordType::NS => {
+                let (input, (name, error_flags)) = DnsName::parse(reference_bytes)(input)?;
+                let name = name.labels().join(&b'.');
+                Ok((input, (NbnsRData::NS(name), error_flags)))
+            }
+            NbnsRecordType::NULL => {
+                Ok((&[], (NbnsRData::NULL(input.to_vec()), ErrorFlags::none())))
+            }
+            NbnsRecordType::NB => Ok((&[], (NbnsRData::parse_nb(input), ErrorFlags::none()))),
+            NbnsRecordType::NBSTAT => {
+                Ok((&[], (NbnsRData::parse_nbstat(input), ErrorFlags::none())))
+            }
+            NbnsRecordType::UNKNOWN => Ok((
+                &[],
+                (NbnsRData::UNKNOWN(input.to_vec()), ErrorFlags::none()),
+            )),
+        }
+    }
+
+    fn parse_nb(input: &[u8]) -> NbnsRData {
+        let entries = input.chunks_exact(NB_ADDRESS_LEN);
+        if !entries.remainder().is_empty() {
+            return NbnsRData::UNKNOWN(input.to_vec());
+        }
+        NbnsRData::NB(
+            entries
+                .map(|entry| {
+                    let flags = Flags::<name_flags_masks>::from_bits(u16::from_be_bytes([
+                        entry[0], entry[1],
+                    ]));
+                    NbAddress {
+                        group: flags.intersects(name_flags_masks::GROUP),
+                        node_type: NodeType::from_flags(flags),
+                        address: entry[2..].to_vec(),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn parse_nbstat(input: &[u8]) -> NbnsRData {
+        let (count, rest) = match input.split_first() {
+            Some((count, rest)) => (usize::from(*count), rest),
+            None => return NbnsRData::UNKNOWN(input.to_vec()),
+        };
+        let names_len = count * (NAME_LEN + 2);
+        if rest.len() < names_len + UNIT_ID_LEN {
+            return NbnsRData::UNKNOWN(input.to_vec());
+        }
+        let (names, statistics) = rest.split_at(names_len);
+        let (unit_id, statistics) = statistics.split_at(UNIT_ID_LEN);
+        NbnsRData::NBSTAT(NodeStatus {
+            names: names
+                .chunks_exact(NAME_LEN + 2)
+                .map(|entry| {
+                    let (name, suffix) = NetbiosName::from_bytes(&entry[..NAME_LEN]);
+                    let flags = Flags::<name_flags_masks>::from_bits(u16::from_be_bytes([
+                        entry[NAME_LEN],
+                        entry[NAME_LEN + 1],
+                    ]));
+                    NodeName {
+                        name,
+                        suffix,
+                        group: flags.intersects(name_flags_masks::GROUP),
+                        node_type: NodeType::from_flags(flags),
+                        deregister: flags.intersects(name_flags_masks::DEREGISTER),
+                        conflict: flags.intersects(name_flags_masks::CONFLICT),
+                        active: flags.intersects(name_flags_masks::ACTIVE),
+                        permanent: flags.intersects(name_flags_masks::PERMANENT),
+                    }
+                })
+                .collect(),
+            unit_id: unit_id.to_vec(),
+            statistics: statistics.to_vec(),
+        })
+    }
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NbnsRecord {
+    pub name: NetbiosName,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub rtype: NbnsRecordType,
+    pub rtype_raw: u16,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub rclass: RecordClass,
+    pub rclass_raw: u16,
+    pub ttl: u32,
+    pub data: NbnsRData,
+}
+
+impl NbnsRecord {
+    fn parse<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (NbnsRecord, Flags<ErrorFlags>)> {
+        let (input, (name, mut error_flags)) = NetbiosName::parse(input, reference_bytes)?;
+        let (input, rtype_raw) = be_u16(input)?;
+        let rtype = NbnsRecordType::from_raw(rtype_raw);
+        if rtype == NbnsRecordType::UNKNOWN {
+            error_flags |= ErrorFlags::UnknownRtype;
+        }
+        let (input, rclass_raw) = be_u16(input)?;
+        let rclass = RecordClass::from_raw(rclass_raw);
+        if rclass == RecordClass::UNKNOWN {
+            error_flags |= ErrorFlags::UnknownRclass;
+        }
+        let (input, ttl) = be_u32(input)?;
+        let (input, data_len) = be_u16(input)?;
+        let (input, local_data) = take(data_len)(input)?;
+        let (_, (data, inner_error_flags)) = NbnsRData::parse(local_data, reference_bytes, rtype)?;
+        error_flags |= inner_error_flags;
+        Ok((
+            input,
+            (
+                NbnsRecord {
+                    name,
+                    rtype,
+                    rtype_raw,
+                    rclass,
+                    rclass_raw,
+                    ttl,
+                    data,
+                },
+                error_flags,
+            ),
+        ))
+    }
+
+    fn parse_records<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+        count: usize,
+    ) -> IResult<'a, (Vec<NbnsRecord>, Flags<ErrorFlags>)> {
+        let mut error_flags = ErrorFlags::none();
+        let (input, records) = custom_count(
+            |input, reference_bytes| {
+                let (input, (record, inner_error_flags)) =
+                    NbnsRecord::parse(input, reference_bytes)?;
+                error_flags |= inner_error_flags;
+                Ok((input, record))
+            },
+            count,
+        )(input, reference_bytes)?;
+        Ok((input, (records, error_flags)))
+    }
+}
+
+/// Breakdown of the parsed NetBIOS name service bytes
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct NbnsMessage {
+    pub header: NbnsHeader,
+    pub questions: Vec<NbnsQuestion>,
+    pub answers: Vec<NbnsRecord>,
+    pub authorities: Vec<NbnsRecord>,
+    pub additionals: Vec<NbnsRecord>,
+    #[cfg_attr(feature = "ffi", sawp_ffi(flag = "u32"))]
+    pub error_flags: Flags<ErrorFlags>,
+}
+
+#[derive(Debug)]
+pub struct Nbns {}
+
+impl<'a> Protocol<'a> for Nbns {
+    type Message = NbnsMessage;
+
+    fn name() -> &'static str {
+        "nbns"
+    }
+}
+
+impl<'a> Probe<'a> for Nbns {}
+
+/// Returns ErrorKind::Incomplete if more data is needed.
+impl<'a> Parse<'a> for Nbns {
+    fn parse(
+        &self,
+        input: &'a [u8],
+        _direction: Direction,
+    ) -> Result<(&'a [u8], Option<Self::Message>)> {
+        let reference_bytes = input;
+
+        let (input, (header, mut error_flags)) = NbnsHeader::parse(input)?;
+
+        let mut inner_error_flags = ErrorFlags::none();
+        let (input, questions) = custom_count(
+            |input, reference_bytes| {
+                let (input, (question, flags)) = NbnsQuestion::parse(input, reference_bytes)?;
+                inner_error_flags |= flags;
+                Ok((input, question))
+            },
+            header.qdcount.into(),
+        )(input, reference_bytes)?;
+        error_flags |= inner_error_flags;
+
+        let (input, (answers, inner_error_flags)) =
+            NbnsRecord::parse_records(input, reference_bytes, header.ancount.into())?;
+        error_flags |= inner_error_flags;
+        let (input, (authorities, inner_error_flags)) =
+            NbnsRecord::parse_records(input, reference_bytes, header.nscount.into())?;
+        error_flags |= inner_error_flags;
+        let (input, (additionals, inner_error_flags)) =
+            NbnsRecord::parse_records(input, reference_bytes, header.arcount.into())?;
+        error_flags |= inner_error_flags;
+
+        Ok((
+            input,
+            Some(NbnsMessage {
+                header,
+                questions,
+                answers,
+                authorities,
+                additionals,
+                error_flags,
+            }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+    use sawp::error::Error;
+
+    fn header(flags: u16, opcode: NbnsOpCode, counts: [u16; 4]) -> NbnsHeader {
+        let wrapped_flags = Flags::<nbns_header_masks>::from_bits(flags);
+        NbnsHeader {
+            transaction_id: 0x8a1c,
+            flags,
+            query_response: if wrapped_flags.intersects(nbns_header_masks::RESPONSE) {
+                QueryResponse::Response
+            } else {
+                QueryResponse::Query
+            },
+            opcode,
+            authoritative: wrapped_flags.intersects(nbns_header_masks::AUTH),
+            truncated: false,
+            recursion_desired: wrapped_flags.intersects(nbns_header_masks::RECUR_DESIRED),
+            recursion_available: false,
+            broadcast: wrapped_flags.intersects(nbns_header_masks::BROADCAST),
+            rcode: NbnsResponseCode::NOERROR,
+            qdcount: counts[0],
+            ancount: counts[1],
+            nscount: counts[2],
+            arcount: counts[3],
+        }
+    }
+
+    fn wpad() -> NetbiosName {
+        NetbiosName {
+            name: b"WPAD".to_vec(),
+            suffix: 0x00,
+            scope: vec![],
+        }
+    }
+
+    #[rstest(
+        input,
+        expected,
+        case::name_query_broadcast(
+            &[
+                0x8a, 0x1c, // Transaction ID: 0x8a1c
+                0x01, 0x10, // Flags: query, recursion desired, broadcast
+                0x00, 0x01, // QDCOUNT: 1
+                0x00, 0x00, // ANCOUNT: 0
+                0x00, 0x00, // NSCOUNT: 0
+                0x00, 0x00, // ARCOUNT: 0
+                0x20, 0x46, 0x48, 0x46, 0x41, 0x45, 0x42, 0x45, 0x45, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43,
+                0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x41,
+                0x41, 0x00, // WPAD<00>
+                0x00, 0x20, // Type: NB
+                0x00, 0x01, // Class: IN
+            ],
+            Ok((
+                b"".as_ref(),
+                Some(NbnsMessage {
+                    header: header(0x0110, NbnsOpCode::QUERY, [1, 0, 0, 0]),
+                    questions: vec![NbnsQuestion {
+                        name: wpad(),
+                        record_type: NbnsRecordType::NB,
+                        record_type_raw: 0x20,
+                        record_class: RecordClass::IN,
+                        record_class_raw: 1,
+                    }],
+                    answers: vec![],
+                    authorities: vec![],
+                    additionals: vec![],
+                    error_flags: ErrorFlags::none(),
+                })
+            ))
+        ),
+        case::name_query_response(
+            &[
+                0x8a, 0x1c, // Transaction ID: 0x8a1c
+                0x85, 0x00, // Flags: response, authoritative, recursion desired
+                0x00, 0x00, // QDCOUNT: 0
+                0x00, 0x01, // ANCOUNT: 1
+                0x00, 0x00, // NSCOUNT: 0
+                0x00, 0x00, // ARCOUNT: 0
+                0x20, 0x46, 0x48, 0x46, 0x41, 0x45, 0x42, 0x45, 0x45, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43,
+                0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x41,
+                0x41, 0x00, // WPAD<00>
+                0x00, 0x20, // Type: NB
+                0x00, 0x01, // Class: IN
+                0x00, 0x04, 0x93, 0xe0, // TTL: 300000
+                0x00, 0x06, // Data length: 6
+                0x00, 0x00, // Flags: unique, B node
+                0xc0, 0xa8, 0x01, 0x42, // 192.168.1.66
+            ],
+            Ok((
+                b"".as_ref(),
+                Some(NbnsMessage {
+                    header: header(0x8500, NbnsOpCode::QUERY, [0, 1, 0, 0]),
+                    questions: vec![],
+                    answers: vec![NbnsRecord {
+                        name: wpad(),
+                        rtype: NbnsRecordType::NB,
+                        rtype_raw: 0x20,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 300_000,
+                        data: NbnsRData::NB(vec![NbAddress {
+                            group: false,
+                            node_type: NodeType::B,
+                            address: vec![192, 168, 1, 66],
+                        }]),
+                    }],
+                    authorities: vec![],
+                    additionals: vec![],
+                    error_flags: ErrorFlags::none(),
+                })
+            ))
+        ),
+        case::node_status_response(
+            &[
+                0x8a, 0x1c, // Transaction ID: 0x8a1c
+                0x84, 0x00, // Flags: response, authoritative
+                0x00, 0x00, // QDCOUNT: 0
+                0x00, 0x01, // ANCOUNT: 1
+                0x00, 0x00, // NSCOUNT: 0
+                0x00, 0x00, // ARCOUNT: 0
+                0x20, 0x43, 0x4b, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41,
+                0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41,
+                0x41, 0x00, // *<00>
+                0x00, 0x21, // Type: NBSTAT
+                0x00, 0x01, // Class: IN
+                0x00, 0x00, 0x00, 0x00, // TTL: 0
+                0x00, 0x2f, // Data length: 47
+                0x02, // Number of names: 2
+                0x44, 0x45, 0x53, 0x4b, 0x54, 0x4f, 0x50, 0x2d, 0x31, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00, // DESKTOP-1<00>
+                0x04, 0x00, // Flags: unique, B node, active
+                0x57, 0x4f, 0x52, 0x4b, 0x47, 0x52, 0x4f, 0x55, 0x50, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00, // WORKGROUP<00>
+                0x84, 0x00, // Flags: group, B node, active
+                0x00, 0x0c, 0x29, 0x12, 0x34, 0x56, // Unit ID: 00:0c:29:12:34:56
+                0x00, 0x00, 0x00, 0x00, // Statistics
+            ],
+            Ok((
+                b"".as_ref(),
+                Some(NbnsMessage {
+                    header: header(0x8400, NbnsOpCode::QUERY, [0, 1, 0, 0]),
+                    questions: vec![],
+                    answers: vec![NbnsRecord {
+                        name: NetbiosName {
+                            name: b"*".to_vec(),
+                            suffix: 0x00,
+                            scope: vec![],
+                        },
+                        rtype: NbnsRecordType::NBSTAT,
+                        rtype_raw: 0x21,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 0,
+                        data: NbnsRData::NBSTAT(NodeStatus {
+                            names: vec![
+                                NodeName {
+                                    name: b"DESKTOP-1".to_vec(),
+                                    suffix: 0x00,
+                                    group: false,
+                                    node_type: NodeType::B,
+                                    deregister: false,
+                                    conflict: false,
+                                    active: true,
+                                    permanent: false,
+                                },
+                                NodeName {
+                                    name: b"WORKGROUP".to_vec(),
+                                    suffix: 0x00,
+                                    group: true,
+                                    node_type: NodeType::B,
+                                    deregister: false,
+                                    conflict: false,
+                                    active: true,
+                                    permanent: false,
+                                },
+                            ],
+                            unit_id: vec![0x00, 0x0c, 0x29, 0x12, 0x34, 0x56],
+                            statistics: vec![0x00, 0x00, 0x00, 0x00],
+                        }),
+                    }],
+                    authorities: vec![],
+                    additionals: vec![],
+                    error_flags: ErrorFlags::none(),
+                })
+            ))
+        ),
+        case::registration_request(
+            &[
+                0x8a, 0x1c, // Transaction ID: 0x8a1c
+                0x29, 0x10, // Flags: registration, recursion desired, broadcast
+                0x00, 0x01, // QDCOUNT: 1
+                0x00, 0x00, // ANCOUNT: 0
+                0x00, 0x00, // NSCOUNT: 0
+                0x00, 0x01, // ARCOUNT: 1
+                0x20, 0x46, 0x48, 0x46, 0x41, 0x45, 0x42, 0x45, 0x45, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43,
+                0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x41,
+                0x41, 0x00, // WPAD<00>
+                0x00, 0x20, // Type: NB
+                0x00, 0x01, // Class: IN
+                0xc0, 0x0c, // WPAD<00>
+                0x00, 0x20, // Type: NB
+                0x00, 0x01, // Class: IN
+                0x00, 0x04, 0x93, 0xe0, // TTL: 300000
+                0x00, 0x06, // Data length: 6
+                0x60, 0x00, // Flags: unique, H node
+                0xc0, 0xa8, 0x01, 0x0a, // 192.168.1.10
+            ],
+            Ok((
+                b"".as_ref(),
+                Some(NbnsMessage {
+                    header: header(0x2910, NbnsOpCode::REGISTRATION, [1, 0, 0, 1]),
+                    questions: vec![NbnsQuestion {
+                        name: wpad(),
+                        record_type: NbnsRecordType::NB,
+                        record_type_raw: 0x20,
+                        record_class: RecordClass::IN,
+                        record_class_raw: 1,
+                    }],
+                    answers: vec![],
+                    authorities: vec![],
+                    additionals: vec![NbnsRecord {
+                        name: wpad(),
+                        rtype: NbnsRecordType::NB,
+                        rtype_raw: 0x20,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 300_000,
+                        data: NbnsRData::NB(vec![NbAddress {
+                            group: false,
+                            node_type: NodeType::H,
+                            address: vec![192, 168, 1, 10],
+                        }]),
+                    }],
+                    error_flags: ErrorFlags::none(),
+                })
+            ))
+        ),
+        case::wait_for_acknowledgement(
+            &[
+                0x8a, 0x1c, // Transaction ID: 0x8a1c
+                0xbc, 0x00, // Flags: response, WACK, authoritative
+                0x00, 0x00, // QDCOUNT: 0
+                0x00, 0x01, // ANCOUNT: 1
+                0x00, 0x00, // NSCOUNT: 0
+                0x00, 0x00, // ARCOUNT: 0
+                0x20, 0x46, 0x48, 0x46, 0x41, 0x45, 0x42, 0x45, 0x45, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43,
+                0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x41,
+                0x41, 0x00, // WPAD<00>
+                0x00, 0x20, // Type: NB
+                0x00, 0x01, // Class: IN
+                0x00, 0x00, 0x00, 0x02, // TTL: 2
+                0x00, 0x02, // Data length: 2
+                0x29, 0x10, // Request flags
+            ],
+            Ok((
+                b"".as_ref(),
+                Some(NbnsMessage {
+                    header: header(0xbc00, NbnsOpCode::WACK, [0, 1, 0, 0]),
+                    questions: vec![],
+                    answers: vec![NbnsRecord {
+                        name: wpad(),
+                        rtype: NbnsRecordType::NB,
+                        rtype_raw: 0x20,
+                        rclass: RecordClass::IN,
+                        rclass_raw: 1,
+                        ttl: 2,
+                        data: NbnsRData::UNKNOWN(vec![0x29, 0x10]),
+                    }],
+                    authorities: vec![],
+                    additionals: vec![],
+                    error_flags: ErrorFlags::none(),
+                })
+            ))
+        ),
+        case::invalid_name(
+            &[
+                0x8a, 0x1c, // Transaction ID: 0x8a1c
+                0x01, 0x10, // Flags: query, recursion desired, broadcast
+                0x00, 0x01, // QDCOUNT: 1
+                0x00, 0x00, // ANCOUNT: 0
+                0x00, 0x00, // NSCOUNT: 0
+                0x00, 0x00, // ARCOUNT: 0
+                0x04, 0x57, 0x50, 0x41, 0x44, 0x00, // WPAD, not encoded
+                0x00, 0x20, // Type: NB
+                0x00, 0x01, // Class: IN
+            ],
+            Ok((
+                b"".as_ref(),
+                Some(NbnsMessage {
+                    header: header(0x0110, NbnsOpCode::QUERY, [1, 0, 0, 0]),
+                    questions: vec![NbnsQuestion {
+                        name: wpad(),
+                        record_type: NbnsRecordType::NB,
+                        record_type_raw: 0x20,
+                        record_class: RecordClass::IN,
+                        record_class_raw: 1,
+                    }],
+                    answers: vec![],
+                    authorities: vec![],
+                    additionals: vec![],
+                    error_flags: ErrorFlags::InvalidNetbiosName.into(),
+                })
+            ))
+        ),
+        case::truncated_record(
+            &[
+                0x8a, 0x1c, // Transaction ID: 0x8a1c
+                0x85, 0x00, // Flags: response, authoritative, recursion desired
+                0x00, 0x00, // QDCOUNT: 0
+                0x00, 0x01, // ANCOUNT: 1
+                0x00, 0x00, // NSCOUNT: 0
+                0x00, 0x00, // ARCOUNT: 0
+                0x20, 0x46, 0x48, 0x46, 0x41, 0x45, 0x42, 0x45, 0x45, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43,
+                0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x43, 0x41, 0x41,
+                0x41, 0x00, // WPAD<00>
+                0x00, 0x20, // Type: NB
+                0x00, 0x01, // Class: IN
+                0x00, 0x04, 0x93, 0xe0, // TTL: 300000
+                0x00, 0x06, // Data length: 6
+                0x00, 0x00, // Flags: unique, B node
+            ],
+            Err(Error::incomplete_needed(6))
+        ),
+        case::empty(&[], Err(Error::incomplete_needed(2))),
+    )]
+    fn parse(input: &[u8], expected: Result<(&[u8], Option<NbnsMessage>)>) {
+        let nbns = Nbns {};
+        assert_eq!(nbns.parse(input, Direction::Unknown), expected);
+    }
+
+    #[rstest(
+        encoded,
+        expected,
+        case::workgroup(
+            b"FHEPFCELEHFCEPFFFACACACACACACABN",
+            Some((b"WORKGROUP".to_vec(), 0x1d))
+        ),
+        case::lower_case(b"fhepfcelehfcepfffacacacacacacabn", None),
+        case::out_of_range(b"FHEPFCELEHFCEPFFFACACACACACACABZ", None),
+        case::short(b"FHEPFCELEHFCEPFFFACACACACACACA", None),
+    )]
+    fn decode(encoded: &[u8], expected: Option<(Vec<u8>, u8)>) {
+        assert_eq!(expected, NetbiosName::decode(encoded));
+    }
+}
@@ -1,3 +1,6 @@
+use alloc::vec::Vec;
+
+use nom::bytes::streaming::take;
 use nom::number::streaming::be_u16;
 
 use sawp_flags::{Flag, Flags};
@@ -8,9 +11,16 @@ use crate::{custom_count, ErrorFlags, IResult, Name};
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Length of the type and class following the name of a question
+const QUESTION_FIXED_LEN: usize = 4;
+
 /// A parsed DNS question
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Question {
     pub name: Vec<u8>,
@@ -23,7 +33,13 @@ pub struct Question {
 }
 
 impl Question {
-    fn parse<'a>(
+    /// mDNS unicast-response bit, set when the querier accepts a unicast reply.
+    /// Only meaningful for messages parsed with [`crate::Mdns`].
+    pub fn unicast_response(&self) -> bool {
+        self.record_class_raw & crate::MDNS_CLASS_FLAG != 0
+    }
+
+    pub(crate) fn parse<'a>(
         input: &'a [u8],
         reference_bytes: &'a [u8],
     ) -> IResult<'a, (Question, Flags<ErrorFlags>)> {
@@ -55,6 +71,13 @@ impl Question {
         ))
     }
 
+    /// Skip over a question without parsing it
+    pub(crate) fn skip(input: &[u8]) -> IResult<()> {
+        let (input, _) = Name::skip(input)?;
+        let (input, _) = take(QUESTION_FIXED_LEN)(input)?;
+        Ok((input, ()))
+    }
+
     pub fn parse_questions<'a>(
         input: &'a [u8],
         reference_bytes: &'a [u8],
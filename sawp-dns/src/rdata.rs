@@ -1,22 +1,64 @@
 #![allow(clippy::upper_case_acronyms)]
 
+use alloc::vec::Vec;
+
 use nom::bytes::streaming::take;
 use nom::number::streaming::{be_u16, be_u32, be_u8};
 
-use sawp_flags::{Flag, Flags};
+use sawp_flags::{BitFlags, Flag, Flags};
 
 use byteorder::{BigEndian, ByteOrder};
 
 use crate::edns::EdnsOption;
-use crate::enums::{RecordType, SshfpAlgorithm, SshfpFingerprint, TSigResponseCode, TkeyMode};
+use crate::enums::{
+    CertType, DigestType, DnssecAlgorithm, Nsec3HashAlgorithm, RecordType, SshfpAlgorithm,
+    SshfpFingerprint, TSigResponseCode, TkeyMode, TlsaCertUsage, TlsaMatchingType, TlsaSelector,
+    ZonemdHashAlgorithm, ZonemdScheme,
+};
 
+use crate::svcb::SvcParam;
 use crate::{ErrorFlags, IResult, Name};
 use nom::combinator::rest;
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Masks for extracting DNSKEY flags
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, BitFlags)]
+#[repr(u16)]
+pub enum dnskey_masks {
+    ZONE = 0b0000_0001_0000_0000,
+    REVOKE = 0b0000_0000_1000_0000,
+    SEP = 0b0000_0000_0000_0001,
+}
+
+/// Masks for extracting NSEC3 and NSEC3PARAM flags
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, BitFlags)]
+#[repr(u8)]
+pub enum nsec3_masks {
+    OPT_OUT = 0b0000_0001,
+}
+
+/// Largest bitmap length of a single NSEC type bitmap window
+const MAX_TYPE_BITMAP_LEN: usize = 32;
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataAFSDB {
+    /// 1 for an AFS cell database server, 2 for a DCE authenticated name server
+    pub subtype: u16,
+    pub hostname: Vec<u8>,
+}
+
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct RDataCAA {
     pub flags: u8,
@@ -26,6 +68,148 @@ pub struct RDataCAA {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataCERT {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub cert_type: CertType,
+    pub key_tag: u16,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub algorithm: DnssecAlgorithm,
+    pub certificate: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataDNSKEY {
+    /// Raw DNSKEY flags
+    pub flags: u16,
+    /// Key is a DNS zone key
+    pub zone_key: bool,
+    /// Key has been revoked
+    pub revoked: bool,
+    /// Key is a secure entry point (key signing key)
+    pub secure_entry_point: bool,
+    /// Must be 3
+    pub protocol: u8,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub algorithm: DnssecAlgorithm,
+    pub public_key: Vec<u8>,
+    /// Key tag referenced by RRSIG and DS records, computed from the RDATA
+    pub key_tag: u16,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataDS {
+    /// Key tag of the referenced DNSKEY
+    pub key_tag: u16,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub algorithm: DnssecAlgorithm,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub digest_type: DigestType,
+    pub digest: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataHINFO {
+    pub cpu: Vec<u8>,
+    pub os: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataLOC {
+    /// Must be zero, the remaining fields are only defined for version zero
+    pub version: u8,
+    /// Diameter of the enclosing sphere, encoded as a base and power of ten in centimeters
+    pub size: u8,
+    /// Horizontal precision, encoded like size
+    pub horizontal_precision: u8,
+    /// Vertical precision, encoded like size
+    pub vertical_precision: u8,
+    /// Thousandths of a second of arc, offset so 2^31 is the equator
+    pub latitude: u32,
+    /// Thousandths of a second of arc, offset so 2^31 is the prime meridian
+    pub longitude: u32,
+    /// Centimeters from a base 100,000m below the WGS 84 reference spheroid
+    pub altitude: u32,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataNAPTR {
+    pub order: u16,
+    pub preference: u16,
+    pub flags: Vec<u8>,
+    pub services: Vec<u8>,
+    pub regexp: Vec<u8>,
+    pub replacement: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataNSEC {
+    /// Next owner name in the canonical ordering of the zone
+    pub next_domain_name: Vec<u8>,
+    /// Record types present at the owner name
+    pub types: Vec<RecordType>,
+    /// Raw type numbers of `types`, keeping the value of unknown types
+    pub types_raw: Vec<u16>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataNSEC3 {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub hash_algorithm: Nsec3HashAlgorithm,
+    /// Raw NSEC3 flags
+    pub flags: u8,
+    /// Insecure delegations may be covered by this record
+    pub opt_out: bool,
+    /// Additional hash iterations
+    pub iterations: u16,
+    pub salt: Vec<u8>,
+    /// Next hashed owner name in binary form
+    pub next_hashed_owner_name: Vec<u8>,
+    /// Record types present at the original owner name
+    pub types: Vec<RecordType>,
+    /// Raw type numbers of `types`, keeping the value of unknown types
+    pub types_raw: Vec<u16>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataNSEC3PARAM {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub hash_algorithm: Nsec3HashAlgorithm,
+    /// Raw NSEC3 flags, zero in zone published records
+    pub flags: u8,
+    /// Additional hash iterations
+    pub iterations: u16,
+    pub salt: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct RDataOPT {
     /// Requestor's UDP payload size
@@ -39,6 +223,55 @@ pub struct RDataOPT {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataRP {
+    /// Mailbox of the responsible person
+    pub mailbox: Vec<u8>,
+    /// Name with TXT records about the responsible person
+    pub txt_domain: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataRRSIG {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    /// Type of the RRset covered by this signature
+    pub type_covered: RecordType,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub algorithm: DnssecAlgorithm,
+    /// Number of labels in the original owner name
+    pub labels: u8,
+    /// TTL of the covered RRset as it appears in the zone
+    pub original_ttl: u32,
+    /// Signature expires - seconds since epoch
+    pub expiration: u32,
+    /// Signature incepted - seconds since epoch
+    pub inception: u32,
+    /// Key tag of the DNSKEY which validates this signature
+    pub key_tag: u16,
+    /// Zone of the RRset covered by this signature
+    pub signer_name: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataSVCB {
+    /// Zero for AliasMode, otherwise the ServiceMode preference
+    pub priority: u16,
+    /// Alias or alternative endpoint, the root name means the owner name
+    pub target_name: Vec<u8>,
+    pub params: Vec<SvcParam>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct RDataSoa {
     /// Primary NS for this zone
@@ -59,6 +292,7 @@ pub struct RDataSoa {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct RDataSSHFP {
     #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
@@ -71,6 +305,7 @@ pub struct RDataSSHFP {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct RDataSRV {
     pub priority: u16,
@@ -81,6 +316,21 @@ pub struct RDataSRV {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataTLSA {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub cert_usage: TlsaCertUsage,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub selector: TlsaSelector,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub matching_type: TlsaMatchingType,
+    pub certificate_association_data: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct RDataTKEY {
     pub algorithm: Vec<u8>,
@@ -98,6 +348,7 @@ pub struct RDataTKEY {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct RDataTSIG {
     pub algorithm_name: Vec<u8>,
@@ -117,6 +368,31 @@ pub struct RDataTSIG {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataURI {
+    pub priority: u16,
+    pub weight: u16,
+    pub target: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct RDataZONEMD {
+    /// Serial of the zone SOA the digest was computed for
+    pub serial: u32,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub scheme: ZonemdScheme,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub hash_algorithm: ZonemdHashAlgorithm,
+    pub digest: Vec<u8>,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub enum RDataType {
     /// Addresses
@@ -124,6 +400,7 @@ pub enum RDataType {
     AAAA(Vec<u8>),
     /// Domain names
     CNAME(Vec<u8>),
+    DNAME(Vec<u8>),
     PTR(Vec<u8>),
     MX(Vec<u8>),
     NS(Vec<u8>),
@@ -131,11 +408,35 @@ pub enum RDataType {
     TXT(Vec<u8>),
     NUL(Vec<u8>),
     /// Multiple field records
+    AFSDB(RDataAFSDB),
     CAA(RDataCAA),
+    HINFO(RDataHINFO),
+    LOC(RDataLOC),
+    NAPTR(RDataNAPTR),
     OPT(RDataOPT),
+    RP(RDataRP),
     SOA(RDataSoa),
+    URI(RDataURI),
+    ZONEMD(RDataZONEMD),
+    /// Certificates and keys
+    CERT(RDataCERT),
+    OPENPGPKEY(Vec<u8>),
+    SMIMEA(RDataTLSA),
+    TLSA(RDataTLSA),
+    /// DNSSEC
+    CDNSKEY(RDataDNSKEY),
+    CDS(RDataDS),
+    DNSKEY(RDataDNSKEY),
+    DS(RDataDS),
+    NSEC(RDataNSEC),
+    NSEC3(RDataNSEC3),
+    NSEC3PARAM(RDataNSEC3PARAM),
+    RRSIG(RDataRRSIG),
     SRV(RDataSRV),
     SSHFP(RDataSSHFP),
+    /// Service binding
+    SVCB(RDataSVCB),
+    HTTPS(RDataSVCB),
     TKEY(RDataTKEY),
     TSIG(RDataTSIG),
     UNKNOWN(Vec<u8>),
@@ -152,23 +453,60 @@ impl RDataType {
                 .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
             RecordType::AAAA => RDataType::parse_rdata_aaaa(input)
                 .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
+            RecordType::HINFO => RDataType::parse_rdata_hinfo(input)
+                .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
+            RecordType::HTTPS => RDataType::parse_rdata_svcb(input, reference_bytes)
+                .map(|(input, (rdata, flags))| (input, (RDataType::HTTPS(rdata), flags))),
+            RecordType::AFSDB => RDataType::parse_rdata_afsdb(input, reference_bytes),
             RecordType::CAA => RDataType::parse_rdata_caa(input)
                 .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
+            RecordType::CERT => RDataType::parse_rdata_cert(input)
+                .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
+            RecordType::CDNSKEY => RDataType::parse_rdata_dnskey(input)
+                .map(|(input, rdata)| (input, (RDataType::CDNSKEY(rdata), ErrorFlags::none()))),
+            RecordType::CDS => RDataType::parse_rdata_ds(input)
+                .map(|(input, rdata)| (input, (RDataType::CDS(rdata), ErrorFlags::none()))),
             RecordType::CNAME => RDataType::parse_rdata_cname(input, reference_bytes),
+            RecordType::DNAME => RDataType::parse_rdata_dname(input, reference_bytes),
+            RecordType::DNSKEY => RDataType::parse_rdata_dnskey(input)
+                .map(|(input, rdata)| (input, (RDataType::DNSKEY(rdata), ErrorFlags::none()))),
+            RecordType::DS => RDataType::parse_rdata_ds(input)
+                .map(|(input, rdata)| (input, (RDataType::DS(rdata), ErrorFlags::none()))),
+            RecordType::LOC => RDataType::parse_rdata_loc(input)
+                .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
             RecordType::MX => RDataType::parse_rdata_mx(input, reference_bytes),
+            RecordType::NAPTR => RDataType::parse_rdata_naptr(input, reference_bytes),
             RecordType::NS => RDataType::parse_rdata_ns(input, reference_bytes),
+            RecordType::NSEC => RDataType::parse_rdata_nsec(input, reference_bytes),
+            RecordType::NSEC3 => RDataType::parse_rdata_nsec3(input),
+            RecordType::NSEC3PARAM => RDataType::parse_rdata_nsec3param(input)
+                .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
             RecordType::NUL => RDataType::parse_rdata_null(input)
                 .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
             RecordType::OPT => RDataType::parse_rdata_opt(input),
+            RecordType::OPENPGPKEY => RDataType::parse_rdata_openpgpkey(input)
+                .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
             RecordType::PTR => RDataType::parse_rdata_ptr(input, reference_bytes),
+            RecordType::RRSIG => RDataType::parse_rdata_rrsig(input, reference_bytes),
+            RecordType::RP => RDataType::parse_rdata_rp(input, reference_bytes),
             RecordType::SOA => RDataType::parse_rdata_soa(input, reference_bytes),
+            RecordType::SMIMEA => RDataType::parse_rdata_tlsa(input)
+                .map(|(input, rdata)| (input, (RDataType::SMIMEA(rdata), ErrorFlags::none()))),
             RecordType::SRV => RDataType::parse_rdata_srv(input, reference_bytes),
             RecordType::SSHFP => RDataType::parse_rdata_sshfp(input)
                 .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
+            RecordType::SVCB => RDataType::parse_rdata_svcb(input, reference_bytes)
+                .map(|(input, (rdata, flags))| (input, (RDataType::SVCB(rdata), flags))),
             RecordType::TKEY => RDataType::parse_rdata_tkey(input, reference_bytes),
+            RecordType::TLSA => RDataType::parse_rdata_tlsa(input)
+                .map(|(input, rdata)| (input, (RDataType::TLSA(rdata), ErrorFlags::none()))),
             RecordType::TSIG => RDataType::parse_rdata_tsig(input, reference_bytes),
             RecordType::TXT => RDataType::parse_rdata_txt(input)
                 .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
+            RecordType::URI => RDataType::parse_rdata_uri(input)
+                .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
+            RecordType::ZONEMD => RDataType::parse_rdata_zonemd(input)
+                .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
             _ => RDataType::parse_rdata_unknown(input)
                 .map(|(input, rdata)| (input, (rdata, ErrorFlags::none()))),
         }
@@ -200,6 +538,188 @@ impl RDataType {
         ))
     }
 
+    fn parse_rdata_dnskey(input: &[u8]) -> IResult<RDataDNSKEY> {
+        let rdata = input;
+        let (input, flags) = be_u16(input)?;
+        let (input, protocol) = be_u8(input)?;
+        let (input, algorithm) = be_u8(input)?;
+        let (input, public_key) = rest(input)?;
+
+        let algorithm = DnssecAlgorithm::from_raw(algorithm);
+        let wrapped_flags = Flags::<dnskey_masks>::from_bits(flags);
+        Ok((
+            input,
+            RDataDNSKEY {
+                flags,
+                zone_key: wrapped_flags.intersects(dnskey_masks::ZONE),
+                revoked: wrapped_flags.intersects(dnskey_masks::REVOKE),
+                secure_entry_point: wrapped_flags.intersects(dnskey_masks::SEP),
+                protocol,
+                algorithm,
+                public_key: public_key.to_vec(),
+                key_tag: RDataType::key_tag(rdata, algorithm),
+            },
+        ))
+    }
+
+    /// Key tag of a DNSKEY as described in RFC 4034 appendix B.
+    fn key_tag(rdata: &[u8], algorithm: DnssecAlgorithm) -> u16 {
+        if algorithm == DnssecAlgorithm::RSAMD5 {
+            // The key tag is the most significant 16 of the least significant
+            // 24 bits of the modulus, which ends the public key.
+            return match rdata.len() {
+                len if len >= 7 => BigEndian::read_u16(&rdata[len - 3..len - 1]),
+                _ => 0,
+            };
+        }
+
+        let mut accumulator: u32 = rdata
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| {
+                if i & 1 == 0 {
+                    u32::from(*byte) << 8
+                } else {
+                    u32::from(*byte)
+                }
+            })
+            .sum();
+        accumulator += (accumulator >> 16) & 0xffff;
+        (accumulator & 0xffff) as u16
+    }
+
+    fn parse_rdata_ds(input: &[u8]) -> IResult<RDataDS> {
+        let (input, key_tag) = be_u16(input)?;
+        let (input, algorithm) = be_u8(input)?;
+        let (input, digest_type) = be_u8(input)?;
+        let (input, digest) = rest(input)?;
+
+        Ok((
+            input,
+            RDataDS {
+                key_tag,
+                algorithm: DnssecAlgorithm::from_raw(algorithm),
+                digest_type: DigestType::from_raw(digest_type),
+                digest: digest.to_vec(),
+            },
+        ))
+    }
+
+    fn parse_rdata_nsec<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (RDataType, Flags<ErrorFlags>)> {
+        let (input, (next_domain_name, mut error_flags)) = Name::parse(reference_bytes)(input)?;
+        let (input, (types_raw, bitmap_error_flags)) = RDataType::parse_type_bitmaps(input)?;
+        error_flags |= bitmap_error_flags;
+
+        Ok((
+            input,
+            (
+                RDataType::NSEC(RDataNSEC {
+                    next_domain_name,
+                    types: RDataType::record_types(&types_raw),
+                    types_raw,
+                }),
+                error_flags,
+            ),
+        ))
+    }
+
+    fn parse_rdata_nsec3(input: &[u8]) -> IResult<(RDataType, Flags<ErrorFlags>)> {
+        let (input, hash_algorithm) = be_u8(input)?;
+        let (input, flags) = be_u8(input)?;
+        let (input, iterations) = be_u16(input)?;
+        let (input, salt_length) = be_u8(input)?;
+        let (input, salt) = take(salt_length)(input)?;
+        let (input, hash_length) = be_u8(input)?;
+        let (input, next_hashed_owner_name) = take(hash_length)(input)?;
+        let (input, (types_raw, error_flags)) = RDataType::parse_type_bitmaps(input)?;
+
+        Ok((
+            input,
+            (
+                RDataType::NSEC3(RDataNSEC3 {
+                    hash_algorithm: Nsec3HashAlgorithm::from_raw(hash_algorithm),
+                    flags,
+                    opt_out: Flags::<nsec3_masks>::from_bits(flags)
+                        .intersects(nsec3_masks::OPT_OUT),
+                    iterations,
+                    salt: salt.to_vec(),
+                    next_hashed_owner_name: next_hashed_owner_name.to_vec(),
+                    types: RDataType::record_types(&types_raw),
+                    types_raw,
+                }),
+                error_flags,
+            ),
+        ))
+    }
+
+    fn parse_rdata_nsec3param(input: &[u8]) -> IResult<RDataType> {
+        let (input, hash_algorithm) = be_u8(input)?;
+        let (input, flags) = be_u8(input)?;
+        let (input, iterations) = be_u16(input)?;
+        let (input, salt_length) = be_u8(input)?;
+        let (input, salt) = take(salt_length)(input)?;
+
+        Ok((
+            input,
+            RDataType::NSEC3PARAM(RDataNSEC3PARAM {
+                hash_algorithm: Nsec3HashAlgorithm::from_raw(hash_algorithm),
+                flags,
+                iterations,
+                salt: salt.to_vec(),
+            }),
+        ))
+    }
+
+    /// Decode the type bitmaps ending NSEC and NSEC3 RDATA into raw record type numbers.
+    ///
+    /// Decoding stops at the first malformed or out of order window.
+    fn parse_type_bitmaps(input: &[u8]) -> IResult<(Vec<u16>, Flags<ErrorFlags>)> {
+        let (input, mut bitmaps) = rest(input)?;
+        let mut types = Vec::new();
+        let mut error_flags = ErrorFlags::none();
+        // Windows must appear in increasing order
+        let mut next_window: u16 = 0;
+
+        while !bitmaps.is_empty() {
+            if bitmaps.len() < 2 {
+                error_flags |= ErrorFlags::InvalidTypeBitmap;
+                break;
+            }
+            let window = u16::from(bitmaps[0]);
+            let length = usize::from(bitmaps[1]);
+            if window < next_window
+                || length == 0
+                || length > MAX_TYPE_BITMAP_LEN
+                || bitmaps.len() < 2 + length
+            {
+                error_flags |= ErrorFlags::InvalidTypeBitmap;
+                break;
+            }
+
+            for (i, byte) in bitmaps[2..2 + length].iter().enumerate() {
+                for bit in 0..8 {
+                    if byte & (0x80 >> bit) != 0 {
+                        types.push(window << 8 | (i * 8 + bit) as u16);
+                    }
+                }
+            }
+            next_window = window + 1;
+            bitmaps = &bitmaps[2 + length..];
+        }
+
+        Ok((input, (types, error_flags)))
+    }
+
+    fn record_types(types_raw: &[u16]) -> Vec<RecordType> {
+        types_raw
+            .iter()
+            .map(|rtype| RecordType::from_raw(*rtype))
+            .collect()
+    }
+
     fn parse_rdata_cname<'a>(
         input: &'a [u8],
         reference_bytes: &'a [u8],
@@ -230,7 +750,8 @@ impl RDataType {
         let (input, version) = be_u8(input)?;
         let (input, flags) = be_u16(input)?;
         let (input, data_len) = be_u16(input)?;
-        let (input, (data, options_error_flags)) = EdnsOption::parse_options(input, data_len)?;
+        let (input, options) = take(data_len)(input)?;
+        let (_, (data, options_error_flags)) = EdnsOption::parse_options(options)?;
 
         Ok((
             input,
@@ -247,6 +768,40 @@ impl RDataType {
         ))
     }
 
+    fn parse_rdata_rrsig<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (RDataType, Flags<ErrorFlags>)> {
+        let (input, type_covered) = be_u16(input)?;
+        let (input, algorithm) = be_u8(input)?;
+        let (input, labels) = be_u8(input)?;
+        let (input, original_ttl) = be_u32(input)?;
+        let (input, expiration) = be_u32(input)?;
+        let (input, inception) = be_u32(input)?;
+        let (input, key_tag) = be_u16(input)?;
+        // Senders must not compress the signer name, accept it anyway
+        let (input, (signer_name, error_flags)) = Name::parse(reference_bytes)(input)?;
+        let (input, signature) = rest(input)?;
+
+        Ok((
+            input,
+            (
+                RDataType::RRSIG(RDataRRSIG {
+                    type_covered: RecordType::from_raw(type_covered),
+                    algorithm: DnssecAlgorithm::from_raw(algorithm),
+                    labels,
+                    original_ttl,
+                    expiration,
+                    inception,
+                    key_tag,
+                    signer_name,
+                    signature: signature.to_vec(),
+                }),
+                error_flags,
+            ),
+        ))
+    }
+
     fn parse_rdata_soa<'a>(
         input: &'a [u8],
         reference_bytes: &'a [u8],
@@ -279,6 +834,28 @@ impl RDataType {
         ))
     }
 
+    fn parse_rdata_svcb<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (RDataSVCB, Flags<ErrorFlags>)> {
+        let (input, priority) = be_u16(input)?;
+        let (input, (target_name, mut error_flags)) = Name::parse(reference_bytes)(input)?;
+        let (input, (params, params_error_flags)) = SvcParam::parse_params(input)?;
+        error_flags |= params_error_flags;
+
+        Ok((
+            input,
+            (
+                RDataSVCB {
+                    priority,
+                    target_name,
+                    params,
+                },
+                error_flags,
+            ),
+        ))
+    }
+
     fn parse_rdata_tkey<'a>(
         input: &'a [u8],
         reference_bytes: &'a [u8],
@@ -400,8 +977,572 @@ impl RDataType {
         ))
     }
 
+    fn parse_character_string(input: &[u8]) -> IResult<&[u8]> {
+        let (input, len) = be_u8(input)?;
+        take(len)(input)
+    }
+
+    fn parse_rdata_afsdb<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (RDataType, Flags<ErrorFlags>)> {
+        let (input, subtype) = be_u16(input)?;
+        let (input, (hostname, error_flags)) = Name::parse(reference_bytes)(input)?;
+        Ok((
+            input,
+            (
+                RDataType::AFSDB(RDataAFSDB { subtype, hostname }),
+                error_flags,
+            ),
+        ))
+    }
+
+    fn parse_rdata_cert(input: &[u8]) -> IResult<RDataType> {
+        let (input, cert_type) = be_u16(input)?;
+        let (input, key_tag) = be_u16(input)?;
+        let (input, algorithm) = be_u8(input)?;
+        let (input, certificate) = rest(input)?;
+
+        Ok((
+            input,
+            RDataType::CERT(RDataCERT {
+                cert_type: CertType::from_raw(cert_type),
+                key_tag,
+                algorithm: DnssecAlgorithm::from_raw(algorithm),
+                certificate: certificate.to_vec(),
+            }),
+        ))
+    }
+
+    fn parse_rdata_dname<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (RDataType, Flags<ErrorFlags>)> {
+        let (input, (name, error_flags)) = Name::parse(reference_bytes)(input)?;
+        Ok((input, (RDataType::DNAME(name), error_flags)))
+    }
+
+    fn parse_rdata_hinfo(input: &[u8]) -> IResult<RDataType> {
+        let (input, cpu) = RDataType::parse_character_string(input)?;
+        let (input, os) = RDataType::parse_character_string(input)?;
+
+        Ok((
+            input,
+            RDataType::HINFO(RDataHINFO {
+                cpu: cpu.to_vec(),
+                os: os.to_vec(),
+            }),
+        ))
+    }
+
+    fn parse_rdata_loc(input: &[u8]) -> IResult<RDataType> {
+        let (input, version) = be_u8(input)?;
+        let (input, size) = be_u8(input)?;
+        let (input, horizontal_precision) = be_u8(input)?;
+        let (input, vertical_precision) = be_u8(input)?;
+        let (input, latitude) = be_u32(input)?;
+        let (input, longitude) = be_u32(input)?;
+        let (input, altitude) = be_u32(input)?;
+
+        Ok((
+            input,
+            RDataType::LOC(RDataLOC {
+                version,
+                size,
+                horizontal_precision,
+                vertical_precision,
+                latitude,
+                longitude,
+                altitude,
+            }),
+        ))
+    }
+
+    fn parse_rdata_naptr<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (RDataType, Flags<ErrorFlags>)> {
+        let (input, order) = be_u16(input)?;
+        let (input, preference) = be_u16(input)?;
+        let (input, flags) = RDataType::parse_character_string(input)?;
+        let (input, services) = RDataType::parse_character_string(input)?;
+        let (input, regexp) = RDataType::parse_character_string(input)?;
+        let (input, (replacement, error_flags)) = Name::parse(reference_bytes)(input)?;
+
+        Ok((
+            input,
+            (
+                RDataType::NAPTR(RDataNAPTR {
+                    order,
+                    preference,
+                    flags: flags.to_vec(),
+                    services: services.to_vec(),
+                    regexp: regexp.to_vec(),
+                    replacement,
+                }),
+                error_flags,
+            ),
+        ))
+    }
+
+    fn parse_rdata_openpgpkey(input: &[u8]) -> IResult<RDataType> {
+        let (input, key) = rest(input)?;
+        Ok((input, RDataType::OPENPGPKEY(key.to_vec())))
+    }
+
+    fn parse_rdata_rp<'a>(
+        input: &'a [u8],
+        reference_bytes: &'a [u8],
+    ) -> IResult<'a, (RDataType, Flags<ErrorFlags>)> {
+        let (input, (mailbox, mut error_flags)) = Name::parse(reference_bytes)(input)?;
+        let (input, (txt_domain, txt_error_flags)) = Name::parse(reference_bytes)(input)?;
+        error_flags |= txt_error_flags;
+
+        Ok((
+            input,
+            (
+                RDataType::RP(RDataRP {
+                    mailbox,
+                    txt_domain,
+                }),
+                error_flags,
+            ),
+        ))
+    }
+
+    fn parse_rdata_tlsa(input: &[u8]) -> IResult<RDataTLSA> {
+        let (input, cert_usage) = be_u8(input)?;
+        let (input, selector) = be_u8(input)?;
+        let (input, matching_type) = be_u8(input)?;
+        let (input, certificate_association_data) = rest(input)?;
+
+        Ok((
+            input,
+            RDataTLSA {
+                cert_usage: TlsaCertUsage::from_raw(cert_usage),
+                selector: TlsaSelector::from_raw(selector),
+                matching_type: TlsaMatchingType::from_raw(matching_type),
+                certificate_association_data: certificate_association_data.to_vec(),
+            },
+        ))
+    }
+
+    fn parse_rdata_uri(input: &[u8]) -> IResult<RDataType> {
+        let (input, priority) = be_u16(input)?;
+        let (input, weight) = be_u16(input)?;
+        let (input, target) = rest(input)?;
+
+        Ok((
+            input,
+            RDataType::URI(RDataURI {
+                priority,
+                weight,
+                target: target.to_vec(),
+            }),
+        ))
+    }
+
+    fn parse_rdata_zonemd(input: &[u8]) -> IResult<RDataType> {
+        let (input, serial) = be_u32(input)?;
+        let (input, scheme) = be_u8(input)?;
+        let (input, hash_algorithm) = be_u8(input)?;
+        let (input, digest) = rest(input)?;
+
+        Ok((
+            input,
+            RDataType::ZONEMD(RDataZONEMD {
+                serial,
+                scheme: ZonemdScheme::from_raw(scheme),
+                hash_algorithm: ZonemdHashAlgorithm::from_raw(hash_algorithm),
+                digest: digest.to_vec(),
+            }),
+        ))
+    }
+
     fn parse_rdata_unknown(input: &[u8]) -> IResult<RDataType> {
         let (input, data) = rest(input)?;
         Ok((input, RDataType::UNKNOWN(data.to_vec())))
     }
 }
+
+#[cfg(test)]
+mod test {
+    #![allow(clippy::type_complexity)]
+
+    use crate::enums::{
+        CertType, DnssecAlgorithm, Nsec3HashAlgorithm, RecordType, TlsaCertUsage, TlsaMatchingType,
+        TlsaSelector, ZonemdHashAlgorithm, ZonemdScheme,
+    };
+    use crate::rdata::{
+        RDataAFSDB, RDataCERT, RDataDNSKEY, RDataHINFO, RDataLOC, RDataNSEC, RDataNSEC3,
+        RDataNSEC3PARAM, RDataRP, RDataTLSA, RDataType, RDataURI, RDataZONEMD,
+    };
+    use crate::{ErrorFlags, IResult};
+    use rstest::rstest;
+    use sawp_flags::{Flag, Flags};
+
+    #[rstest(
+    input,
+    rtype,
+    expected,
+    case::parse_dnskey_rsamd5_key_tag(
+        &[
+            0x01, 0x00, // flags: zone key
+            0x03, // protocol: 3
+            0x01, // algorithm: RSAMD5
+            0x01, 0x03, 0xab, 0xcd, 0xef, 0x12, 0x34, // public key
+        ],
+        RecordType::DNSKEY,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::DNSKEY(RDataDNSKEY {
+                    flags: 256,
+                    zone_key: true,
+                    revoked: false,
+                    secure_entry_point: false,
+                    protocol: 3,
+                    algorithm: DnssecAlgorithm::RSAMD5,
+                    public_key: vec![0x01, 0x03, 0xab, 0xcd, 0xef, 0x12, 0x34],
+                    key_tag: 0xef12,
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_nsec3param(
+        &[
+            0x01, // hash algorithm: SHA1
+            0x00, // flags: 0
+            0x00, 0x0a, // iterations: 10
+            0x02, // salt length: 2
+            0xab, 0xcd, // salt
+        ],
+        RecordType::NSEC3PARAM,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::NSEC3PARAM(RDataNSEC3PARAM {
+                    hash_algorithm: Nsec3HashAlgorithm::SHA1,
+                    flags: 0,
+                    iterations: 10,
+                    salt: vec![0xab, 0xcd],
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_nsec3_no_types(
+        &[
+            0x01, // hash algorithm: SHA1
+            0x00, // flags: 0
+            0x00, 0x00, // iterations: 0
+            0x00, // salt length: 0
+            0x02, // hash length: 2
+            0x12, 0x34, // next hashed owner name
+        ],
+        RecordType::NSEC3,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::NSEC3(RDataNSEC3 {
+                    hash_algorithm: Nsec3HashAlgorithm::SHA1,
+                    flags: 0,
+                    opt_out: false,
+                    iterations: 0,
+                    salt: vec![],
+                    next_hashed_owner_name: vec![0x12, 0x34],
+                    types: vec![],
+                    types_raw: vec![],
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_nsec_empty_window(
+        &[
+            0x00, // next domain name: <root>
+            0x00, 0x00, // window 0: empty
+        ],
+        RecordType::NSEC,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::NSEC(RDataNSEC {
+                    next_domain_name: vec![],
+                    types: vec![],
+                    types_raw: vec![],
+                }),
+                ErrorFlags::InvalidTypeBitmap.into()
+            )
+        ))
+    ),
+    case::parse_nsec_unordered_windows(
+        &[
+            0x00, // next domain name: <root>
+            0x01, 0x01, 0x40, // window 1: CAA
+            0x00, 0x01, 0x40, // window 0: A
+        ],
+        RecordType::NSEC,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::NSEC(RDataNSEC {
+                    next_domain_name: vec![],
+                    types: vec![RecordType::CAA],
+                    types_raw: vec![257],
+                }),
+                ErrorFlags::InvalidTypeBitmap.into()
+            )
+        ))
+    ),
+    case::parse_nsec_window_too_long(
+        &[
+            0x00, // next domain name: <root>
+            0x00, 0x21, // window 0: 33 bytes
+            0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        RecordType::NSEC,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::NSEC(RDataNSEC {
+                    next_domain_name: vec![],
+                    types: vec![],
+                    types_raw: vec![],
+                }),
+                ErrorFlags::InvalidTypeBitmap.into()
+            )
+        ))
+    ),
+    case::parse_nsec_truncated_window(
+        &[
+            0x00, // next domain name: <root>
+            0x00, 0x02, 0x40, // window 0: missing a byte
+        ],
+        RecordType::NSEC,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::NSEC(RDataNSEC {
+                    next_domain_name: vec![],
+                    types: vec![],
+                    types_raw: vec![],
+                }),
+                ErrorFlags::InvalidTypeBitmap.into()
+            )
+        ))
+    ),
+    case::parse_afsdb(
+        &[
+            0x00, 0x01, // subtype: AFS cell database server
+            0x03, 0x61, 0x66, 0x73, 0x00, // hostname: afs
+        ],
+        RecordType::AFSDB,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::AFSDB(RDataAFSDB {
+                    subtype: 1,
+                    hostname: b"afs".to_vec(),
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_cert(
+        &[
+            0x00, 0x03, // type: PGP
+            0x00, 0x00, // key tag: 0
+            0x00, // algorithm: unspecified
+            0x99, 0x01, 0x0d, // certificate
+        ],
+        RecordType::CERT,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::CERT(RDataCERT {
+                    cert_type: CertType::PGP,
+                    key_tag: 0,
+                    algorithm: DnssecAlgorithm::DELETE,
+                    certificate: vec![0x99, 0x01, 0x0d],
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_dname(
+        &[0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x6e, 0x65, 0x74, 0x00],
+        RecordType::DNAME,
+        Ok((
+            b"".as_ref(),
+            (RDataType::DNAME(b"example.net".to_vec()), ErrorFlags::none())
+        ))
+    ),
+    case::parse_hinfo(
+        &[
+            0x05, 0x41, 0x4d, 0x44, 0x36, 0x34, // cpu: AMD64
+            0x05, 0x4c, 0x69, 0x6e, 0x75, 0x78, // os: Linux
+        ],
+        RecordType::HINFO,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::HINFO(RDataHINFO {
+                    cpu: b"AMD64".to_vec(),
+                    os: b"Linux".to_vec(),
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_loc(
+        &[
+            0x00, // version: 0
+            0x12, // size: 1m
+            0x16, // horizontal precision: 10km
+            0x13, // vertical precision: 10m
+            0x89, 0x17, 0x2d, 0xd0, // latitude: 42 21 54 N
+            0x70, 0xbe, 0x15, 0xf0, // longitude: 71 06 18 W
+            0x00, 0x98, 0x8d, 0x20, // altitude: -24m
+        ],
+        RecordType::LOC,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::LOC(RDataLOC {
+                    version: 0,
+                    size: 0x12,
+                    horizontal_precision: 0x16,
+                    vertical_precision: 0x13,
+                    latitude: 0x8917_2dd0,
+                    longitude: 0x70be_15f0,
+                    altitude: 0x0098_8d20,
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_loc_truncated(
+        &[0x00, 0x12, 0x16, 0x13, 0x89, 0x17, 0x2d, 0xd0],
+        RecordType::LOC,
+        Err(nom::Err::Incomplete(nom::Needed::Size(4)))
+    ),
+    case::parse_openpgpkey(
+        &[0x99, 0x01, 0xa2, 0x04],
+        RecordType::OPENPGPKEY,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::OPENPGPKEY(vec![0x99, 0x01, 0xa2, 0x04]),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_rp(
+        &[
+            0x04, 0x72, 0x6f, 0x6f, 0x74, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x00,
+            // mailbox: root.example
+            0x02, 0x72, 0x70, 0xc0, 0x05, // txt domain: rp.example
+        ],
+        RecordType::RP,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::RP(RDataRP {
+                    mailbox: b"root.example".to_vec(),
+                    txt_domain: b"rp.example".to_vec(),
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_smimea(
+        &[
+            0x03, // usage: DANE-EE
+            0x00, // selector: full certificate
+            0x01, // matching type: SHA-256
+            0xab, 0xcd, // certificate association data
+        ],
+        RecordType::SMIMEA,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::SMIMEA(RDataTLSA {
+                    cert_usage: TlsaCertUsage::DANEEE,
+                    selector: TlsaSelector::CERT,
+                    matching_type: TlsaMatchingType::SHA256,
+                    certificate_association_data: vec![0xab, 0xcd],
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_tlsa(
+        &[
+            0x02, // usage: DANE-TA
+            0x01, // selector: SubjectPublicKeyInfo
+            0xff, // matching type: private use
+            0x01, 0x02, 0x03, // certificate association data
+        ],
+        RecordType::TLSA,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::TLSA(RDataTLSA {
+                    cert_usage: TlsaCertUsage::DANETA,
+                    selector: TlsaSelector::SPKI,
+                    matching_type: TlsaMatchingType::PRIVMATCH,
+                    certificate_association_data: vec![0x01, 0x02, 0x03],
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_uri(
+        &[
+            0x00, 0x0a, // priority: 10
+            0x00, 0x01, // weight: 1
+            0x66, 0x74, 0x70, 0x3a, 0x2f, 0x2f, 0x66, 0x74, 0x70, 0x31, // target: ftp://ftp1
+        ],
+        RecordType::URI,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::URI(RDataURI {
+                    priority: 10,
+                    weight: 1,
+                    target: b"ftp://ftp1".to_vec(),
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    case::parse_zonemd(
+        &[
+            0x78, 0x48, 0xb9, 0x1c, // serial: 2018031900
+            0x01, // scheme: SIMPLE
+            0x01, // hash algorithm: SHA384
+            0xc6, 0x80, 0x90, 0xd9, // digest
+        ],
+        RecordType::ZONEMD,
+        Ok((
+            b"".as_ref(),
+            (
+                RDataType::ZONEMD(RDataZONEMD {
+                    serial: 2_018_031_900,
+                    scheme: ZonemdScheme::SIMPLE,
+                    hash_algorithm: ZonemdHashAlgorithm::SHA384,
+                    digest: vec![0xc6, 0x80, 0x90, 0xd9],
+                }),
+                ErrorFlags::none()
+            )
+        ))
+    ),
+    )]
+    fn rdata(input: &[u8], rtype: RecordType, expected: IResult<(RDataType, Flags<ErrorFlags>)>) {
+        assert_eq!(RDataType::parse(input, input, rtype), expected);
+    }
+}
@@ -0,0 +1,514 @@
+//! Service Binding
+//! [RFC9460](https://tools.ietf.org/html/rfc9460)
+//!
+//! SVCB and HTTPS records tell clients how to reach an alternative endpoint
+//! for a service along with the parameters needed to connect to it, such as
+//! the supported application protocols, address hints and the Encrypted
+//! ClientHello configuration. Parameters are key-value pairs which must be
+//! sorted by key and appear at most once.
+
+#![allow(clippy::upper_case_acronyms)]
+
+use alloc::vec::Vec;
+
+use nom::bytes::complete::take;
+use nom::combinator::rest;
+use nom::number::complete::{be_u16, be_u8};
+
+use num_enum::TryFromPrimitive;
+
+use sawp_flags::{Flag, Flags};
+
+use core::convert::TryFrom;
+
+use crate::{ErrorFlags, IResult};
+#[cfg(feature = "ffi")]
+use sawp_ffi::GenerateFFI;
+
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Reserved key which must not appear in a record
+const INVALID_KEY: u16 = 65535;
+const IPV4_LEN: usize = 4;
+const IPV6_LEN: usize = 16;
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum SvcParamKey {
+    /// Keys the client must support to use the record
+    MANDATORY = 0,
+    /// Supported application protocols
+    ALPN = 1,
+    /// The default application protocol is not supported
+    NODEFAULTALPN = 2,
+    /// Alternative port
+    PORT = 3,
+    /// IPv4 address hints
+    IPV4HINT = 4,
+    /// Encrypted ClientHello configuration list
+    ECH = 5,
+    /// IPv6 address hints
+    IPV6HINT = 6,
+    /// DNS over HTTPS URI template. See https://tools.ietf.org/html/rfc9461
+    DOHPATH = 7,
+    /// Oblivious HTTP gateway support. See https://tools.ietf.org/html/rfc9540
+    OHTTP = 8,
+    UNKNOWN,
+}
+
+impl SvcParamKey {
+    pub fn from_raw(val: u16) -> Self {
+        SvcParamKey::try_from(val).unwrap_or(SvcParamKey::UNKNOWN)
+    }
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub enum SvcParamValue {
+    /// Raw keys, see [`SvcParamKey::from_raw`]
+    MANDATORY(Vec<u16>),
+    /// Protocol identifiers, e.g. "h2" and "h3"
+    ALPN(Vec<Vec<u8>>),
+    NODEFAULTALPN,
+    PORT(u16),
+    IPV4HINT(Vec<Vec<u8>>),
+    /// Raw ECHConfigList
+    ECH(Vec<u8>),
+    IPV6HINT(Vec<Vec<u8>>),
+    DOHPATH(Vec<u8>),
+    OHTTP,
+    /// Unknown keys and malformed values
+    UNKNOWN(Vec<u8>),
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct SvcParam {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub key: SvcParamKey,
+    pub key_raw: u16,
+    pub value: SvcParamValue,
+}
+
+impl SvcParam {
+    pub fn parse(input: &[u8]) -> IResult<SvcParam> {
+        let (input, key_raw) = be_u16(input)?;
+        let (input, value_length) = be_u16(input)?;
+        let (input, value) = take(value_length)(input)?;
+        let key = SvcParamKey::from_raw(key_raw);
+
+        let value = if key_raw == INVALID_KEY {
+            None
+        } else {
+            SvcParam::parse_value(key, value)
+        }
+        .unwrap_or_else(|| SvcParamValue::UNKNOWN(value.to_vec()));
+
+        Ok((
+            input,
+            SvcParam {
+                key,
+                key_raw,
+                value,
+            },
+        ))
+    }
+
+    /// Parse the value of a known key, returns None if it is malformed.
+    fn parse_value(key: SvcParamKey, value: &[u8]) -> Option<SvcParamValue> {
+        match key {
+            SvcParamKey::MANDATORY => {
+                let keys = value.chunks_exact(2);
+                if value.is_empty() || !keys.remainder().is_empty() {
+                    return None;
+                }
+                Some(SvcParamValue::MANDATORY(
+                    keys.map(|key| u16::from_be_bytes([key[0], key[1]]))
+                        .collect(),
+                ))
+            }
+            SvcParamKey::ALPN => {
+                let mut ids = Vec::new();
+                let mut input = value;
+                while !input.is_empty() {
+                    let (rem, id) = SvcParam::parse_alpn_id(input).ok()?;
+                    ids.push(id.to_vec());
+                    input = rem;
+                }
+                if ids.is_empty() {
+                    return None;
+                }
+                Some(SvcParamValue::ALPN(ids))
+            }
+            SvcParamKey::NODEFAULTALPN if value.is_empty() => Some(SvcParamValue::NODEFAULTALPN),
+            SvcParamKey::PORT if value.len() == 2 => {
+                Some(SvcParamValue::PORT(u16::from_be_bytes([
+                    value[0], value[1],
+                ])))
+            }
+            SvcParamKey::IPV4HINT => {
+                SvcParam::parse_hints(value, IPV4_LEN).map(SvcParamValue::IPV4HINT)
+            }
+            SvcParamKey::ECH if !value.is_empty() => Some(SvcParamValue::ECH(value.to_vec())),
+            SvcParamKey::IPV6HINT => {
+                SvcParam::parse_hints(value, IPV6_LEN).map(SvcParamValue::IPV6HINT)
+            }
+            SvcParamKey::DOHPATH => Some(SvcParamValue::DOHPATH(value.to_vec())),
+            SvcParamKey::OHTTP if value.is_empty() => Some(SvcParamValue::OHTTP),
+            SvcParamKey::UNKNOWN => Some(SvcParamValue::UNKNOWN(value.to_vec())),
+            _ => None,
+        }
+    }
+
+    fn parse_alpn_id(input: &[u8]) -> IResult<&[u8]> {
+        let (input, length) = be_u8(input)?;
+        if length == 0 {
+            return Err(nom::Err::Error((input, nom::error::ErrorKind::Verify)));
+        }
+        take(length)(input)
+    }
+
+    fn parse_hints(value: &[u8], address_len: usize) -> Option<Vec<Vec<u8>>> {
+        let hints = value.chunks_exact(address_len);
+        if value.is_empty() || !hints.remainder().is_empty() {
+            return None;
+        }
+        Some(hints.map(|hint| hint.to_vec()).collect())
+    }
+
+    /// Parse the parameters ending SVCB and HTTPS RDATA.
+    ///
+    /// Parsing stops at the first parameter which does not fit in the RDATA.
+    pub fn parse_params(input: &[u8]) -> IResult<(Vec<SvcParam>, Flags<ErrorFlags>)> {
+        let (input, mut data) = rest(input)?;
+        let mut params: Vec<SvcParam> = Vec::new();
+        let mut error_flags = ErrorFlags::none();
+
+        while !data.is_empty() {
+            let (rem, param) = match SvcParam::parse(data) {
+                Ok(result) => result,
+                Err(_) => {
+                    error_flags |= ErrorFlags::SvcParamMalformed;
+                    break;
+                }
+            };
+            if let SvcParamValue::UNKNOWN(_) = param.value {
+                if param.key != SvcParamKey::UNKNOWN || param.key_raw == INVALID_KEY {
+                    error_flags |= ErrorFlags::SvcParamMalformed;
+                }
+            }
+            if let Some(last) = params.last() {
+                if param.key_raw <= last.key_raw {
+                    error_flags |= ErrorFlags::SvcParamKeysUnsorted;
+                }
+            }
+            params.push(param);
+            data = rem;
+        }
+
+        error_flags |= SvcParam::check_mandatory(&params);
+
+        Ok((input, (params, error_flags)))
+    }
+
+    /// Mandatory keys must be sorted, exclude "mandatory" and be present in the record.
+    fn check_mandatory(params: &[SvcParam]) -> Flags<ErrorFlags> {
+        let mut error_flags = ErrorFlags::none();
+        let mandatory = params.iter().find_map(|param| match &param.value {
+            SvcParamValue::MANDATORY(keys) => Some(keys),
+            _ => None,
+        });
+
+        if let Some(keys) = mandatory {
+            for (i, key) in keys.iter().enumerate() {
+                if *key == SvcParamKey::MANDATORY as u16
+                    || !params.iter().any(|param| param.key_raw == *key)
+                {
+                    error_flags |= ErrorFlags::SvcParamMalformed;
+                }
+                if i > 0 && keys[i - 1] >= *key {
+                    error_flags |= ErrorFlags::SvcParamKeysUnsorted;
+                }
+            }
+        }
+        error_flags
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(clippy::type_complexity)]
+
+    use crate::svcb::{SvcParam, SvcParamKey, SvcParamValue};
+    use crate::{ErrorFlags, IResult};
+    use alloc::vec::Vec;
+    use rstest::rstest;
+    use sawp_flags::{Flag, Flags};
+
+    #[rstest(
+    input,
+    expected,
+    case::empty(b"", Ok((b"".as_ref(), (vec![], ErrorFlags::none())))),
+    case::port(
+        &[0x00, 0x03, 0x00, 0x02, 0x00, 0x35],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![SvcParam {
+                    key: SvcParamKey::PORT,
+                    key_raw: 3,
+                    value: SvcParamValue::PORT(53),
+                }],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::unknown_key(
+        &[0x02, 0x9b, 0x00, 0x05, 0x68, 0x65, 0x6c, 0x6c, 0x6f],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![SvcParam {
+                    key: SvcParamKey::UNKNOWN,
+                    key_raw: 667,
+                    value: SvcParamValue::UNKNOWN(b"hello".to_vec()),
+                }],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::mandatory(
+        &[
+            0x00, 0x00, 0x00, 0x04, 0x00, 0x01, 0x00, 0x04, // mandatory: alpn, ipv4hint
+            0x00, 0x01, 0x00, 0x09, 0x02, 0x68, 0x32, 0x05, 0x68, 0x33, 0x2d, 0x31, 0x39, // alpn: h2, h3-19
+            0x00, 0x02, 0x00, 0x00, // no-default-alpn
+            0x00, 0x04, 0x00, 0x04, 0xc0, 0x00, 0x02, 0x01, // ipv4hint: 192.0.2.1
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    SvcParam {
+                        key: SvcParamKey::MANDATORY,
+                        key_raw: 0,
+                        value: SvcParamValue::MANDATORY(vec![1, 4]),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::ALPN,
+                        key_raw: 1,
+                        value: SvcParamValue::ALPN(vec![b"h2".to_vec(), b"h3-19".to_vec()]),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::NODEFAULTALPN,
+                        key_raw: 2,
+                        value: SvcParamValue::NODEFAULTALPN,
+                    },
+                    SvcParam {
+                        key: SvcParamKey::IPV4HINT,
+                        key_raw: 4,
+                        value: SvcParamValue::IPV4HINT(vec![vec![192, 0, 2, 1]]),
+                    },
+                ],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    case::mandatory_missing_key(
+        &[
+            0x00, 0x00, 0x00, 0x02, 0x00, 0x03, // mandatory: port
+            0x00, 0x01, 0x00, 0x03, 0x02, 0x68, 0x32, // alpn: h2
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    SvcParam {
+                        key: SvcParamKey::MANDATORY,
+                        key_raw: 0,
+                        value: SvcParamValue::MANDATORY(vec![3]),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::ALPN,
+                        key_raw: 1,
+                        value: SvcParamValue::ALPN(vec![b"h2".to_vec()]),
+                    },
+                ],
+                ErrorFlags::SvcParamMalformed.into(),
+            )
+        ))
+    ),
+    case::mandatory_unknown_keys(
+        &[
+            0x00, 0x00, 0x00, 0x04, 0x02, 0x9b, 0x02, 0x9c, // mandatory: key667, key668
+            0x02, 0x9b, 0x00, 0x01, 0x61, // key667: a
+            0x02, 0x9c, 0x00, 0x01, 0x62, // key668: b
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    SvcParam {
+                        key: SvcParamKey::MANDATORY,
+                        key_raw: 0,
+                        value: SvcParamValue::MANDATORY(vec![667, 668]),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::UNKNOWN,
+                        key_raw: 667,
+                        value: SvcParamValue::UNKNOWN(b"a".to_vec()),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::UNKNOWN,
+                        key_raw: 668,
+                        value: SvcParamValue::UNKNOWN(b"b".to_vec()),
+                    },
+                ],
+                ErrorFlags::none(),
+            )
+        ))
+    ),
+    // Another unknown key does not stand in for a missing one
+    case::mandatory_missing_unknown_key(
+        &[
+            0x00, 0x00, 0x00, 0x02, 0x02, 0x9c, // mandatory: key668
+            0x02, 0x9b, 0x00, 0x01, 0x61, // key667: a
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    SvcParam {
+                        key: SvcParamKey::MANDATORY,
+                        key_raw: 0,
+                        value: SvcParamValue::MANDATORY(vec![668]),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::UNKNOWN,
+                        key_raw: 667,
+                        value: SvcParamValue::UNKNOWN(b"a".to_vec()),
+                    },
+                ],
+                ErrorFlags::SvcParamMalformed.into(),
+            )
+        ))
+    ),
+    case::unsorted(
+        &[
+            0x00, 0x03, 0x00, 0x02, 0x01, 0xbb, // port: 443
+            0x00, 0x01, 0x00, 0x03, 0x02, 0x68, 0x32, // alpn: h2
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    SvcParam {
+                        key: SvcParamKey::PORT,
+                        key_raw: 3,
+                        value: SvcParamValue::PORT(443),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::ALPN,
+                        key_raw: 1,
+                        value: SvcParamValue::ALPN(vec![b"h2".to_vec()]),
+                    },
+                ],
+                ErrorFlags::SvcParamKeysUnsorted.into(),
+            )
+        ))
+    ),
+    case::duplicate(
+        &[
+            0x00, 0x03, 0x00, 0x02, 0x01, 0xbb, // port: 443
+            0x00, 0x03, 0x00, 0x02, 0x00, 0x50, // port: 80
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    SvcParam {
+                        key: SvcParamKey::PORT,
+                        key_raw: 3,
+                        value: SvcParamValue::PORT(443),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::PORT,
+                        key_raw: 3,
+                        value: SvcParamValue::PORT(80),
+                    },
+                ],
+                ErrorFlags::SvcParamKeysUnsorted.into(),
+            )
+        ))
+    ),
+    case::malformed_values(
+        &[
+            0x00, 0x01, 0x00, 0x03, 0x00, 0x68, 0x32, // alpn: empty protocol id
+            0x00, 0x03, 0x00, 0x01, 0x01, // port: one byte
+            0x00, 0x06, 0x00, 0x04, 0x20, 0x01, 0x0d, 0xb8, // ipv6hint: partial address
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![
+                    SvcParam {
+                        key: SvcParamKey::ALPN,
+                        key_raw: 1,
+                        value: SvcParamValue::UNKNOWN(vec![0x00, 0x68, 0x32]),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::PORT,
+                        key_raw: 3,
+                        value: SvcParamValue::UNKNOWN(vec![0x01]),
+                    },
+                    SvcParam {
+                        key: SvcParamKey::IPV6HINT,
+                        key_raw: 6,
+                        value: SvcParamValue::UNKNOWN(vec![0x20, 0x01, 0x0d, 0xb8]),
+                    },
+                ],
+                ErrorFlags::SvcParamMalformed.into(),
+            )
+        ))
+    ),
+    case::invalid_key(
+        &[0xff, 0xff, 0x00, 0x00],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![SvcParam {
+                    key: SvcParamKey::UNKNOWN,
+                    key_raw: 65535,
+                    value: SvcParamValue::UNKNOWN(vec![]),
+                }],
+                ErrorFlags::SvcParamMalformed.into(),
+            )
+        ))
+    ),
+    case::truncated(
+        &[
+            0x00, 0x03, 0x00, 0x02, 0x01, 0xbb, // port: 443
+            0x00, 0x05, 0x00, 0x10, 0x00, 0x0e, // ech: value exceeds the rdata
+        ],
+        Ok((
+            b"".as_ref(),
+            (
+                vec![SvcParam {
+                    key: SvcParamKey::PORT,
+                    key_raw: 3,
+                    value: SvcParamValue::PORT(443),
+                }],
+                ErrorFlags::SvcParamMalformed.into(),
+            )
+        ))
+    ),
+    )]
+    fn parse_params(input: &[u8], expected: IResult<(Vec<SvcParam>, Flags<ErrorFlags>)>) {
+        assert_eq!(SvcParam::parse_params(input), expected);
+    }
+}
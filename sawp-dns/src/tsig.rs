@@ -0,0 +1,600 @@
+//! Transaction signatures
+//! [RFC8945](https://tools.ietf.org/html/rfc8945)
+//! [RFC4635](https://tools.ietf.org/html/rfc4635)
+//!
+//! A TSIG record is the last additional record of a message and holds an HMAC
+//! computed with a secret shared between client and server. The MAC covers the
+//! message as it was before the record was added, followed by the TSIG
+//! variables: the key name, class and TTL of the record and its fields other
+//! than the MAC and original ID. Responses also cover the MAC of the request.
+//!
+//! [`TsigKey::verify`] recomputes the MAC of a raw message. Only the first
+//! message of a multi-message response such as a zone transfer can be checked
+//! this way since later messages are signed with the previous MAC chained in.
+//! SIG(0) signatures use public key algorithms and are not verified, messages
+//! signed with them are reported as [`TsigStatus::Unsupported`].
+
+use alloc::vec::Vec;
+
+use byteorder::{BigEndian, ByteOrder};
+use hmac::{Hmac, Mac, NewMac};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Sha224, Sha256, Sha384, Sha512};
+
+use crate::answer::Answer;
+use crate::enums::RecordType;
+use crate::header::Header;
+use crate::name::DnsName;
+use crate::question::Question;
+use crate::rdata::RDataType;
+
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Offset of the ARCOUNT field in the header
+const ARCOUNT_OFFSET: usize = 10;
+/// MACs may be truncated down to this length but no shorter
+const MIN_MAC_LEN: usize = 10;
+
+/// HMAC of `data` using the given digest
+macro_rules! hmac {
+    ($digest:ty, $secret:expr, $data:expr) => {{
+        // HMAC accepts keys of any length
+        let mut mac = Hmac::<$digest>::new_from_slice($secret).unwrap();
+        mac.update($data);
+        mac.finalize().into_bytes().to_vec()
+    }};
+}
+
+/// HMAC algorithms of TSIG keys
+/// See https://www.iana.org/assignments/tsig-algorithm-names/tsig-algorithm-names.xhtml
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum TsigAlgorithm {
+    HmacMd5,
+    HmacSha1,
+    HmacSha224,
+    HmacSha256,
+    HmacSha384,
+    HmacSha512,
+}
+
+impl TsigAlgorithm {
+    /// Algorithm matching the name found in a TSIG record, ignoring case
+    pub fn from_name(name: &DnsName) -> Option<Self> {
+        [
+            TsigAlgorithm::HmacMd5,
+            TsigAlgorithm::HmacSha1,
+            TsigAlgorithm::HmacSha224,
+            TsigAlgorithm::HmacSha256,
+            TsigAlgorithm::HmacSha384,
+            TsigAlgorithm::HmacSha512,
+        ]
+        .iter()
+        .find(|algorithm| DnsName::from_dotted(algorithm.name()) == *name)
+        .copied()
+    }
+
+    pub fn name(self) -> &'static [u8] {
+        match self {
+            TsigAlgorithm::HmacMd5 => b"hmac-md5.sig-alg.reg.int",
+            TsigAlgorithm::HmacSha1 => b"hmac-sha1",
+            TsigAlgorithm::HmacSha224 => b"hmac-sha224",
+            TsigAlgorithm::HmacSha256 => b"hmac-sha256",
+            TsigAlgorithm::HmacSha384 => b"hmac-sha384",
+            TsigAlgorithm::HmacSha512 => b"hmac-sha512",
+        }
+    }
+
+    /// Length of an untruncated MAC
+    pub fn mac_len(self) -> usize {
+        match self {
+            TsigAlgorithm::HmacMd5 => 16,
+            TsigAlgorithm::HmacSha1 => 20,
+            TsigAlgorithm::HmacSha224 => 28,
+            TsigAlgorithm::HmacSha256 => 32,
+            TsigAlgorithm::HmacSha384 => 48,
+            TsigAlgorithm::HmacSha512 => 64,
+        }
+    }
+
+    fn mac(self, secret: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            TsigAlgorithm::HmacMd5 => hmac!(Md5, secret, data),
+            TsigAlgorithm::HmacSha1 => hmac!(Sha1, secret, data),
+            TsigAlgorithm::HmacSha224 => hmac!(Sha224, secret, data),
+            TsigAlgorithm::HmacSha256 => hmac!(Sha256, secret, data),
+            TsigAlgorithm::HmacSha384 => hmac!(Sha384, secret, data),
+            TsigAlgorithm::HmacSha512 => hmac!(Sha512, secret, data),
+        }
+    }
+}
+
+/// Outcome of verifying a message against a key
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum TsigStatus {
+    /// The MAC matches and the message was signed within the fudge
+    Valid,
+    /// The message has no TSIG record
+    Unsigned,
+    /// The message or its TSIG record could not be parsed, or the TSIG record
+    /// is not the last additional record
+    Malformed,
+    /// The record was signed with another key name or algorithm
+    BadKey,
+    /// The MAC does not match
+    BadSig,
+    /// The MAC is truncated below the allowed length
+    BadTrunc,
+    /// The MAC matches but the time signed is further than the fudge from now
+    BadTime,
+    /// The message is signed with SIG(0), which is not verified
+    Unsupported,
+}
+
+/// A shared secret used to sign messages
+#[derive(Clone, Debug)]
+pub struct TsigKey {
+    pub name: DnsName,
+    pub algorithm: TsigAlgorithm,
+    pub secret: Vec<u8>,
+}
+
+impl TsigKey {
+    /// `name` is a dotted key name and `secret` the decoded key material
+    pub fn new(name: &[u8], algorithm: TsigAlgorithm, secret: &[u8]) -> Self {
+        TsigKey {
+            name: DnsName::from_dotted(name),
+            algorithm,
+            secret: secret.to_vec(),
+        }
+    }
+
+    /// Verify the TSIG record of the raw message `input`.
+    ///
+    /// `now` is the current time in seconds since the epoch. `request_mac` is
+    /// the MAC of the request when verifying a response.
+    pub fn verify(&self, input: &[u8], now: u64, request_mac: Option<&[u8]>) -> TsigStatus {
+        let signed = match SignedMessage::parse(input) {
+            Some(signed) => signed,
+            None => return TsigStatus::Malformed,
+        };
+        let tsig = match signed.last {
+            Some(record) if record.rtype == RecordType::TSIG => record,
+            Some(record) if is_sig0(&record) => return TsigStatus::Unsupported,
+            _ => return TsigStatus::Unsigned,
+        };
+        let rdata = match &tsig.data {
+            RDataType::TSIG(rdata) => rdata,
+            _ => return TsigStatus::Malformed,
+        };
+
+        let variables = match TsigVariables::parse(input, signed.tsig_offset) {
+            Some(variables) => variables,
+            None => return TsigStatus::Malformed,
+        };
+        if variables.name != self.name
+            || TsigAlgorithm::from_name(&variables.algorithm) != Some(self.algorithm)
+        {
+            return TsigStatus::BadKey;
+        }
+
+        let mac_len = self.algorithm.mac_len();
+        if rdata.mac.is_empty() || rdata.mac.len() > mac_len {
+            return TsigStatus::Malformed;
+        }
+        if rdata.mac.len() < mac_len && rdata.mac.len() < MIN_MAC_LEN.max(mac_len / 2) {
+            return TsigStatus::BadTrunc;
+        }
+
+        let mut data = Vec::new();
+        if let Some(request_mac) = request_mac {
+            push_u16(&mut data, request_mac.len() as u16);
+            data.extend_from_slice(request_mac);
+        }
+        let message_start = data.len();
+        data.extend_from_slice(&input[..signed.tsig_offset]);
+        BigEndian::write_u16(&mut data[message_start..], rdata.original_id);
+        BigEndian::write_u16(
+            &mut data[message_start + ARCOUNT_OFFSET..],
+            signed.arcount - 1,
+        );
+        variables.write(&mut data);
+
+        let expected = self.algorithm.mac(&self.secret, &data);
+        if !constant_time_eq(&expected[..rdata.mac.len()], &rdata.mac) {
+            return TsigStatus::BadSig;
+        }
+
+        let skew = now.max(rdata.time_signed) - now.min(rdata.time_signed);
+        if skew > u64::from(rdata.fudge) {
+            return TsigStatus::BadTime;
+        }
+        TsigStatus::Valid
+    }
+}
+
+/// Position of the TSIG record in a raw message
+struct SignedMessage {
+    arcount: u16,
+    tsig_offset: usize,
+    /// Last additional record, where a TSIG or SIG(0) record is placed
+    last: Option<Answer>,
+}
+
+impl SignedMessage {
+    fn parse(input: &[u8]) -> Option<Self> {
+        let (rest, (header, _)) = Header::parse(input).ok()?;
+        let (rest, _) = Question::parse_questions(rest, input, header.qdcount.into()).ok()?;
+        let (rest, _) = Answer::parse_answers(rest, input, header.ancount.into()).ok()?;
+        let (rest, _) = Answer::parse_answers(rest, input, header.nscount.into()).ok()?;
+        if header.arcount == 0 {
+            return Some(SignedMessage {
+                arcount: 0,
+                tsig_offset: input.len(),
+                last: None,
+            });
+        }
+        let (rest, (additional, _)) =
+            Answer::parse_additionals(rest, input, usize::from(header.arcount) - 1).ok()?;
+        let tsig_offset = input.len() - rest.len();
+        let (_, (mut last, _)) = Answer::parse_additionals(rest, input, 1).ok()?;
+
+        if additional
+            .iter()
+            .any(|record| record.rtype == RecordType::TSIG)
+        {
+            return None;
+        }
+        Some(SignedMessage {
+            arcount: header.arcount,
+            tsig_offset,
+            last: last.pop(),
+        })
+    }
+}
+
+/// TSIG variables covered by the MAC, taken from the raw record so that fields
+/// the parser does not model, such as unassigned error codes, are signed as sent
+struct TsigVariables<'a> {
+    name: DnsName,
+    /// Class and TTL of the record
+    class_ttl: &'a [u8],
+    algorithm: DnsName,
+    /// Time signed and fudge
+    time_fudge: &'a [u8],
+    /// Error, other length and other data
+    error_other: &'a [u8],
+}
+
+impl<'a> TsigVariables<'a> {
+    /// Parse the TSIG record starting at `offset` in the raw message `input`
+    fn parse(input: &'a [u8], offset: usize) -> Option<Self> {
+        let (record, (name, _)) = DnsName::parse(input)(&input[offset..]).ok()?;
+        // Type, class, TTL and data length
+        let class_ttl = record.get(2..8)?;
+        let data_len = usize::from(BigEndian::read_u16(record.get(8..10)?));
+        let rdata = record.get(10..10 + data_len)?;
+
+        let (rdata, (algorithm, _)) = DnsName::parse(input)(rdata).ok()?;
+        // Time signed, fudge and MAC size, then the MAC and original ID
+        let time_fudge = rdata.get(..8)?;
+        let mac_len = usize::from(BigEndian::read_u16(rdata.get(8..10)?));
+        let error_other = rdata.get(10 + mac_len + 2..)?;
+
+        Some(TsigVariables {
+            name,
+            class_ttl,
+            algorithm,
+            time_fudge,
+            error_other,
+        })
+    }
+
+    fn write(&self, data: &mut Vec<u8>) {
+        push_name(data, &self.name);
+        data.extend_from_slice(self.class_ttl);
+        push_name(data, &self.algorithm);
+        data.extend_from_slice(self.time_fudge);
+        data.extend_from_slice(self.error_other);
+    }
+}
+
+/// Whether a record is a SIG(0) signature: a SIG record covering no record type
+fn is_sig0(record: &Answer) -> bool {
+    match &record.data {
+        RDataType::UNKNOWN(data) => {
+            record.rtype == RecordType::SIG && data.get(..2) == Some(&[0, 0][..])
+        }
+        _ => false,
+    }
+}
+
+/// Compare without returning early so the time taken does not reveal the MAC
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right.iter())
+            .fold(0, |acc, (l, r)| acc | (l ^ r))
+            == 0
+}
+
+fn push_u16(data: &mut Vec<u8>, value: u16) {
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Append the name in canonical wire format: uncompressed and in lower case
+fn push_name(data: &mut Vec<u8>, name: &DnsName) {
+    for label in name.labels() {
+        data.push(label.len() as u8);
+        data.extend(label.iter().map(|c| c.to_ascii_lowercase()));
+    }
+    data.push(0);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    const SECRET: &[u8] = b"secret-key";
+    const TIME_SIGNED: u64 = 1_600_000_000;
+
+    /// Query for example.com signed with tsig-key
+    const SIGNED_QUERY: &[u8] = &[
+        0x12, 0x34, // Transaction ID: 0x1234
+        0x01, 0x00, // Flags: RD
+        0x00, 0x01, // QDCOUNT: 1
+        0x00, 0x00, // ANCOUNT: 0
+        0x00, 0x00, // NSCOUNT: 0
+        0x00, 0x01, // ARCOUNT: 1
+        0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+        // Query: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0x08, 0x74, 0x73, 0x69, 0x67, 0x2d, 0x6b, 0x65, 0x79, 0x00, // Name: tsig-key
+        0x00, 0xfa, // RType: TSIG
+        0x00, 0xff, // RClass: ANY
+        0x00, 0x00, 0x00, 0x00, // TTL: 0
+        0x00, 0x3d, // Data length: 61
+        0x0b, 0x68, 0x6d, 0x61, 0x63, 0x2d, 0x73, 0x68, 0x61, 0x32, 0x35, 0x36, 0x00,
+        // Algorithm: hmac-sha256
+        0x00, 0x00, 0x5f, 0x5e, 0x10, 0x00, // Time signed: 1600000000
+        0x01, 0x2c, // Fudge: 300
+        0x00, 0x20, // MAC size: 32
+        0x5c, 0xbc, 0x9b, 0xce, 0xf2, 0x31, 0x6d, 0x8f, 0x5f, 0x0a, 0x35, 0x73, 0xad, 0xef, 0x77,
+        0xa8, 0xad, 0x34, 0x33, 0x9f, 0xb8, 0x6f, 0x68, 0xc8, 0x00, 0xce, 0x5b, 0x3f, 0xe6, 0x7f,
+        0x62, 0x9c, // MAC
+        0x12, 0x34, // Original ID: 0x1234
+        0x00, 0x00, // Error: NOERROR
+        0x00, 0x00, // Other length: 0
+    ];
+    /// Position of the MAC in SIGNED_QUERY
+    const QUERY_MAC: core::ops::Range<usize> = 72..104;
+
+    /// Response to SIGNED_QUERY covering its MAC
+    const SIGNED_RESPONSE: &[u8] = &[
+        0x12, 0x34, // Transaction ID: 0x1234
+        0x81, 0x80, // Flags: response, RD, RA
+        0x00, 0x01, // QDCOUNT: 1
+        0x00, 0x01, // ANCOUNT: 1
+        0x00, 0x00, // NSCOUNT: 0
+        0x00, 0x01, // ARCOUNT: 1
+        0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+        // Query: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0xc0, 0x0c, // Answer: example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0x00, 0x00, 0x01, 0x2c, // TTL: 300
+        0x00, 0x04, // Data length: 4
+        0xc0, 0x00, 0x02, 0x01, // 192.0.2.1
+        0x08, 0x74, 0x73, 0x69, 0x67, 0x2d, 0x6b, 0x65, 0x79, 0x00, // Name: tsig-key
+        0x00, 0xfa, // RType: TSIG
+        0x00, 0xff, // RClass: ANY
+        0x00, 0x00, 0x00, 0x00, // TTL: 0
+        0x00, 0x3d, // Data length: 61
+        0x0b, 0x68, 0x6d, 0x61, 0x63, 0x2d, 0x73, 0x68, 0x61, 0x32, 0x35, 0x36, 0x00,
+        // Algorithm: hmac-sha256
+        0x00, 0x00, 0x5f, 0x5e, 0x10, 0x01, // Time signed: 1600000001
+        0x01, 0x2c, // Fudge: 300
+        0x00, 0x20, // MAC size: 32
+        0xfc, 0x0b, 0x1b, 0xa7, 0x90, 0x76, 0x29, 0xc3, 0x38, 0x43, 0x5e, 0x32, 0x84, 0x85, 0x54,
+        0xe7, 0x5b, 0x19, 0xc5, 0xad, 0x59, 0x53, 0xc2, 0x82, 0x59, 0x75, 0x7b, 0x69, 0xc4, 0xe5,
+        0xa5, 0x18, // MAC
+        0x12, 0x34, // Original ID: 0x1234
+        0x00, 0x00, // Error: NOERROR
+        0x00, 0x00, // Other length: 0
+    ];
+
+    /// MAC of SIGNED_QUERY with the unassigned error code 26
+    const UNASSIGNED_ERROR_MAC: &[u8] = &[
+        0x39, 0xe4, 0x72, 0xd1, 0xe4, 0x4f, 0x0b, 0xab, 0xc7, 0xdd, 0xd2, 0x30, 0x54, 0xfe, 0x6c,
+        0x3a, 0x84, 0xed, 0xbe, 0x72, 0x49, 0x0b, 0x52, 0xc9, 0x0d, 0x6a, 0x8d, 0x16, 0xdf, 0xce,
+        0xff, 0x32,
+    ];
+    /// MAC of SIGNED_QUERY signed with the single label key name `tsig.key`
+    const DOTTED_LABEL_MAC: &[u8] = &[
+        0x42, 0xab, 0xbf, 0xbd, 0x31, 0x58, 0x35, 0x4b, 0x0c, 0xaf, 0x7c, 0x4e, 0xd4, 0x69, 0x4f,
+        0x69, 0xad, 0xf9, 0xd6, 0xb8, 0x07, 0x28, 0xa7, 0x9e, 0x81, 0x04, 0xe2, 0x0f, 0x06, 0x98,
+        0xd6, 0xb0,
+    ];
+
+    /// SIG(0) record to append to the question of SIGNED_QUERY
+    const SIG0_RECORD: &[u8] = &[
+        0x00, // Name: root
+        0x00, 0x18, // RType: SIG
+        0x00, 0xff, // RClass: ANY
+        0x00, 0x00, 0x00, 0x00, // TTL: 0
+        0x00, 0x18, // Data length: 24
+        0x00, 0x00, // Type covered: 0
+        0x0f, // Algorithm: ED25519
+        0x00, // Labels: 0
+        0x00, 0x00, 0x00, 0x00, // Original TTL: 0
+        0x5f, 0x5e, 0x11, 0x2c, // Signature expiration: 1600000300
+        0x5f, 0x5e, 0x10, 0x00, // Signature inception: 1600000000
+        0x30, 0x39, // Key tag: 12345
+        0xc0, 0x0c, // Signer: example.com
+        0x01, 0x02, 0x03, 0x04, // Signature
+    ];
+
+    fn key() -> TsigKey {
+        TsigKey::new(b"tsig-key.", TsigAlgorithm::HmacSha256, SECRET)
+    }
+
+    /// SIGNED_QUERY with `(index, value)` bytes replaced
+    fn modified(changes: &[(usize, u8)]) -> Vec<u8> {
+        let mut input = SIGNED_QUERY.to_vec();
+        for (index, value) in changes {
+            input[*index] = *value;
+        }
+        input
+    }
+
+    /// SIGNED_QUERY with `(index, value)` bytes replaced and signed with `mac`
+    fn resigned(changes: &[(usize, u8)], mac: &[u8]) -> Vec<u8> {
+        let mut input = modified(changes);
+        input[QUERY_MAC].copy_from_slice(mac);
+        input
+    }
+
+    /// SIGNED_QUERY with its MAC truncated to `len` bytes
+    fn truncated_mac(len: u8) -> Vec<u8> {
+        let removed = QUERY_MAC.len() as u8 - len;
+        let mut input = modified(&[(48, 0x3d - removed), (71, len)]);
+        input.drain(QUERY_MAC.start + usize::from(len)..QUERY_MAC.end);
+        input
+    }
+
+    #[rstest(
+        input,
+        key,
+        now,
+        expected,
+        case::valid(SIGNED_QUERY.to_vec(), key(), TIME_SIGNED, TsigStatus::Valid),
+        case::key_name_case(
+            SIGNED_QUERY.to_vec(),
+            TsigKey::new(b"TSIG-Key", TsigAlgorithm::HmacSha256, SECRET),
+            TIME_SIGNED,
+            TsigStatus::Valid
+        ),
+        case::within_fudge(SIGNED_QUERY.to_vec(), key(), TIME_SIGNED + 300, TsigStatus::Valid),
+        case::after_fudge(SIGNED_QUERY.to_vec(), key(), TIME_SIGNED + 301, TsigStatus::BadTime),
+        case::before_fudge(SIGNED_QUERY.to_vec(), key(), TIME_SIGNED - 301, TsigStatus::BadTime),
+        // The original ID is signed, not the one in the header
+        case::forwarded(modified(&[(0, 0xab)]), key(), TIME_SIGNED, TsigStatus::Valid),
+        case::modified_question(modified(&[(26, 0x1c)]), key(), TIME_SIGNED, TsigStatus::BadSig),
+        case::modified_original_id(modified(&[(104, 0x00)]), key(), TIME_SIGNED, TsigStatus::BadSig),
+        case::modified_time(modified(&[(66, 0x01)]), key(), TIME_SIGNED + 1, TsigStatus::BadSig),
+        case::wrong_secret(
+            SIGNED_QUERY.to_vec(),
+            TsigKey::new(b"tsig-key", TsigAlgorithm::HmacSha256, b"other-key"),
+            TIME_SIGNED,
+            TsigStatus::BadSig
+        ),
+        case::wrong_key_name(
+            SIGNED_QUERY.to_vec(),
+            TsigKey::new(b"other-key", TsigAlgorithm::HmacSha256, SECRET),
+            TIME_SIGNED,
+            TsigStatus::BadKey
+        ),
+        case::wrong_algorithm(
+            SIGNED_QUERY.to_vec(),
+            TsigKey::new(b"tsig-key", TsigAlgorithm::HmacSha1, SECRET),
+            TIME_SIGNED,
+            TsigStatus::BadKey
+        ),
+        // Error codes without a TSigResponseCode variant are signed as sent
+        case::unassigned_error(
+            resigned(&[(107, 0x1a)], UNASSIGNED_ERROR_MAC),
+            key(),
+            TIME_SIGNED,
+            TsigStatus::Valid
+        ),
+        case::dotted_label(
+            resigned(&[(34, b'.')], DOTTED_LABEL_MAC),
+            TsigKey {
+                name: DnsName::new(vec![b"tsig.key".to_vec()]),
+                algorithm: TsigAlgorithm::HmacSha256,
+                secret: SECRET.to_vec(),
+            },
+            TIME_SIGNED,
+            TsigStatus::Valid
+        ),
+        case::dotted_label_other_key(
+            resigned(&[(34, b'.')], DOTTED_LABEL_MAC),
+            TsigKey::new(b"tsig.key", TsigAlgorithm::HmacSha256, SECRET),
+            TIME_SIGNED,
+            TsigStatus::BadKey
+        ),
+        case::truncated_mac(truncated_mac(16), key(), TIME_SIGNED, TsigStatus::Valid),
+        case::truncated_mac_too_short(truncated_mac(15), key(), TIME_SIGNED, TsigStatus::BadTrunc),
+        case::unsigned(
+            {
+                let mut input = SIGNED_QUERY[..29].to_vec();
+                input[11] = 0;
+                input
+            },
+            key(),
+            TIME_SIGNED,
+            TsigStatus::Unsigned
+        ),
+        case::not_tsig(modified(&[(40, 0x01)]), key(), TIME_SIGNED, TsigStatus::Unsigned),
+        case::sig0(
+            [&SIGNED_QUERY[..29], SIG0_RECORD].concat(),
+            key(),
+            TIME_SIGNED,
+            TsigStatus::Unsupported
+        ),
+        // A SIG record covering another type is not a transaction signature
+        case::sig(
+            {
+                let mut input = [&SIGNED_QUERY[..29], SIG0_RECORD].concat();
+                input[40] = 0x01;
+                input
+            },
+            key(),
+            TIME_SIGNED,
+            TsigStatus::Unsigned
+        ),
+        case::incomplete(SIGNED_QUERY[..100].to_vec(), key(), TIME_SIGNED, TsigStatus::Malformed),
+        case::empty(vec![], key(), TIME_SIGNED, TsigStatus::Malformed),
+    )]
+    fn verify(input: Vec<u8>, key: TsigKey, now: u64, expected: TsigStatus) {
+        assert_eq!(key.verify(&input, now, None), expected);
+    }
+
+    #[rstest(
+        request_mac,
+        expected,
+        case::request_mac(Some(&SIGNED_QUERY[QUERY_MAC]), TsigStatus::Valid),
+        case::missing_request_mac(None, TsigStatus::BadSig),
+        case::other_request_mac(Some(&SIGNED_QUERY[QUERY_MAC.start + 1..QUERY_MAC.end]), TsigStatus::BadSig),
+    )]
+    fn verify_response(request_mac: Option<&[u8]>, expected: TsigStatus) {
+        assert_eq!(
+            key().verify(SIGNED_RESPONSE, TIME_SIGNED, request_mac),
+            expected
+        );
+    }
+
+    #[rstest(
+        name,
+        expected,
+        case::sha256(b"hmac-sha256", Some(TsigAlgorithm::HmacSha256)),
+        case::md5(b"HMAC-MD5.SIG-ALG.REG.INT.", Some(TsigAlgorithm::HmacMd5)),
+        case::gss(b"gss-tsig", None)
+    )]
+    fn algorithm_from_name(name: &[u8], expected: Option<TsigAlgorithm>) {
+        assert_eq!(
+            TsigAlgorithm::from_name(&DnsName::from_dotted(name)),
+            expected
+        );
+    }
+}
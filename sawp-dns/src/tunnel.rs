@@ -0,0 +1,608 @@
+//! Tunneling and exfiltration heuristics
+//!
+//! DNS tunnels encode data in the labels queried below a domain controlled by
+//! the attacker and return data in TXT, NULL or other large answers. The names
+//! are long, random looking and rarely repeat, and a tunnel sends many of them
+//! to the same domain.
+//!
+//! [`QueryFeatures`] describes a single question and [`TunnelDetector`]
+//! aggregates the queries sent to each domain over a time window, scoring each
+//! question and listing the [`TunnelReason`]s behind the score.
+
+use std::collections::{HashMap, HashSet};
+
+use sawp_flags::{BitFlags, Flag, Flags};
+
+use crate::answer::Answer;
+use crate::enums::RecordType;
+use crate::name::DnsName;
+use crate::question::Question;
+use crate::rdata::RDataType;
+use crate::Message;
+
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Subdomains remembered per domain and window to count the unique ones
+const MAX_TRACKED_SUBDOMAINS: usize = 4096;
+/// Highest score given to a query
+const MAX_SCORE: u8 = 100;
+
+/// Why a query looks like tunneling
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, BitFlags)]
+pub enum TunnelReason {
+    /// the subdomain has high character entropy
+    HighEntropy = 0b0000_0000_0000_0001,
+    /// a label is longer than usual
+    LongLabel = 0b0000_0000_0000_0010,
+    /// the name is longer than usual
+    LongName = 0b0000_0000_0000_0100,
+    /// the name has many labels
+    ManyLabels = 0b0000_0000_0000_1000,
+    /// the subdomain is mostly digits
+    HighDigitRatio = 0b0000_0000_0001_0000,
+    /// the subdomain mixes upper and lower case, digits and symbols
+    MixedCharacters = 0b0000_0000_0010_0000,
+    /// the record type is rarely used by legitimate clients
+    UnusualRecordType = 0b0000_0000_0100_0000,
+    /// TXT or NULL answers carry a lot of data
+    LargeAnswer = 0b0000_0000_1000_0000,
+    /// many different subdomains were queried in the window
+    ManySubdomains = 0b0000_0001_0000_0000,
+    /// many bytes were sent in subdomains in the window
+    HighVolume = 0b0000_0010_0000_0000,
+}
+
+impl TunnelReason {
+    /// Contribution of the reason to the score
+    fn weight(self) -> u8 {
+        match self {
+            TunnelReason::HighEntropy => 25,
+            TunnelReason::LongLabel => 20,
+            TunnelReason::LongName => 15,
+            TunnelReason::ManyLabels => 10,
+            TunnelReason::HighDigitRatio => 15,
+            TunnelReason::MixedCharacters => 10,
+            TunnelReason::UnusualRecordType => 15,
+            TunnelReason::LargeAnswer => 20,
+            TunnelReason::ManySubdomains => 25,
+            TunnelReason::HighVolume => 20,
+        }
+    }
+}
+
+/// Thresholds of the heuristics
+#[derive(Clone, Debug)]
+pub struct TunnelConfig {
+    /// Number of trailing labels forming the domain aggregates are kept for,
+    /// e.g. 2 for example.com
+    pub domain_labels: usize,
+    /// Length of the aggregation window in seconds
+    pub window: u64,
+    /// Domains tracked at once. Queries to further domains are scored without
+    /// aggregates until windows expire.
+    pub max_domains: usize,
+    /// Entropy of the subdomain in bits per character
+    pub entropy: f64,
+    /// Subdomains shorter than this are not checked for entropy since short
+    /// strings cannot reach a high entropy
+    pub entropy_min_len: usize,
+    pub label_len: usize,
+    pub name_len: usize,
+    pub label_count: usize,
+    pub digit_ratio: f64,
+    /// Bytes of TXT and NULL answer data in a message
+    pub answer_data_len: usize,
+    /// Unique subdomains per window
+    pub unique_subdomains: usize,
+    /// Bytes of subdomains per window
+    pub subdomain_bytes: usize,
+}
+
+impl Default for TunnelConfig {
+    fn default() -> Self {
+        TunnelConfig {
+            domain_labels: 2,
+            window: 60,
+            max_domains: 10_000,
+            entropy: 4.0,
+            entropy_min_len: 24,
+            label_len: 40,
+            name_len: 100,
+            label_count: 6,
+            digit_ratio: 0.5,
+            answer_data_len: 200,
+            unique_subdomains: 100,
+            subdomain_bytes: 4096,
+        }
+    }
+}
+
+/// Features of a single question
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryFeatures {
+    /// Length of the name in wire format
+    pub name_len: usize,
+    pub label_count: usize,
+    pub max_label_len: usize,
+    /// Characters in the labels below the domain
+    pub subdomain_len: usize,
+    /// Shannon entropy of the subdomain in bits per character
+    pub entropy: f64,
+    /// Share of digits in the subdomain
+    pub digit_ratio: f64,
+    /// Number of character classes (lower case, upper case, digits and other)
+    /// found in the subdomain
+    pub char_classes: u8,
+    pub record_type: RecordType,
+    /// Bytes of TXT and NULL answer data for the question
+    pub answer_data_len: usize,
+}
+
+impl QueryFeatures {
+    /// Features of `question` below a domain of `domain_labels` labels.
+    /// `answers` are the answers of the message, if any.
+    pub fn new(question: &Question, answers: &[Answer], domain_labels: usize) -> Self {
+        let name = DnsName::from_dotted(&question.name);
+        let labels = name.labels();
+        let subdomain = &labels[..labels.len().saturating_sub(domain_labels)];
+
+        let mut counts = [0_usize; 256];
+        let mut classes = [false; 4];
+        let mut subdomain_len = 0;
+        for c in subdomain.iter().flatten() {
+            counts[usize::from(*c)] += 1;
+            subdomain_len += 1;
+            let class = if c.is_ascii_lowercase() {
+                0
+            } else if c.is_ascii_uppercase() {
+                1
+            } else if c.is_ascii_digit() {
+                2
+            } else {
+                3
+            };
+            classes[class] = true;
+        }
+        let digits: usize = counts[usize::from(b'0')..=usize::from(b'9')].iter().sum();
+
+        QueryFeatures {
+            name_len: labels.iter().map(|label| label.len() + 1).sum::<usize>() + 1,
+            label_count: labels.len(),
+            max_label_len: labels.iter().map(|label| label.len()).max().unwrap_or(0),
+            subdomain_len,
+            entropy: entropy(&counts, subdomain_len),
+            digit_ratio: ratio(digits, subdomain_len),
+            char_classes: classes.iter().filter(|class| **class).count() as u8,
+            record_type: question.record_type,
+            answer_data_len: answers
+                .iter()
+                .filter(|answer| answer.name.eq_ignore_ascii_case(&question.name))
+                .map(|answer| match &answer.data {
+                    RDataType::TXT(data) | RDataType::NUL(data) => data.len(),
+                    _ => 0,
+                })
+                .sum(),
+        }
+    }
+}
+
+/// Aggregates of the queries to a domain in the current window
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainStats {
+    pub queries: usize,
+    pub unique_subdomains: usize,
+    pub subdomain_bytes: usize,
+    pub answer_data_len: usize,
+}
+
+/// Score of a question
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assessment {
+    pub domain: DnsName,
+    pub features: QueryFeatures,
+    /// Aggregates of the domain including this question
+    pub stats: DomainStats,
+    /// From 0 to 100
+    pub score: u8,
+    pub reasons: Flags<TunnelReason>,
+}
+
+#[derive(Debug)]
+struct DomainWindow {
+    start: u64,
+    stats: DomainStats,
+    subdomains: HashSet<DnsName>,
+}
+
+impl DomainWindow {
+    fn new(start: u64) -> Self {
+        DomainWindow {
+            start,
+            stats: DomainStats::default(),
+            subdomains: HashSet::new(),
+        }
+    }
+
+    fn add(&mut self, subdomain: DnsName, features: &QueryFeatures) {
+        self.stats.queries += 1;
+        self.stats.answer_data_len += features.answer_data_len;
+        if subdomain.is_root() {
+            return;
+        }
+        self.stats.subdomain_bytes += subdomain
+            .labels()
+            .iter()
+            .map(|label| label.len() + 1)
+            .sum::<usize>();
+        if self.subdomains.len() < MAX_TRACKED_SUBDOMAINS && self.subdomains.insert(subdomain) {
+            self.stats.unique_subdomains += 1;
+        }
+    }
+}
+
+/// Scores questions and keeps per domain aggregates over a window.
+///
+/// Feed either the responses seen by the sensor, which carry both the question
+/// and the answers, or the queries when no response is available; feeding both
+/// counts each question twice.
+#[derive(Debug, Default)]
+pub struct TunnelDetector {
+    pub config: TunnelConfig,
+    domains: HashMap<DnsName, DomainWindow>,
+}
+
+impl TunnelDetector {
+    pub fn new(config: TunnelConfig) -> Self {
+        TunnelDetector {
+            config,
+            domains: HashMap::new(),
+        }
+    }
+
+    /// Number of domains with aggregates
+    pub fn domains(&self) -> usize {
+        self.domains.len()
+    }
+
+    /// Score each question of `message`. `now` is the time the message was seen
+    /// in seconds.
+    pub fn add_message(&mut self, message: &Message, now: u64) -> Vec<Assessment> {
+        message
+            .queries
+            .iter()
+            .map(|question| self.add_question(question, &message.answers, now))
+            .collect()
+    }
+
+    fn add_question(&mut self, question: &Question, answers: &[Answer], now: u64) -> Assessment {
+        let features = QueryFeatures::new(question, answers, self.config.domain_labels);
+        let name = DnsName::from_dotted(&question.name);
+        let split = name
+            .labels()
+            .len()
+            .saturating_sub(self.config.domain_labels);
+        let (subdomain, domain) = name.labels().split_at(split);
+        let subdomain = DnsName::new(subdomain.to_vec());
+        let domain = DnsName::new(domain.to_vec()).to_lowercase();
+
+        let window = self.config.window;
+        if let Some(current) = self.domains.get_mut(&domain) {
+            if now.saturating_sub(current.start) >= window {
+                *current = DomainWindow::new(now);
+            }
+        } else {
+            if self.domains.len() >= self.config.max_domains {
+                self.domains
+                    .retain(|_, current| now.saturating_sub(current.start) < window);
+            }
+            if self.domains.len() < self.config.max_domains {
+                self.domains.insert(domain.clone(), DomainWindow::new(now));
+            }
+        }
+        let stats = match self.domains.get_mut(&domain) {
+            Some(current) => {
+                current.add(subdomain, &features);
+                current.stats.clone()
+            }
+            None => {
+                let mut current = DomainWindow::new(now);
+                current.add(subdomain, &features);
+                current.stats
+            }
+        };
+
+        let reasons = self.reasons(&features, &stats);
+        let score = [
+            TunnelReason::HighEntropy,
+            TunnelReason::LongLabel,
+            TunnelReason::LongName,
+            TunnelReason::ManyLabels,
+            TunnelReason::HighDigitRatio,
+            TunnelReason::MixedCharacters,
+            TunnelReason::UnusualRecordType,
+            TunnelReason::LargeAnswer,
+            TunnelReason::ManySubdomains,
+            TunnelReason::HighVolume,
+        ]
+        .iter()
+        .filter(|reason| reasons.contains(**reason))
+        .map(|reason| u32::from(reason.weight()))
+        .sum::<u32>()
+        .min(u32::from(MAX_SCORE)) as u8;
+
+        Assessment {
+            domain,
+            features,
+            stats,
+            score,
+            reasons,
+        }
+    }
+
+    fn reasons(&self, features: &QueryFeatures, stats: &DomainStats) -> Flags<TunnelReason> {
+        let config = &self.config;
+        let mut reasons = TunnelReason::none();
+        if features.subdomain_len >= config.entropy_min_len && features.entropy >= config.entropy {
+            reasons |= TunnelReason::HighEntropy;
+        }
+        if features.max_label_len >= config.label_len {
+            reasons |= TunnelReason::LongLabel;
+        }
+        if features.name_len >= config.name_len {
+            reasons |= TunnelReason::LongName;
+        }
+        if features.label_count >= config.label_count {
+            reasons |= TunnelReason::ManyLabels;
+        }
+        if features.digit_ratio >= config.digit_ratio {
+            reasons |= TunnelReason::HighDigitRatio;
+        }
+        if features.char_classes >= 4 {
+            reasons |= TunnelReason::MixedCharacters;
+        }
+        if is_unusual(features.record_type) {
+            reasons |= TunnelReason::UnusualRecordType;
+        }
+        if features.answer_data_len >= config.answer_data_len {
+            reasons |= TunnelReason::LargeAnswer;
+        }
+        if stats.unique_subdomains >= config.unique_subdomains {
+            reasons |= TunnelReason::ManySubdomains;
+        }
+        if stats.subdomain_bytes >= config.subdomain_bytes {
+            reasons |= TunnelReason::HighVolume;
+        }
+        reasons
+    }
+}
+
+/// Record types used by tunneling tools but rarely queried otherwise, including
+/// the private use range
+fn is_unusual(record_type: RecordType) -> bool {
+    record_type == RecordType::NUL || record_type == RecordType::UNKNOWN
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+/// Shannon entropy in bits per character of a string with the given byte counts
+fn entropy(counts: &[usize; 256], total: usize) -> f64 {
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = ratio(*count, total);
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Dns;
+    use rstest::rstest;
+    use sawp::parser::{Direction, Parse};
+
+    /// Base32 encoded data as sent by tunneling tools
+    const ENCODED: &[u8] = b"mfrggzdfmztwq2lknnwg23tpobyxe5dvoz3xq6l2gaytemzu";
+
+    /// Message asking for `name`, answered with a TXT record of `txt` if given
+    fn message(name: &[u8], record_type: RecordType, txt: Option<&[u8]>) -> Message {
+        let mut input = vec![0x12, 0x34];
+        input.extend_from_slice(if txt.is_some() {
+            &[0x81, 0x80]
+        } else {
+            &[0x01, 0x00]
+        });
+        input.extend_from_slice(&[
+            0x00,
+            0x01,
+            0x00,
+            txt.is_some() as u8,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+        ]);
+        for label in DnsName::from_dotted(name).labels() {
+            input.push(label.len() as u8);
+            input.extend_from_slice(label);
+        }
+        input.push(0);
+        input.extend_from_slice(&(record_type as u16).to_be_bytes());
+        input.extend_from_slice(&[0x00, 0x01]);
+        if let Some(txt) = txt {
+            input.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0x3c]);
+            input.extend_from_slice(&(txt.len() as u16 + 1).to_be_bytes());
+            input.push(txt.len() as u8);
+            input.extend_from_slice(txt);
+        }
+        let (_, message) = Dns::default().parse(&input, Direction::Unknown).unwrap();
+        message.unwrap()
+    }
+
+    fn features(name: &[u8]) -> QueryFeatures {
+        let message = message(name, RecordType::A, None);
+        QueryFeatures::new(&message.queries[0], &message.answers, 2)
+    }
+
+    #[rstest(
+        name,
+        name_len,
+        label_count,
+        subdomain_len,
+        entropy,
+        digit_ratio,
+        char_classes,
+        case::domain(b"example.com", 13, 2, 0, 0.0, 0.0, 0),
+        case::www(b"www.example.com", 17, 3, 3, 0.0, 0.0, 1),
+        case::digits(b"a1b2.example.com", 18, 3, 4, 2.0, 0.5, 2),
+        case::mixed(b"Ab-9.example.com", 18, 3, 4, 2.0, 0.25, 4),
+        case::labels(b"aa.bb.example.com", 19, 4, 4, 1.0, 0.0, 1)
+    )]
+    fn query_features(
+        name: &[u8],
+        name_len: usize,
+        label_count: usize,
+        subdomain_len: usize,
+        entropy: f64,
+        digit_ratio: f64,
+        char_classes: u8,
+    ) {
+        assert_eq!(
+            features(name),
+            QueryFeatures {
+                name_len,
+                label_count,
+                max_label_len: 7,
+                subdomain_len,
+                entropy,
+                digit_ratio,
+                char_classes,
+                record_type: RecordType::A,
+                answer_data_len: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn answer_data_len() {
+        let message = message(b"t.example.com", RecordType::TXT, Some(ENCODED));
+        let features = QueryFeatures::new(&message.queries[0], &message.answers, 2);
+        assert_eq!(features.answer_data_len, ENCODED.len());
+    }
+
+    #[rstest(
+        message,
+        score,
+        reasons,
+        case::benign(
+            message(b"www.example.com", RecordType::A, None),
+            0,
+            TunnelReason::none()
+        ),
+        case::encoded(
+            message(&[ENCODED, b".t.example.com"].concat(), RecordType::A, None),
+            45,
+            TunnelReason::HighEntropy | TunnelReason::LongLabel
+        ),
+        case::null(
+            message(b"www.example.com", RecordType::NUL, None),
+            15,
+            TunnelReason::UnusualRecordType.into()
+        ),
+        case::txt(
+            message(b"www.example.com", RecordType::TXT, Some(&[b'a'; 200])),
+            20,
+            TunnelReason::LargeAnswer.into()
+        ),
+        case::numeric(
+            message(b"10.0.0.1.example.com", RecordType::A, None),
+            25,
+            TunnelReason::HighDigitRatio | TunnelReason::ManyLabels
+        ),
+    )]
+    fn assess(message: Message, score: u8, reasons: Flags<TunnelReason>) {
+        let mut detector = TunnelDetector::default();
+        let assessments = detector.add_message(&message, 0);
+        assert_eq!(assessments.len(), 1);
+        assert_eq!(assessments[0].domain, DnsName::from_dotted(b"example.com"));
+        assert_eq!(assessments[0].reasons, reasons);
+        assert_eq!(assessments[0].score, score);
+    }
+
+    #[test]
+    fn domain_aggregates() {
+        let mut detector = TunnelDetector::default();
+        let mut assessment = None;
+        for index in 0..100 {
+            let name = [format!("{}", index).as_bytes(), b".EXAMPLE.com"].concat();
+            assessment = detector
+                .add_message(&message(&name, RecordType::A, None), 0)
+                .pop();
+        }
+        let assessment = assessment.unwrap();
+        assert_eq!(detector.domains(), 1);
+        assert_eq!(
+            assessment.stats,
+            DomainStats {
+                queries: 100,
+                unique_subdomains: 100,
+                subdomain_bytes: 10 * 2 + 90 * 3,
+                answer_data_len: 0,
+            }
+        );
+        assert!(assessment.reasons.contains(TunnelReason::ManySubdomains));
+
+        // Repeated names are counted once
+        let assessment = detector
+            .add_message(&message(b"99.example.com", RecordType::A, None), 30)
+            .pop()
+            .unwrap();
+        assert_eq!(assessment.stats.queries, 101);
+        assert_eq!(assessment.stats.unique_subdomains, 100);
+
+        // A new window starts after a minute
+        let assessment = detector
+            .add_message(&message(b"1.example.com", RecordType::A, None), 60)
+            .pop()
+            .unwrap();
+        assert_eq!(assessment.stats.queries, 1);
+        assert_eq!(assessment.reasons, TunnelReason::HighDigitRatio);
+    }
+
+    #[test]
+    fn max_domains() {
+        let mut detector = TunnelDetector::new(TunnelConfig {
+            max_domains: 1,
+            ..TunnelConfig::default()
+        });
+        detector.add_message(&message(b"a.example.com", RecordType::A, None), 0);
+        let assessment = detector
+            .add_message(&message(b"a.example.net", RecordType::A, None), 10)
+            .pop()
+            .unwrap();
+        assert_eq!(assessment.stats.queries, 1);
+        assert_eq!(detector.domains(), 1);
+
+        // The expired window of example.com makes room for example.net
+        detector.add_message(&message(b"b.example.net", RecordType::A, None), 60);
+        let assessment = detector
+            .add_message(&message(b"c.example.net", RecordType::A, None), 61)
+            .pop()
+            .unwrap();
+        assert_eq!(assessment.stats.queries, 2);
+        assert_eq!(detector.domains(), 1);
+    }
+}
@@ -0,0 +1,440 @@
+//! Dynamic updates
+//! [RFC2136](https://tools.ietf.org/html/rfc2136)
+//!
+//! The sections of an UPDATE message are reused: the question section holds
+//! the zone, the answer section the prerequisites, the authority section the
+//! updates and the additional section any related records. The ANY and NONE
+//! classes together with a zero TTL and empty rdata turn records into
+//! conditions on the zone content and into deletions.
+//!
+//! [`Update`] is a view over a parsed [`Message`] giving each record its
+//! meaning.
+
+#![allow(clippy::upper_case_acronyms)]
+
+use alloc::vec::Vec;
+
+use sawp_flags::{BitFlags, Flag, Flags};
+
+use crate::answer::Answer;
+use crate::enums::{OpCode, RecordClass, RecordType};
+use crate::question::Question;
+use crate::rdata::RDataType;
+use crate::Message;
+
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Records a server would reject with FORMERR
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, BitFlags)]
+pub enum UpdateErrorFlags {
+    /// the zone section does not hold exactly one record
+    ZoneCount = 0b0000_0001,
+    /// the zone record type is not SOA
+    ZoneNotSoa = 0b0000_0010,
+    /// a prerequisite does not match any of the allowed forms
+    InvalidPrerequisite = 0b0000_0100,
+    /// an update does not match any of the allowed forms
+    InvalidUpdate = 0b0000_1000,
+}
+
+/// Condition on the zone content, see RFC2136 section 2.4
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrerequisiteKind {
+    /// At least one RRset exists with the name
+    NameInUse,
+    /// No RRset exists with the name
+    NameNotInUse,
+    /// An RRset exists with the name and type, regardless of its records
+    RRsetExists,
+    /// An RRset exists with the name and type and holds this record
+    RRsetExistsValue,
+    /// No RRset exists with the name and type
+    RRsetDoesNotExist,
+    Invalid,
+}
+
+/// Change to the zone content, see RFC2136 section 2.5
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UpdateAction {
+    /// Add the record to its RRset
+    Add,
+    /// Delete every RRset with the name
+    DeleteAllRRsets,
+    /// Delete the RRset with the name and type
+    DeleteRRset,
+    /// Delete the record from its RRset
+    DeleteRR,
+    Invalid,
+}
+
+impl UpdateAction {
+    pub fn is_delete(self) -> bool {
+        match self {
+            UpdateAction::DeleteAllRRsets | UpdateAction::DeleteRRset | UpdateAction::DeleteRR => {
+                true
+            }
+            UpdateAction::Add | UpdateAction::Invalid => false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct Prerequisite<'a> {
+    pub kind: PrerequisiteKind,
+    pub record: &'a Answer,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct UpdateRecord<'a> {
+    pub action: UpdateAction,
+    pub record: &'a Answer,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct Update<'a> {
+    /// Zone being updated, None if the zone section is empty
+    pub zone: Option<&'a Question>,
+    pub prerequisites: Vec<Prerequisite<'a>>,
+    pub updates: Vec<UpdateRecord<'a>>,
+    pub additional: &'a [Answer],
+    pub error_flags: Flags<UpdateErrorFlags>,
+}
+
+impl<'a> Update<'a> {
+    /// View a message as an update.
+    ///
+    /// Returns None if the opcode is not UPDATE.
+    pub fn new(message: &'a Message) -> Option<Update<'a>> {
+        if message.header.opcode != OpCode::UPDATE {
+            return None;
+        }
+
+        let mut error_flags = UpdateErrorFlags::none();
+        let zone = message.queries.first();
+        if message.queries.len() != 1 {
+            error_flags |= UpdateErrorFlags::ZoneCount;
+        }
+        if let Some(zone) = zone {
+            if zone.record_type != RecordType::SOA {
+                error_flags |= UpdateErrorFlags::ZoneNotSoa;
+            }
+        }
+        let zone_class = zone.map(|zone| zone.record_class_raw);
+
+        let prerequisites: Vec<Prerequisite> = message
+            .answers
+            .iter()
+            .map(|record| Prerequisite {
+                kind: prerequisite_kind(record, zone_class),
+                record,
+            })
+            .collect();
+        if prerequisites
+            .iter()
+            .any(|prerequisite| prerequisite.kind == PrerequisiteKind::Invalid)
+        {
+            error_flags |= UpdateErrorFlags::InvalidPrerequisite;
+        }
+
+        let updates: Vec<UpdateRecord> = message
+            .nameservers
+            .iter()
+            .map(|record| UpdateRecord {
+                action: update_action(record, zone_class),
+                record,
+            })
+            .collect();
+        if updates
+            .iter()
+            .any(|update| update.action == UpdateAction::Invalid)
+        {
+            error_flags |= UpdateErrorFlags::InvalidUpdate;
+        }
+
+        Some(Update {
+            zone,
+            prerequisites,
+            updates,
+            additional: &message.additional,
+            error_flags,
+        })
+    }
+
+    /// Name of the zone being updated
+    pub fn zone_name(&self) -> Option<&'a [u8]> {
+        self.zone.map(|zone| zone.name.as_slice())
+    }
+
+    pub fn additions(&self) -> impl Iterator<Item = &UpdateRecord<'a>> {
+        self.updates
+            .iter()
+            .filter(|update| update.action == UpdateAction::Add)
+    }
+
+    pub fn deletions(&self) -> impl Iterator<Item = &UpdateRecord<'a>> {
+        self.updates
+            .iter()
+            .filter(|update| update.action.is_delete())
+    }
+}
+
+fn has_rdata(record: &Answer) -> bool {
+    if let RDataType::UNKNOWN(data) = &record.data {
+        !data.is_empty()
+    } else {
+        true
+    }
+}
+
+/// Types that only make sense in questions
+fn is_query_type(rtype: RecordType) -> bool {
+    rtype == RecordType::ANY
+        || rtype == RecordType::AXFR
+        || rtype == RecordType::IXFR
+        || rtype == RecordType::MAILA
+        || rtype == RecordType::MAILB
+}
+
+fn prerequisite_kind(record: &Answer, zone_class: Option<u16>) -> PrerequisiteKind {
+    if record.ttl != 0 {
+        return PrerequisiteKind::Invalid;
+    }
+    match record.rclass {
+        RecordClass::ANY if !has_rdata(record) => match record.rtype {
+            RecordType::ANY => PrerequisiteKind::NameInUse,
+            _ => PrerequisiteKind::RRsetExists,
+        },
+        RecordClass::NONE if !has_rdata(record) => match record.rtype {
+            RecordType::ANY => PrerequisiteKind::NameNotInUse,
+            _ => PrerequisiteKind::RRsetDoesNotExist,
+        },
+        _ if Some(record.rclass_raw) == zone_class && !is_query_type(record.rtype) => {
+            PrerequisiteKind::RRsetExistsValue
+        }
+        _ => PrerequisiteKind::Invalid,
+    }
+}
+
+fn update_action(record: &Answer, zone_class: Option<u16>) -> UpdateAction {
+    match record.rclass {
+        RecordClass::ANY if record.ttl == 0 && !has_rdata(record) => match record.rtype {
+            RecordType::ANY => UpdateAction::DeleteAllRRsets,
+            rtype if is_query_type(rtype) => UpdateAction::Invalid,
+            _ => UpdateAction::DeleteRRset,
+        },
+        RecordClass::NONE if record.ttl == 0 && !is_query_type(record.rtype) => {
+            UpdateAction::DeleteRR
+        }
+        _ if Some(record.rclass_raw) == zone_class && !is_query_type(record.rtype) => {
+            UpdateAction::Add
+        }
+        _ => UpdateAction::Invalid,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Dns;
+    use rstest::rstest;
+    use sawp::parser::{Direction, Parse};
+
+    /// Update of host.example.com replacing its address and removing its mail exchangers
+    const UPDATE: &[u8] = &[
+        0x1d, 0x2a, // Transaction ID: 0x1d2a
+        0x28, 0x00, // Flags: UPDATE
+        0x00, 0x01, // ZOCOUNT: 1
+        0x00, 0x01, // PRCOUNT: 1
+        0x00, 0x03, // UPCOUNT: 3
+        0x00, 0x00, // ADCOUNT: 0
+        0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+        // zone: example.com
+        0x00, 0x06, // RType: SOA
+        0x00, 0x01, // RClass: IN
+        0x04, 0x68, 0x6f, 0x73, 0x74, 0xc0, 0x0c, // prerequisite: host.example.com
+        0x00, 0x05, // RType: CNAME
+        0x00, 0xfe, // RClass: NONE
+        0x00, 0x00, 0x00, 0x00, // TTL: 0
+        0x00, 0x00, // Data length: 0
+        0xc0, 0x1d, // update: host.example.com
+        0x00, 0x0f, // RType: MX
+        0x00, 0xff, // RClass: ANY
+        0x00, 0x00, 0x00, 0x00, // TTL: 0
+        0x00, 0x00, // Data length: 0
+        0xc0, 0x1d, // update: host.example.com
+        0x00, 0x01, // RType: A
+        0x00, 0x01, // RClass: IN
+        0x00, 0x00, 0x04, 0xb0, // TTL: 1200
+        0x00, 0x04, // Data length: 4
+        0xc0, 0x00, 0x02, 0x0a, // 192.0.2.10
+        0xc0, 0x1d, // update: host.example.com
+        0x00, 0x01, // RType: A
+        0x00, 0xfe, // RClass: NONE
+        0x00, 0x00, 0x00, 0x00, // TTL: 0
+        0x00, 0x04, // Data length: 4
+        0xc0, 0x00, 0x02, 0x14, // 192.0.2.20
+    ];
+
+    fn record(rclass: RecordClass, rtype: RecordType, ttl: u32, data: Option<Vec<u8>>) -> Answer {
+        Answer {
+            name: b"host.example.com".to_vec(),
+            rtype,
+            rtype_raw: rtype as u16,
+            rclass,
+            rclass_raw: rclass as u16,
+            ttl,
+            data: match data {
+                Some(data) => RDataType::A(data),
+                None => RDataType::UNKNOWN(vec![]),
+            },
+        }
+    }
+
+    #[test]
+    fn update() {
+        let dns = Dns::default();
+        let (rest, message) = dns.parse(UPDATE, Direction::ToServer).unwrap();
+        assert!(rest.is_empty());
+        let message = message.unwrap();
+
+        let update = Update::new(&message).unwrap();
+        assert_eq!(Some(&b"example.com"[..]), update.zone_name());
+        assert_eq!(UpdateErrorFlags::none(), update.error_flags);
+        assert_eq!(
+            vec![PrerequisiteKind::RRsetDoesNotExist],
+            update
+                .prerequisites
+                .iter()
+                .map(|prerequisite| prerequisite.kind)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                UpdateAction::DeleteRRset,
+                UpdateAction::Add,
+                UpdateAction::DeleteRR
+            ],
+            update
+                .updates
+                .iter()
+                .map(|update| update.action)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, update.additions().count());
+        assert_eq!(2, update.deletions().count());
+        assert_eq!(
+            RDataType::UNKNOWN(vec![]),
+            update.deletions().next().unwrap().record.data
+        );
+        assert_eq!(
+            RDataType::A(vec![192, 0, 2, 10]),
+            update.additions().next().unwrap().record.data
+        );
+    }
+
+    #[test]
+    fn update_not_update_opcode() {
+        let mut message = Dns::default()
+            .parse(UPDATE, Direction::ToServer)
+            .unwrap()
+            .1
+            .unwrap();
+        message.header.opcode = OpCode::QUERY;
+        assert_eq!(None, Update::new(&message));
+    }
+
+    #[test]
+    fn update_zone_errors() {
+        let mut message = Dns::default()
+            .parse(UPDATE, Direction::ToServer)
+            .unwrap()
+            .1
+            .unwrap();
+        message.queries[0].record_type = RecordType::A;
+        assert_eq!(
+            Flags::from(UpdateErrorFlags::ZoneNotSoa),
+            Update::new(&message).unwrap().error_flags
+        );
+
+        message.queries.clear();
+        let update = Update::new(&message).unwrap();
+        assert_eq!(None, update.zone);
+        // Without a zone class neither the prerequisite nor the addition can be valid
+        assert_eq!(
+            UpdateErrorFlags::ZoneCount | UpdateErrorFlags::InvalidUpdate,
+            update.error_flags
+        );
+    }
+
+    #[rstest(
+        rclass,
+        rtype,
+        ttl,
+        data,
+        expected,
+        case::name_in_use(RecordClass::ANY, RecordType::ANY, 0, None, PrerequisiteKind::NameInUse),
+        case::rrset_exists(RecordClass::ANY, RecordType::A, 0, None, PrerequisiteKind::RRsetExists),
+        case::name_not_in_use(RecordClass::NONE, RecordType::ANY, 0, None, PrerequisiteKind::NameNotInUse),
+        case::rrset_does_not_exist(RecordClass::NONE, RecordType::A, 0, None, PrerequisiteKind::RRsetDoesNotExist),
+        case::rrset_exists_value(RecordClass::IN, RecordType::A, 0, Some(vec![192, 0, 2, 1]), PrerequisiteKind::RRsetExistsValue),
+        case::ttl(RecordClass::ANY, RecordType::A, 300, None, PrerequisiteKind::Invalid),
+        case::any_with_rdata(RecordClass::ANY, RecordType::A, 0, Some(vec![192, 0, 2, 1]), PrerequisiteKind::Invalid),
+        case::query_type(RecordClass::IN, RecordType::AXFR, 0, None, PrerequisiteKind::Invalid),
+        case::other_class(RecordClass::CH, RecordType::A, 0, Some(vec![192, 0, 2, 1]), PrerequisiteKind::Invalid),
+    )]
+    fn prerequisite(
+        rclass: RecordClass,
+        rtype: RecordType,
+        ttl: u32,
+        data: Option<Vec<u8>>,
+        expected: PrerequisiteKind,
+    ) {
+        assert_eq!(
+            expected,
+            prerequisite_kind(
+                &record(rclass, rtype, ttl, data),
+                Some(RecordClass::IN as u16)
+            )
+        );
+    }
+
+    #[rstest(
+        rclass,
+        rtype,
+        ttl,
+        data,
+        expected,
+        case::add(RecordClass::IN, RecordType::A, 300, Some(vec![192, 0, 2, 1]), UpdateAction::Add),
+        case::delete_all_rrsets(RecordClass::ANY, RecordType::ANY, 0, None, UpdateAction::DeleteAllRRsets),
+        case::delete_rrset(RecordClass::ANY, RecordType::A, 0, None, UpdateAction::DeleteRRset),
+        case::delete_rr(RecordClass::NONE, RecordType::A, 0, Some(vec![192, 0, 2, 1]), UpdateAction::DeleteRR),
+        case::delete_rrset_ttl(RecordClass::ANY, RecordType::A, 300, None, UpdateAction::Invalid),
+        case::delete_rrset_query_type(RecordClass::ANY, RecordType::AXFR, 0, None, UpdateAction::Invalid),
+        case::delete_rr_ttl(RecordClass::NONE, RecordType::A, 300, Some(vec![192, 0, 2, 1]), UpdateAction::Invalid),
+        case::delete_rr_any(RecordClass::NONE, RecordType::ANY, 0, None, UpdateAction::Invalid),
+        case::add_query_type(RecordClass::IN, RecordType::ANY, 0, None, UpdateAction::Invalid),
+        case::other_class(RecordClass::CH, RecordType::A, 300, Some(vec![192, 0, 2, 1]), UpdateAction::Invalid),
+    )]
+    fn update_record(
+        rclass: RecordClass,
+        rtype: RecordType,
+        ttl: u32,
+        data: Option<Vec<u8>>,
+        expected: UpdateAction,
+    ) {
+        assert_eq!(
+            expected,
+            update_action(
+                &record(rclass, rtype, ttl, data),
+                Some(RecordClass::IN as u16)
+            )
+        );
+    }
+}
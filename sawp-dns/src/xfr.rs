@@ -0,0 +1,665 @@
+//! Zone transfers
+//! [RFC5936](https://tools.ietf.org/html/rfc5936)
+//! [RFC1995](https://tools.ietf.org/html/rfc1995)
+//!
+//! A zone transfer response spans one or more messages over TCP and is
+//! delimited by the SOA record of the zone. A full transfer (AXFR) starts and
+//! ends with the same SOA record. An incremental transfer (IXFR) starts with the
+//! SOA of the new version, followed by one or more differences each made of the
+//! old SOA, the deleted records, the new SOA and the added records, and ends
+//! with the SOA of the new version again. Servers may fall back to a full
+//! transfer or reply with a single SOA when the client is up to date.
+//!
+//! [`ZoneTransfer`] groups the messages of a transfer parsed with
+//! [`crate::DnsTcp`] and reports its progress.
+
+#![allow(clippy::upper_case_acronyms)]
+
+use alloc::vec::Vec;
+
+use sawp_flags::{BitFlags, Flag, Flags};
+
+use crate::enums::{QueryResponse, RecordType, ResponseCode};
+use crate::rdata::RDataType;
+use crate::Message;
+#[cfg(feature = "ffi")]
+use sawp_ffi::GenerateFFI;
+
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Anomalies found while following a zone transfer
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, BitFlags)]
+pub enum TransferErrorFlags {
+    /// the first record of the response is not an SOA
+    MissingStartSoa = 0b0000_0001,
+    /// an SOA serial does not continue the transfer
+    SerialMismatch = 0b0000_0010,
+    /// records were sent after the closing SOA
+    RecordsAfterEnd = 0b0000_0100,
+    /// a message does not belong to this transfer
+    TransactionIdMismatch = 0b0000_1000,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum TransferType {
+    AXFR,
+    IXFR,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum TransferStatus {
+    /// No response has been seen yet
+    Pending,
+    InProgress,
+    /// The closing SOA was seen
+    Complete,
+    /// The server returned an error or the response is not a zone transfer
+    Failed,
+}
+
+/// One difference sequence of an incremental transfer
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct IxfrDiff {
+    pub from_serial: u32,
+    /// Zero until the new SOA of the difference is seen
+    pub to_serial: u32,
+    pub deleted: usize,
+    pub added: usize,
+}
+
+/// Position in the record sequence of the response
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    /// Waiting for the opening SOA
+    Start,
+    /// Seen the opening SOA only
+    Opened,
+    /// Full zone records
+    Records,
+    /// Records deleted by the current difference
+    Deleting,
+    /// Records added by the current difference
+    Adding,
+    Done,
+    /// The response is not a zone transfer
+    Invalid,
+}
+
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_dns"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct ZoneTransfer {
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub transfer_type: TransferType,
+    pub transaction_id: u16,
+    pub zone: Vec<u8>,
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub status: TransferStatus,
+    /// Response code of the first failed response
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub rcode: ResponseCode,
+    /// Serial of the zone version being transferred
+    pub serial: Option<u32>,
+    /// Serial of the version the client has, from the authority SOA of an
+    /// incremental transfer query
+    pub client_serial: Option<u32>,
+    /// Response messages seen
+    pub messages: usize,
+    /// Answer records seen, including the opening and closing SOA
+    pub records: usize,
+    /// Difference sequences of an incremental transfer, empty for full transfers
+    pub diffs: Vec<IxfrDiff>,
+    #[cfg_attr(feature = "ffi", sawp_ffi(flag = "u8"))]
+    pub error_flags: Flags<TransferErrorFlags>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    state: State,
+}
+
+impl ZoneTransfer {
+    /// Start following a transfer from its query or first response.
+    ///
+    /// Returns None if the message does not ask for a zone transfer.
+    pub fn new(message: &Message) -> Option<ZoneTransfer> {
+        let question = message.queries.first()?;
+        let transfer_type = match question.record_type {
+            RecordType::AXFR => TransferType::AXFR,
+            RecordType::IXFR => TransferType::IXFR,
+            _ => return None,
+        };
+        let client_serial = match (transfer_type, message.header.query_response) {
+            (TransferType::IXFR, QueryResponse::Query) => {
+                message
+                    .nameservers
+                    .iter()
+                    .find_map(|record| match &record.data {
+                        RDataType::SOA(soa) => Some(soa.serial),
+                        _ => None,
+                    })
+            }
+            _ => None,
+        };
+
+        let mut transfer = ZoneTransfer {
+            transfer_type,
+            transaction_id: message.header.transaction_id,
+            zone: question.name.clone(),
+            status: TransferStatus::Pending,
+            rcode: ResponseCode::NOERROR,
+            serial: None,
+            client_serial,
+            messages: 0,
+            records: 0,
+            diffs: Vec::new(),
+            error_flags: TransferErrorFlags::none(),
+            state: State::Start,
+        };
+        transfer.add_message(message);
+        Some(transfer)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.status == TransferStatus::Complete
+    }
+
+    /// Add the next message of the transfer, queries are ignored.
+    pub fn add_message(&mut self, message: &Message) {
+        if message.header.query_response != QueryResponse::Response {
+            return;
+        }
+        if message.header.transaction_id != self.transaction_id {
+            self.error_flags |= TransferErrorFlags::TransactionIdMismatch;
+            return;
+        }
+        self.messages += 1;
+        if self.status == TransferStatus::Failed {
+            return;
+        }
+        if message.header.rcode != ResponseCode::NOERROR {
+            self.rcode = message.header.rcode;
+            self.status = TransferStatus::Failed;
+            return;
+        }
+
+        let opening = self.state == State::Start;
+        for answer in &message.answers {
+            let serial = match &answer.data {
+                RDataType::SOA(soa) => Some(soa.serial),
+                _ => None,
+            };
+            self.add_record(serial);
+        }
+
+        // A response made of a single SOA not newer than the client's version
+        // tells an incremental client it is up to date
+        if opening && message.answers.len() == 1 && self.state == State::Opened {
+            if let (Some(serial), Some(client_serial)) = (self.serial, self.client_serial) {
+                if self.transfer_type == TransferType::IXFR && !serial_newer(serial, client_serial)
+                {
+                    self.state = State::Done;
+                }
+            }
+        }
+        self.status = match self.state {
+            State::Start | State::Invalid => TransferStatus::Failed,
+            State::Done => TransferStatus::Complete,
+            _ => TransferStatus::InProgress,
+        };
+    }
+
+    /// Advance through the record sequence, `serial` is set for SOA records.
+    fn add_record(&mut self, serial: Option<u32>) {
+        self.records += 1;
+        match (self.state, serial) {
+            (State::Start, Some(serial)) => {
+                self.serial = Some(serial);
+                self.state = State::Opened;
+            }
+            (State::Start, None) => {
+                self.error_flags |= TransferErrorFlags::MissingStartSoa;
+                self.state = State::Invalid;
+            }
+            (State::Opened, None) => self.state = State::Records,
+            (State::Opened, Some(serial)) if self.serial == Some(serial) => {
+                self.state = State::Done
+            }
+            (State::Opened, Some(_)) if self.transfer_type == TransferType::AXFR => {
+                self.error_flags |= TransferErrorFlags::SerialMismatch;
+                self.state = State::Done;
+            }
+            (State::Opened, Some(serial)) => self.start_diff(serial),
+            (State::Records, None) => {}
+            (State::Records, Some(serial)) => {
+                if self.serial != Some(serial) {
+                    self.error_flags |= TransferErrorFlags::SerialMismatch;
+                }
+                self.state = State::Done;
+            }
+            (State::Deleting, None) => {
+                if let Some(diff) = self.diffs.last_mut() {
+                    diff.deleted += 1;
+                }
+            }
+            (State::Deleting, Some(serial)) => {
+                if let Some(diff) = self.diffs.last_mut() {
+                    diff.to_serial = serial;
+                }
+                self.state = State::Adding;
+            }
+            (State::Adding, None) => {
+                if let Some(diff) = self.diffs.last_mut() {
+                    diff.added += 1;
+                }
+            }
+            (State::Adding, Some(serial)) => {
+                let to_serial = self.diffs.last().map(|diff| diff.to_serial);
+                if self.serial == Some(serial) && to_serial == Some(serial) {
+                    self.state = State::Done;
+                } else {
+                    // Each difference continues from the version the previous one produced
+                    if to_serial != Some(serial) {
+                        self.error_flags |= TransferErrorFlags::SerialMismatch;
+                    }
+                    self.start_diff(serial);
+                }
+            }
+            (State::Done, _) => {
+                self.error_flags |= TransferErrorFlags::RecordsAfterEnd;
+            }
+            (State::Invalid, _) => {}
+        }
+    }
+
+    fn start_diff(&mut self, from_serial: u32) {
+        self.diffs.push(IxfrDiff {
+            from_serial,
+            to_serial: 0,
+            deleted: 0,
+            added: 0,
+        });
+        self.state = State::Deleting;
+    }
+}
+
+/// Whether `serial` is newer than `other` using serial number arithmetic,
+/// where serials wrap around.
+/// See https://tools.ietf.org/html/rfc1982#section-3.2
+fn serial_newer(serial: u32, other: u32) -> bool {
+    (serial.wrapping_sub(other) as i32) > 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::answer::Answer;
+    use crate::enums::{OpCode, RecordClass};
+    use crate::header::Header;
+    use crate::question::Question;
+    use crate::rdata::RDataSoa;
+    use rstest::rstest;
+
+    const ZONE: &[u8] = b"example.com";
+
+    fn message(
+        query_response: QueryResponse,
+        transaction_id: u16,
+        rcode: ResponseCode,
+        record_type: RecordType,
+        records: &[Option<u32>],
+    ) -> Message {
+        Message {
+            header: Header {
+                transaction_id,
+                flags: 0,
+                query_response,
+                opcode: OpCode::QUERY,
+                authoritative: true,
+                truncated: false,
+                recursion_desired: false,
+                recursion_available: false,
+                zflag: false,
+                authenticated_data: false,
+                check_disabled: false,
+                rcode,
+                qdcount: 1,
+                ancount: records.len() as u16,
+                nscount: 0,
+                arcount: 0,
+            },
+            queries: vec![Question {
+                name: ZONE.to_vec(),
+                record_type,
+                record_type_raw: record_type as u16,
+                record_class: RecordClass::IN,
+                record_class_raw: RecordClass::IN as u16,
+            }],
+            answers: records.iter().map(|serial| answer(*serial)).collect(),
+            nameservers: vec![],
+            additional: vec![],
+            error_flags: crate::ErrorFlags::none(),
+        }
+    }
+
+    /// An SOA record when `serial` is set, an A record otherwise
+    fn answer(serial: Option<u32>) -> Answer {
+        let (rtype, data) = match serial {
+            Some(serial) => (
+                RecordType::SOA,
+                RDataType::SOA(RDataSoa {
+                    mname: b"ns1.example.com".to_vec(),
+                    rname: b"hostmaster.example.com".to_vec(),
+                    serial,
+                    refresh: 7200,
+                    retry: 900,
+                    expire: 1_209_600,
+                    minimum: 3600,
+                }),
+            ),
+            None => (RecordType::A, RDataType::A(vec![192, 0, 2, 1])),
+        };
+        Answer {
+            name: ZONE.to_vec(),
+            rtype,
+            rtype_raw: rtype as u16,
+            rclass: RecordClass::IN,
+            rclass_raw: RecordClass::IN as u16,
+            ttl: 3600,
+            data,
+        }
+    }
+
+    fn response(record_type: RecordType, records: &[Option<u32>]) -> Message {
+        message(
+            QueryResponse::Response,
+            0x1234,
+            ResponseCode::NOERROR,
+            record_type,
+            records,
+        )
+    }
+
+    fn diff(from_serial: u32, to_serial: u32, deleted: usize, added: usize) -> IxfrDiff {
+        IxfrDiff {
+            from_serial,
+            to_serial,
+            deleted,
+            added,
+        }
+    }
+
+    #[rstest(
+        record_type,
+        client_serial,
+        responses,
+        expected_status,
+        expected_records,
+        expected_diffs,
+        expected_flags,
+        case::axfr_single_message(
+            RecordType::AXFR,
+            None,
+            vec![vec![Some(3), None, None, Some(3)]],
+            TransferStatus::Complete,
+            4,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        case::axfr_multiple_messages(
+            RecordType::AXFR,
+            None,
+            vec![vec![Some(3), None], vec![None, None], vec![None, Some(3)]],
+            TransferStatus::Complete,
+            6,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        case::axfr_in_progress(
+            RecordType::AXFR,
+            None,
+            vec![vec![Some(3), None], vec![None]],
+            TransferStatus::InProgress,
+            3,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        case::axfr_missing_start_soa(
+            RecordType::AXFR,
+            None,
+            vec![vec![None, None, Some(3)]],
+            TransferStatus::Failed,
+            3,
+            vec![],
+            TransferErrorFlags::MissingStartSoa.into(),
+        ),
+        case::axfr_serial_mismatch(
+            RecordType::AXFR,
+            None,
+            vec![vec![Some(3), None, Some(4)]],
+            TransferStatus::Complete,
+            3,
+            vec![],
+            TransferErrorFlags::SerialMismatch.into(),
+        ),
+        case::axfr_records_after_end(
+            RecordType::AXFR,
+            None,
+            vec![vec![Some(3), None, Some(3)], vec![None]],
+            TransferStatus::Complete,
+            4,
+            vec![],
+            TransferErrorFlags::RecordsAfterEnd.into(),
+        ),
+        case::ixfr_up_to_date(
+            RecordType::IXFR,
+            Some(3),
+            vec![vec![Some(3)]],
+            TransferStatus::Complete,
+            1,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        // RFC1995 section 7 example: version 1 to 3 through version 2
+        case::ixfr_incremental(
+            RecordType::IXFR,
+            Some(1),
+            vec![vec![
+                Some(3),
+                Some(1),
+                None,
+                Some(2),
+                None,
+                Some(2),
+                None,
+                Some(3),
+                None,
+                None,
+                Some(3),
+            ]],
+            TransferStatus::Complete,
+            11,
+            vec![diff(1, 2, 1, 1), diff(2, 3, 1, 2)],
+            TransferErrorFlags::none(),
+        ),
+        case::ixfr_up_to_date_client_newer(
+            RecordType::IXFR,
+            Some(4),
+            vec![vec![Some(3)]],
+            TransferStatus::Complete,
+            1,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        case::ixfr_up_to_date_client_wrapped(
+            RecordType::IXFR,
+            Some(5),
+            vec![vec![Some(0xffff_fff0)]],
+            TransferStatus::Complete,
+            1,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        // The server serial wrapped past the client's and is newer
+        case::ixfr_opening_soa_alone_wrapped(
+            RecordType::IXFR,
+            Some(0xffff_fff0),
+            vec![vec![Some(5)]],
+            TransferStatus::InProgress,
+            1,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        // Only the opening SOA in the first message of a transfer
+        case::ixfr_opening_soa_alone(
+            RecordType::IXFR,
+            Some(2),
+            vec![vec![Some(3)], vec![Some(2), None, Some(3), None, Some(3)]],
+            TransferStatus::Complete,
+            6,
+            vec![diff(2, 3, 1, 1)],
+            TransferErrorFlags::none(),
+        ),
+        case::ixfr_opening_soa_alone_in_progress(
+            RecordType::IXFR,
+            Some(2),
+            vec![vec![Some(3)]],
+            TransferStatus::InProgress,
+            1,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+        case::ixfr_incremental_multiple_messages(
+            RecordType::IXFR,
+            Some(2),
+            vec![vec![Some(3), Some(2), None], vec![None, Some(3)], vec![None, Some(3)]],
+            TransferStatus::Complete,
+            7,
+            vec![diff(2, 3, 2, 1)],
+            TransferErrorFlags::none(),
+        ),
+        case::ixfr_in_progress(
+            RecordType::IXFR,
+            Some(2),
+            vec![vec![Some(3), Some(2), None, Some(3)]],
+            TransferStatus::InProgress,
+            4,
+            vec![diff(2, 3, 1, 0)],
+            TransferErrorFlags::none(),
+        ),
+        case::ixfr_discontinuous(
+            RecordType::IXFR,
+            Some(1),
+            vec![vec![Some(3), Some(1), Some(2), Some(5), Some(3), Some(3)]],
+            TransferStatus::Complete,
+            6,
+            vec![diff(1, 2, 0, 0), diff(5, 3, 0, 0)],
+            TransferErrorFlags::SerialMismatch.into(),
+        ),
+        case::ixfr_axfr_fallback(
+            RecordType::IXFR,
+            Some(1),
+            vec![vec![Some(3), None, None, Some(3)]],
+            TransferStatus::Complete,
+            4,
+            vec![],
+            TransferErrorFlags::none(),
+        ),
+    )]
+    fn zone_transfer(
+        record_type: RecordType,
+        client_serial: Option<u32>,
+        responses: Vec<Vec<Option<u32>>>,
+        expected_status: TransferStatus,
+        expected_records: usize,
+        expected_diffs: Vec<IxfrDiff>,
+        expected_flags: Flags<TransferErrorFlags>,
+    ) {
+        let mut query = message(
+            QueryResponse::Query,
+            0x1234,
+            ResponseCode::NOERROR,
+            record_type,
+            &[],
+        );
+        if let Some(serial) = client_serial {
+            query.header.nscount = 1;
+            query.nameservers.push(answer(Some(serial)));
+        }
+        let mut transfer = ZoneTransfer::new(&query).unwrap();
+        assert_eq!(TransferStatus::Pending, transfer.status);
+        assert_eq!(client_serial, transfer.client_serial);
+
+        for records in &responses {
+            transfer.add_message(&response(record_type, records));
+        }
+        assert_eq!(expected_status, transfer.status);
+        assert_eq!(responses.len(), transfer.messages);
+        assert_eq!(expected_records, transfer.records);
+        assert_eq!(expected_diffs, transfer.diffs);
+        assert_eq!(expected_flags, transfer.error_flags);
+    }
+
+    #[test]
+    fn zone_transfer_not_requested() {
+        assert_eq!(None, ZoneTransfer::new(&response(RecordType::A, &[None])));
+    }
+
+    #[test]
+    fn zone_transfer_from_response() {
+        let transfer =
+            ZoneTransfer::new(&response(RecordType::AXFR, &[Some(7), None, Some(7)])).unwrap();
+        assert!(transfer.is_complete());
+        assert_eq!(TransferType::AXFR, transfer.transfer_type);
+        assert_eq!(ZONE, transfer.zone.as_slice());
+        assert_eq!(Some(7), transfer.serial);
+        assert_eq!(1, transfer.messages);
+    }
+
+    #[test]
+    fn zone_transfer_refused() {
+        let mut transfer = ZoneTransfer::new(&message(
+            QueryResponse::Response,
+            0x1234,
+            ResponseCode::REFUSED,
+            RecordType::AXFR,
+            &[],
+        ))
+        .unwrap();
+        assert_eq!(TransferStatus::Failed, transfer.status);
+        assert_eq!(ResponseCode::REFUSED, transfer.rcode);
+
+        // Later messages are counted but do not revive the transfer
+        transfer.add_message(&response(RecordType::AXFR, &[Some(3), None, Some(3)]));
+        assert_eq!(TransferStatus::Failed, transfer.status);
+        assert_eq!(2, transfer.messages);
+        assert_eq!(0, transfer.records);
+    }
+
+    #[test]
+    fn zone_transfer_transaction_id_mismatch() {
+        let mut transfer =
+            ZoneTransfer::new(&response(RecordType::AXFR, &[Some(3), None])).unwrap();
+        transfer.add_message(&message(
+            QueryResponse::Response,
+            0x4321,
+            ResponseCode::NOERROR,
+            RecordType::AXFR,
+            &[None, Some(3)],
+        ));
+        assert_eq!(TransferStatus::InProgress, transfer.status);
+        assert_eq!(1, transfer.messages);
+        assert_eq!(2, transfer.records);
+        assert_eq!(
+            Flags::from(TransferErrorFlags::TransactionIdMismatch),
+            transfer.error_flags
+        );
+    }
+}
@@ -10,6 +10,12 @@ pub enum ErrorKind {
     VersionParse,
     // Version did not match during deserialization (expected, actual).
     VersionMismatch((Version, Version)),
+    // Capture file could not be decoded.
+    PcapFormat(String),
+    // Capture uses a link layer type that can't be decoded.
+    UnsupportedLinkType(u32),
+    // Flow endpoints don't share the same address family.
+    AddressFamilyMismatch,
 }
 #[derive(Debug)]
 pub struct Error {
@@ -31,6 +37,13 @@ impl std::fmt::Display for Error {
             ErrorKind::VersionMismatch((expected, actual)) => {
                 write!(fmt, "expected version {} got {}", expected, actual)
             }
+            ErrorKind::PcapFormat(err) => write!(fmt, "pcap format error: {}", err),
+            ErrorKind::UnsupportedLinkType(link_type) => {
+                write!(fmt, "unsupported pcap link type {}", link_type)
+            }
+            ErrorKind::AddressFamilyMismatch => {
+                write!(fmt, "flow endpoints use different address families")
+            }
         }
     }
 }
@@ -30,18 +30,52 @@ pub enum Direction {
     ToClient,
 }
 
+impl From<Direction> for sawp::parser::Direction {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Unknown => sawp::parser::Direction::Unknown,
+            Direction::ToServer => sawp::parser::Direction::ToServer,
+            Direction::ToClient => sawp::parser::Direction::ToClient,
+        }
+    }
+}
+
 /// A chunk of input data to parse.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Data {
-    direction: Direction,
-    data: Vec<u8>,
+    pub(crate) direction: Direction,
+    pub(crate) data: Vec<u8>,
+}
+
+impl Data {
+    /// Direction the data was sent in.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Bytes passed to the parser.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
 }
 
 /// Identifies a missing chunk of input data.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Gap {
-    direction: Direction,
-    gap: usize,
+    pub(crate) direction: Direction,
+    pub(crate) gap: usize,
+}
+
+impl Gap {
+    /// Direction the gap occurred in.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Number of missing bytes.
+    pub fn gap(&self) -> usize {
+        self.gap
+    }
 }
 
 /// A list of all API calls we want to expose.
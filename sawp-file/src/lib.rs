@@ -2,6 +2,9 @@
 //!
 //! This module defines structs to serialize and deserialize arguments to SAWP
 //! calls in order to replay them into a parser.
+//!
+//! The [`pcap`] module converts flows from pcap captures into this format and
+//! back.
 
 #![allow(clippy::upper_case_acronyms)]
 
@@ -13,6 +16,7 @@ extern crate rmp_serde as rmps;
 
 pub mod error;
 pub mod format;
+pub mod pcap;
 
 pub type Version = usize;
 
@@ -0,0 +1,1022 @@
+//! Conversion between pcap captures and SAWP recordings.
+//!
+//! [`pcap_to_sawp`] extracts a single TCP or UDP flow from a classic libpcap
+//! capture and records its payloads as [`Call::Parse`] and [`Call::Gap`]
+//! calls. [`sawp_to_pcap`] does the opposite and synthesizes a capture from a
+//! recording using fabricated Ethernet, IP and transport headers so it can be
+//! opened with tools such as Wireshark.
+//!
+//! Only the classic pcap format is supported (pcapng is not). Captures may use
+//! Ethernet (with VLAN tags), Linux cooked, BSD loopback or raw IP link
+//! layers.
+//!
+//! TCP payloads are ordered using sequence numbers: retransmitted bytes are
+//! dropped and missing bytes are recorded as gaps. Segments are not buffered,
+//! so an out-of-order segment is recorded as a gap followed by its data and
+//! the late segment filling the hole is dropped as a retransmission. IP
+//! fragments are ignored.
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::format::{Call, Data, Direction, Gap, Writer};
+use std::convert::TryInto;
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr};
+
+const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const PCAP_VERSION_MAJOR: u16 = 2;
+const PCAP_VERSION_MINOR: u16 = 4;
+const PCAP_GLOBAL_HEADER_LEN: usize = 24;
+const PCAP_RECORD_HEADER_LEN: usize = 16;
+// Largest record we agree to allocate. Anything bigger is a corrupt file.
+const PCAP_MAX_RECORD_LEN: u32 = 256 * 1024;
+const PCAP_SNAPLEN: u32 = 65535;
+
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LOOP: u32 = 108;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_IPV6: u32 = 229;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88a8;
+const ETHERNET_HEADER_LEN: usize = 14;
+const LINUX_SLL_HEADER_LEN: usize = 16;
+
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+const IPV4_HEADER_LEN: usize = 20;
+const IPV6_HEADER_LEN: usize = 40;
+// Hop-by-hop, routing and destination options extension headers
+const IPV6_SKIPPED_HEADERS: [u8; 3] = [0, 43, 60];
+const TCP_HEADER_LEN: usize = 20;
+const UDP_HEADER_LEN: usize = 8;
+
+const TCP_FIN: u8 = 0x01;
+const TCP_SYN: u8 = 0x02;
+const TCP_PSH: u8 = 0x08;
+const TCP_ACK: u8 = 0x10;
+
+// Fabricated values used when synthesizing a capture.
+const CLIENT_MAC: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x01];
+const SERVER_MAC: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x02];
+const CLIENT_ISN: u32 = 0x0001_0000;
+const SERVER_ISN: u32 = 0x0002_0000;
+const IP_TTL: u8 = 64;
+const TCP_WINDOW: u16 = 0xffff;
+const TCP_MSS: usize = 1460;
+const PACKET_INTERVAL_MICROS: u32 = 1000;
+
+/// Transport layer protocol of a flow.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Transport {
+    Tcp,
+    Udp,
+}
+
+/// A bidirectional transport layer conversation.
+///
+/// Packets sent from `client` to `server` are recorded as
+/// [`Direction::ToServer`] and packets in the opposite direction as
+/// [`Direction::ToClient`].
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Flow {
+    pub transport: Transport,
+    pub client: SocketAddr,
+    pub server: SocketAddr,
+}
+
+impl Flow {
+    pub fn new(transport: Transport, client: SocketAddr, server: SocketAddr) -> Self {
+        Flow {
+            transport,
+            client,
+            server,
+        }
+    }
+
+    /// Direction of a packet sent from `src` to `dst`, if it belongs to this flow.
+    fn direction(
+        &self,
+        transport: Transport,
+        src: SocketAddr,
+        dst: SocketAddr,
+    ) -> Option<Direction> {
+        if transport != self.transport {
+            None
+        } else if src == self.client && dst == self.server {
+            Some(Direction::ToServer)
+        } else if src == self.server && dst == self.client {
+            Some(Direction::ToClient)
+        } else {
+            None
+        }
+    }
+
+    fn contains(&self, transport: Transport, src: SocketAddr, dst: SocketAddr) -> bool {
+        self.direction(transport, src, dst).is_some()
+    }
+}
+
+/// Lists the TCP and UDP flows found in a capture, in order of appearance.
+///
+/// The client of a TCP flow is the sender of the initial SYN when it is
+/// captured. Otherwise the sender of the first packet is assumed to be the
+/// client.
+pub fn flows<R: Read>(input: R) -> Result<Vec<Flow>> {
+    let mut reader = PcapReader::new(input)?;
+    let mut flows: Vec<Flow> = Vec::new();
+
+    while let Some(frame) = reader.next_frame()? {
+        let segment = match Segment::decode(reader.link_type, &frame) {
+            Some(segment) => segment,
+            None => continue,
+        };
+        if flows
+            .iter()
+            .any(|flow| flow.contains(segment.transport, segment.src, segment.dst))
+        {
+            continue;
+        }
+        let flow = if segment.transport == Transport::Tcp
+            && segment.flags & (TCP_SYN | TCP_ACK) == (TCP_SYN | TCP_ACK)
+        {
+            Flow::new(segment.transport, segment.dst, segment.src)
+        } else {
+            Flow::new(segment.transport, segment.src, segment.dst)
+        };
+        flows.push(flow);
+    }
+
+    Ok(flows)
+}
+
+/// Extracts the payloads of `flow` from a capture into a SAWP recording.
+///
+/// Each TCP segment or UDP datagram carrying data becomes a parse call.
+/// Missing TCP bytes, including bytes cut off by the capture's snapshot
+/// length, become gap calls.
+pub fn pcap_to_sawp<R: Read, W: Write>(
+    input: R,
+    flow: &Flow,
+    output: &mut Writer<W>,
+) -> Result<()> {
+    let mut reader = PcapReader::new(input)?;
+    let mut to_server = TcpStream::default();
+    let mut to_client = TcpStream::default();
+
+    while let Some(frame) = reader.next_frame()? {
+        let segment = match Segment::decode(reader.link_type, &frame) {
+            Some(segment) => segment,
+            None => continue,
+        };
+        let direction = match flow.direction(segment.transport, segment.src, segment.dst) {
+            Some(direction) => direction,
+            None => continue,
+        };
+
+        match segment.transport {
+            Transport::Udp => {
+                if !segment.payload.is_empty() {
+                    output.parse(direction, segment.payload)?;
+                }
+            }
+            Transport::Tcp => {
+                let stream = if direction == Direction::ToServer {
+                    &mut to_server
+                } else {
+                    &mut to_client
+                };
+                for chunk in stream.reassemble(&segment) {
+                    match chunk {
+                        Chunk::Data(data) => output.parse(direction, data)?,
+                        Chunk::Gap(gap) => output.gap(direction, gap)?,
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Synthesizes a capture of `flow` from the calls of a SAWP recording.
+///
+/// TCP flows are given a fabricated handshake and teardown, and gaps advance
+/// the sequence number of their direction without sending data. UDP has no
+/// way of representing a gap so gap calls are skipped. Calls with an unknown
+/// direction are sent to the server.
+pub fn sawp_to_pcap<I, W>(calls: I, flow: &Flow, output: W) -> Result<()>
+where
+    I: IntoIterator<Item = Call>,
+    W: Write,
+{
+    let mut builder = FrameBuilder::new(flow)?;
+    let mut writer = PcapWriter::new(output)?;
+
+    if flow.transport == Transport::Tcp {
+        writer.write_frame(&builder.tcp(true, TCP_SYN, &[]))?;
+        writer.write_frame(&builder.tcp(false, TCP_SYN | TCP_ACK, &[]))?;
+        writer.write_frame(&builder.tcp(true, TCP_ACK, &[]))?;
+    }
+
+    for call in calls {
+        match call {
+            Call::Parse(Data { direction, data }) => {
+                let from_client = direction != Direction::ToClient;
+                match flow.transport {
+                    Transport::Tcp => {
+                        for chunk in data.chunks(TCP_MSS) {
+                            writer.write_frame(&builder.tcp(
+                                from_client,
+                                TCP_PSH | TCP_ACK,
+                                chunk,
+                            ))?;
+                        }
+                    }
+                    Transport::Udp => {
+                        for chunk in data.chunks(builder.max_udp_payload()) {
+                            writer.write_frame(&builder.udp(from_client, chunk))?;
+                        }
+                    }
+                }
+            }
+            Call::Gap(Gap { direction, gap }) => {
+                if flow.transport == Transport::Tcp {
+                    builder.skip(direction != Direction::ToClient, gap);
+                }
+            }
+        }
+    }
+
+    if flow.transport == Transport::Tcp {
+        writer.write_frame(&builder.tcp(true, TCP_FIN | TCP_ACK, &[]))?;
+        writer.write_frame(&builder.tcp(false, TCP_FIN | TCP_ACK, &[]))?;
+        writer.write_frame(&builder.tcp(true, TCP_ACK, &[]))?;
+    }
+
+    Ok(())
+}
+
+/// Part of a TCP stream produced by reassembly.
+#[derive(Debug, PartialEq)]
+enum Chunk<'a> {
+    Data(&'a [u8]),
+    Gap(usize),
+}
+
+/// Sequence tracking for one direction of a TCP flow.
+#[derive(Debug, Default)]
+struct TcpStream {
+    /// Sequence number of the next expected byte, once known.
+    next_seq: Option<u32>,
+}
+
+impl TcpStream {
+    /// Returns the chunks needed to record the segment's new bytes.
+    fn reassemble<'a>(&mut self, segment: &Segment<'a>) -> Vec<Chunk<'a>> {
+        let mut chunks = Vec::new();
+        let mut data_seq = segment.seq;
+        if segment.flags & TCP_SYN != 0 {
+            data_seq = data_seq.wrapping_add(1);
+            self.next_seq = Some(data_seq);
+        }
+
+        // Total length of the segment's data, including bytes not captured.
+        let total_len = segment.payload.len() + segment.missing;
+        if total_len == 0 {
+            return chunks;
+        }
+
+        let next_seq = *self.next_seq.get_or_insert(data_seq);
+        let offset = data_seq.wrapping_sub(next_seq) as i32;
+        let end_seq = data_seq.wrapping_add(total_len as u32);
+
+        let skip = if offset < 0 {
+            // Overlaps data that has already been recorded.
+            let overlap = -i64::from(offset) as usize;
+            if overlap >= total_len {
+                return chunks;
+            }
+            overlap
+        } else {
+            if offset > 0 {
+                chunks.push(Chunk::Gap(offset as usize));
+            }
+            0
+        };
+
+        if skip < segment.payload.len() {
+            chunks.push(Chunk::Data(&segment.payload[skip..]));
+        }
+        let missing = std::cmp::min(segment.missing, total_len - skip);
+        if missing > 0 {
+            chunks.push(Chunk::Gap(missing));
+        }
+
+        self.next_seq = Some(end_seq);
+        chunks
+    }
+}
+
+/// Transport layer segment decoded from a captured frame.
+#[derive(Debug)]
+struct Segment<'a> {
+    transport: Transport,
+    src: SocketAddr,
+    dst: SocketAddr,
+    /// TCP sequence number, zero for UDP.
+    seq: u32,
+    /// TCP flags, zero for UDP.
+    flags: u8,
+    /// Captured payload bytes.
+    payload: &'a [u8],
+    /// Number of payload bytes cut off by the snapshot length.
+    missing: usize,
+}
+
+impl<'a> Segment<'a> {
+    fn decode(link_type: u32, frame: &'a [u8]) -> Option<Self> {
+        let packet = match link_type {
+            LINKTYPE_ETHERNET => {
+                let mut ethertype = read_u16(frame, 12)?;
+                let mut offset = ETHERNET_HEADER_LEN;
+                while ethertype == ETHERTYPE_VLAN || ethertype == ETHERTYPE_QINQ {
+                    ethertype = read_u16(frame, offset + 2)?;
+                    offset += 4;
+                }
+                match ethertype {
+                    ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => frame.get(offset..)?,
+                    _ => return None,
+                }
+            }
+            LINKTYPE_LINUX_SLL => match read_u16(frame, 14)? {
+                ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => frame.get(LINUX_SLL_HEADER_LEN..)?,
+                _ => return None,
+            },
+            // The address family is stored in host byte order so rely on the
+            // IP version instead.
+            LINKTYPE_NULL | LINKTYPE_LOOP => frame.get(4..)?,
+            LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => frame,
+            _ => return None,
+        };
+        Segment::decode_ip(packet)
+    }
+
+    fn decode_ip(packet: &'a [u8]) -> Option<Self> {
+        let (protocol, src, dst, payload, missing) = match packet.first()? >> 4 {
+            4 => {
+                let header_len = usize::from(packet.first()? & 0x0f) * 4;
+                let total_len = usize::from(read_u16(packet, 2)?);
+                let fragment = read_u16(packet, 6)?;
+                // Ignore fragments: more fragments flag set or non-zero offset
+                if fragment & 0x3fff != 0 || header_len < IPV4_HEADER_LEN || total_len < header_len
+                {
+                    return None;
+                }
+                let src: [u8; 4] = packet.get(12..16)?.try_into().ok()?;
+                let dst: [u8; 4] = packet.get(16..20)?.try_into().ok()?;
+                let end = std::cmp::min(total_len, packet.len());
+                (
+                    *packet.get(9)?,
+                    IpAddr::from(src),
+                    IpAddr::from(dst),
+                    packet.get(header_len..end)?,
+                    total_len - end,
+                )
+            }
+            6 => {
+                let payload_len = usize::from(read_u16(packet, 4)?);
+                let src: [u8; 16] = packet.get(8..24)?.try_into().ok()?;
+                let dst: [u8; 16] = packet.get(24..40)?.try_into().ok()?;
+                let mut next_header = *packet.get(6)?;
+                let mut offset = IPV6_HEADER_LEN;
+                while IPV6_SKIPPED_HEADERS.contains(&next_header) {
+                    next_header = *packet.get(offset)?;
+                    offset += (usize::from(*packet.get(offset + 1)?) + 1) * 8;
+                }
+                let total_len = IPV6_HEADER_LEN + payload_len;
+                if total_len < offset {
+                    return None;
+                }
+                let end = std::cmp::min(total_len, packet.len());
+                (
+                    next_header,
+                    IpAddr::from(src),
+                    IpAddr::from(dst),
+                    packet.get(offset..end)?,
+                    total_len - end,
+                )
+            }
+            _ => return None,
+        };
+
+        match protocol {
+            IPPROTO_TCP => {
+                let header_len = usize::from(payload.get(12)? >> 4) * 4;
+                if header_len < TCP_HEADER_LEN {
+                    return None;
+                }
+                Some(Segment {
+                    transport: Transport::Tcp,
+                    src: SocketAddr::new(src, read_u16(payload, 0)?),
+                    dst: SocketAddr::new(dst, read_u16(payload, 2)?),
+                    seq: read_u32(payload, 4)?,
+                    flags: *payload.get(13)?,
+                    payload: payload.get(header_len..)?,
+                    missing,
+                })
+            }
+            IPPROTO_UDP => {
+                let length = usize::from(read_u16(payload, 4)?);
+                if length < UDP_HEADER_LEN {
+                    return None;
+                }
+                let end = std::cmp::min(length, payload.len());
+                Some(Segment {
+                    transport: Transport::Udp,
+                    src: SocketAddr::new(src, read_u16(payload, 0)?),
+                    dst: SocketAddr::new(dst, read_u16(payload, 2)?),
+                    seq: 0,
+                    flags: 0,
+                    payload: payload.get(UDP_HEADER_LEN..end)?,
+                    missing: 0,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn read_u16(input: &[u8], offset: usize) -> Option<u16> {
+    let bytes = input.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(input: &[u8], offset: usize) -> Option<u32> {
+    let bytes = input.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads frames from a classic pcap capture.
+struct PcapReader<R: Read> {
+    inner: R,
+    /// Whether the capture's byte order differs from big endian.
+    little_endian: bool,
+    link_type: u32,
+}
+
+impl<R: Read> PcapReader<R> {
+    fn new(mut inner: R) -> Result<Self> {
+        let mut header = [0u8; PCAP_GLOBAL_HEADER_LEN];
+        inner.read_exact(&mut header)?;
+
+        let magic = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let little_endian = match magic {
+            PCAP_MAGIC_MICROS | PCAP_MAGIC_NANOS => false,
+            _ if magic.swap_bytes() == PCAP_MAGIC_MICROS
+                || magic.swap_bytes() == PCAP_MAGIC_NANOS =>
+            {
+                true
+            }
+            _ => {
+                return Err(Error::new(ErrorKind::PcapFormat(format!(
+                    "unknown magic number {:#010x}",
+                    magic
+                ))))
+            }
+        };
+
+        let mut reader = PcapReader {
+            inner,
+            little_endian,
+            link_type: 0,
+        };
+        reader.link_type = reader.u32_at(&header, 20);
+        match reader.link_type {
+            LINKTYPE_NULL | LINKTYPE_ETHERNET | LINKTYPE_RAW | LINKTYPE_LOOP
+            | LINKTYPE_LINUX_SLL | LINKTYPE_IPV4 | LINKTYPE_IPV6 => Ok(reader),
+            link_type => Err(Error::new(ErrorKind::UnsupportedLinkType(link_type))),
+        }
+    }
+
+    fn u32_at(&self, input: &[u8], offset: usize) -> u32 {
+        let bytes = [
+            input[offset],
+            input[offset + 1],
+            input[offset + 2],
+            input[offset + 3],
+        ];
+        if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        }
+    }
+
+    /// Returns the next captured frame. A truncated final record ends the capture.
+    fn next_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut header = [0u8; PCAP_RECORD_HEADER_LEN];
+        if !self.fill(&mut header)? {
+            return Ok(None);
+        }
+
+        let captured_len = self.u32_at(&header, 8);
+        if captured_len > PCAP_MAX_RECORD_LEN {
+            return Err(Error::new(ErrorKind::PcapFormat(format!(
+                "record length {} exceeds maximum",
+                captured_len
+            ))));
+        }
+
+        let mut frame = vec![0u8; captured_len as usize];
+        if !self.fill(&mut frame)? {
+            return Ok(None);
+        }
+        Ok(Some(frame))
+    }
+
+    /// Fills `buffer` completely, returning false if the input ended first.
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<bool> {
+        match self.inner.read_exact(buffer) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Writes Ethernet frames to a classic pcap capture.
+struct PcapWriter<W: Write> {
+    inner: W,
+    timestamp_micros: u64,
+}
+
+impl<W: Write> PcapWriter<W> {
+    fn new(mut inner: W) -> Result<Self> {
+        let mut header = Vec::with_capacity(PCAP_GLOBAL_HEADER_LEN);
+        header.extend_from_slice(&PCAP_MAGIC_MICROS.to_le_bytes());
+        header.extend_from_slice(&PCAP_VERSION_MAJOR.to_le_bytes());
+        header.extend_from_slice(&PCAP_VERSION_MINOR.to_le_bytes());
+        // Timezone offset and timestamp accuracy
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&PCAP_SNAPLEN.to_le_bytes());
+        header.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+        inner.write_all(&header)?;
+
+        Ok(PcapWriter {
+            inner,
+            timestamp_micros: 0,
+        })
+    }
+
+    fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.timestamp_micros += u64::from(PACKET_INTERVAL_MICROS);
+        let seconds = (self.timestamp_micros / 1_000_000) as u32;
+        let micros = (self.timestamp_micros % 1_000_000) as u32;
+        let len = frame.len() as u32;
+
+        let mut header = Vec::with_capacity(PCAP_RECORD_HEADER_LEN);
+        header.extend_from_slice(&seconds.to_le_bytes());
+        header.extend_from_slice(&micros.to_le_bytes());
+        header.extend_from_slice(&len.to_le_bytes());
+        header.extend_from_slice(&len.to_le_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(frame)?;
+        Ok(())
+    }
+}
+
+/// Builds frames with fabricated headers for a flow.
+struct FrameBuilder<'a> {
+    flow: &'a Flow,
+    client_seq: u32,
+    server_seq: u32,
+}
+
+impl<'a> FrameBuilder<'a> {
+    fn new(flow: &'a Flow) -> Result<Self> {
+        if flow.client.is_ipv4() != flow.server.is_ipv4() {
+            return Err(Error::new(ErrorKind::AddressFamilyMismatch));
+        }
+        Ok(FrameBuilder {
+            flow,
+            client_seq: CLIENT_ISN,
+            server_seq: SERVER_ISN,
+        })
+    }
+
+    fn max_udp_payload(&self) -> usize {
+        let ip_header_len = if self.flow.client.is_ipv4() {
+            IPV4_HEADER_LEN
+        } else {
+            IPV6_HEADER_LEN
+        };
+        usize::from(u16::MAX) - ip_header_len - UDP_HEADER_LEN
+    }
+
+    /// Advances the sequence number of one direction without sending data.
+    fn skip(&mut self, from_client: bool, len: usize) {
+        let seq = if from_client {
+            &mut self.client_seq
+        } else {
+            &mut self.server_seq
+        };
+        *seq = seq.wrapping_add(len as u32);
+    }
+
+    fn endpoints(&self, from_client: bool) -> (SocketAddr, SocketAddr) {
+        if from_client {
+            (self.flow.client, self.flow.server)
+        } else {
+            (self.flow.server, self.flow.client)
+        }
+    }
+
+    fn tcp(&mut self, from_client: bool, flags: u8, payload: &[u8]) -> Vec<u8> {
+        let (src, dst) = self.endpoints(from_client);
+        let (seq, ack) = if from_client {
+            (self.client_seq, self.server_seq)
+        } else {
+            (self.server_seq, self.client_seq)
+        };
+
+        let mut segment = Vec::with_capacity(TCP_HEADER_LEN + payload.len());
+        segment.extend_from_slice(&src.port().to_be_bytes());
+        segment.extend_from_slice(&dst.port().to_be_bytes());
+        segment.extend_from_slice(&seq.to_be_bytes());
+        // The initial SYN doesn't acknowledge anything
+        let ack = if flags & TCP_ACK != 0 { ack } else { 0 };
+        segment.extend_from_slice(&ack.to_be_bytes());
+        segment.push(((TCP_HEADER_LEN / 4) as u8) << 4);
+        segment.push(flags);
+        segment.extend_from_slice(&TCP_WINDOW.to_be_bytes());
+        // Checksum and urgent pointer
+        segment.extend_from_slice(&[0, 0, 0, 0]);
+        segment.extend_from_slice(payload);
+
+        let checksum = transport_checksum(src.ip(), dst.ip(), IPPROTO_TCP, &segment);
+        segment[16..18].copy_from_slice(&checksum.to_be_bytes());
+
+        let mut consumed = payload.len() as u32;
+        if flags & (TCP_SYN | TCP_FIN) != 0 {
+            consumed += 1;
+        }
+        self.skip(from_client, consumed as usize);
+
+        self.frame(from_client, IPPROTO_TCP, &segment)
+    }
+
+    fn udp(&self, from_client: bool, payload: &[u8]) -> Vec<u8> {
+        let (src, dst) = self.endpoints(from_client);
+        let length = (UDP_HEADER_LEN + payload.len()) as u16;
+
+        let mut datagram = Vec::with_capacity(usize::from(length));
+        datagram.extend_from_slice(&src.port().to_be_bytes());
+        datagram.extend_from_slice(&dst.port().to_be_bytes());
+        datagram.extend_from_slice(&length.to_be_bytes());
+        datagram.extend_from_slice(&[0, 0]);
+        datagram.extend_from_slice(payload);
+
+        let checksum = match transport_checksum(src.ip(), dst.ip(), IPPROTO_UDP, &datagram) {
+            // A computed checksum of zero is transmitted as all ones
+            0 => 0xffff,
+            checksum => checksum,
+        };
+        datagram[6..8].copy_from_slice(&checksum.to_be_bytes());
+
+        self.frame(from_client, IPPROTO_UDP, &datagram)
+    }
+
+    fn frame(&self, from_client: bool, protocol: u8, payload: &[u8]) -> Vec<u8> {
+        let (src, dst) = self.endpoints(from_client);
+        let (src_mac, dst_mac) = if from_client {
+            (CLIENT_MAC, SERVER_MAC)
+        } else {
+            (SERVER_MAC, CLIENT_MAC)
+        };
+
+        let mut frame = Vec::with_capacity(ETHERNET_HEADER_LEN + IPV6_HEADER_LEN + payload.len());
+        frame.extend_from_slice(&dst_mac);
+        frame.extend_from_slice(&src_mac);
+
+        match (src.ip(), dst.ip()) {
+            (IpAddr::V4(src), IpAddr::V4(dst)) => {
+                frame.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+                let mut header = Vec::with_capacity(IPV4_HEADER_LEN);
+                // Version 4 with a 5 word header, default type of service
+                header.extend_from_slice(&[0x45, 0x00]);
+                header.extend_from_slice(&((IPV4_HEADER_LEN + payload.len()) as u16).to_be_bytes());
+                // Identification, flags and fragment offset
+                header.extend_from_slice(&[0, 0, 0, 0]);
+                header.extend_from_slice(&[IP_TTL, protocol, 0, 0]);
+                header.extend_from_slice(&src.octets());
+                header.extend_from_slice(&dst.octets());
+                let checksum = checksum(&header);
+                header[10..12].copy_from_slice(&checksum.to_be_bytes());
+                frame.extend_from_slice(&header);
+            }
+            (IpAddr::V6(src), IpAddr::V6(dst)) => {
+                frame.extend_from_slice(&ETHERTYPE_IPV6.to_be_bytes());
+                // Version 6, no traffic class or flow label
+                frame.extend_from_slice(&[0x60, 0x00, 0x00, 0x00]);
+                frame.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+                frame.extend_from_slice(&[protocol, IP_TTL]);
+                frame.extend_from_slice(&src.octets());
+                frame.extend_from_slice(&dst.octets());
+            }
+            // Rejected by FrameBuilder::new
+            _ => unreachable!("flow endpoints use different address families"),
+        }
+
+        frame.extend_from_slice(payload);
+        frame
+    }
+}
+
+/// Internet checksum as described in [RFC1071](https://tools.ietf.org/html/rfc1071).
+fn checksum(input: &[u8]) -> u16 {
+    let mut sum: u32 = input
+        .chunks(2)
+        .map(|word| match word {
+            [high, low] => u32::from(u16::from_be_bytes([*high, *low])),
+            [high] => u32::from(*high) << 8,
+            _ => 0,
+        })
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// TCP/UDP checksum over the IP pseudo-header and segment.
+fn transport_checksum(src: IpAddr, dst: IpAddr, protocol: u8, segment: &[u8]) -> u16 {
+    let mut pseudo = Vec::with_capacity(IPV6_HEADER_LEN + segment.len());
+    match (src, dst) {
+        (IpAddr::V4(src), IpAddr::V4(dst)) => {
+            pseudo.extend_from_slice(&src.octets());
+            pseudo.extend_from_slice(&dst.octets());
+            pseudo.extend_from_slice(&[0, protocol]);
+            pseudo.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+        }
+        (src, dst) => {
+            pseudo.extend_from_slice(&ipv6_octets(src));
+            pseudo.extend_from_slice(&ipv6_octets(dst));
+            pseudo.extend_from_slice(&(segment.len() as u32).to_be_bytes());
+            pseudo.extend_from_slice(&[0, 0, 0, protocol]);
+        }
+    }
+    pseudo.extend_from_slice(segment);
+    checksum(&pseudo)
+}
+
+fn ipv6_octets(addr: IpAddr) -> [u8; 16] {
+    match addr {
+        IpAddr::V4(addr) => addr.to_ipv6_mapped().octets(),
+        IpAddr::V6(addr) => addr.octets(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Reader;
+
+    fn tcp_flow() -> Flow {
+        Flow::new(
+            Transport::Tcp,
+            "192.168.0.1:49152".parse().unwrap(),
+            "192.168.0.2:502".parse().unwrap(),
+        )
+    }
+
+    fn record(calls: &[Call]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).unwrap();
+        for call in calls {
+            match call {
+                Call::Parse(Data { direction, data }) => writer.parse(*direction, data).unwrap(),
+                Call::Gap(Gap { direction, gap }) => writer.gap(*direction, *gap).unwrap(),
+            }
+        }
+        buffer
+    }
+
+    fn parse(direction: Direction, data: &[u8]) -> Call {
+        Call::Parse(Data {
+            direction,
+            data: data.to_vec(),
+        })
+    }
+
+    fn gap(direction: Direction, gap: usize) -> Call {
+        Call::Gap(Gap { direction, gap })
+    }
+
+    fn round_trip(calls: Vec<Call>, flow: &Flow) -> Vec<Call> {
+        let mut capture = Vec::new();
+        sawp_to_pcap(calls, flow, &mut capture).unwrap();
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).unwrap();
+        pcap_to_sawp(capture.as_slice(), flow, &mut writer).unwrap();
+        Reader::new(buffer.as_slice()).unwrap().collect()
+    }
+
+    #[test]
+    fn test_tcp_round_trip() {
+        let calls = vec![
+            parse(Direction::ToServer, b"request"),
+            parse(Direction::ToClient, b"response"),
+            gap(Direction::ToServer, 10),
+            parse(Direction::ToServer, b"after gap"),
+        ];
+        assert_eq!(calls, round_trip(calls.clone(), &tcp_flow()));
+    }
+
+    #[test]
+    fn test_tcp_round_trip_ipv6_segmented() {
+        let flow = Flow::new(
+            Transport::Tcp,
+            "[2001:db8::1]:49152".parse().unwrap(),
+            "[2001:db8::2]:53".parse().unwrap(),
+        );
+        let data = vec![0x41; TCP_MSS * 2 + 10];
+        let expected = vec![
+            parse(Direction::ToServer, &data[..TCP_MSS]),
+            parse(Direction::ToServer, &data[TCP_MSS..TCP_MSS * 2]),
+            parse(Direction::ToServer, &data[TCP_MSS * 2..]),
+        ];
+        assert_eq!(
+            expected,
+            round_trip(vec![parse(Direction::ToServer, &data)], &flow)
+        );
+    }
+
+    #[test]
+    fn test_udp_round_trip() {
+        let flow = Flow::new(
+            Transport::Udp,
+            "10.0.0.1:5353".parse().unwrap(),
+            "10.0.0.2:53".parse().unwrap(),
+        );
+        let calls = vec![
+            parse(Direction::ToServer, b"query"),
+            gap(Direction::ToServer, 10),
+            parse(Direction::ToClient, b"answer"),
+        ];
+        let expected = vec![
+            parse(Direction::ToServer, b"query"),
+            parse(Direction::ToClient, b"answer"),
+        ];
+        assert_eq!(expected, round_trip(calls, &flow));
+    }
+
+    #[test]
+    fn test_unknown_direction() {
+        let calls = vec![parse(Direction::Unknown, b"data")];
+        let expected = vec![parse(Direction::ToServer, b"data")];
+        assert_eq!(expected, round_trip(calls, &tcp_flow()));
+    }
+
+    #[test]
+    fn test_flows() {
+        let flow = tcp_flow();
+        let mut capture = Vec::new();
+        sawp_to_pcap(
+            vec![parse(Direction::ToClient, b"response")],
+            &flow,
+            &mut capture,
+        )
+        .unwrap();
+        assert_eq!(vec![flow], flows(capture.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn test_other_flows_ignored() {
+        let flow = tcp_flow();
+        let other = Flow::new(
+            Transport::Tcp,
+            "192.168.0.3:49152".parse().unwrap(),
+            "192.168.0.2:502".parse().unwrap(),
+        );
+        let mut capture = Vec::new();
+        sawp_to_pcap(
+            vec![parse(Direction::ToServer, b"request")],
+            &other,
+            &mut capture,
+        )
+        .unwrap();
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).unwrap();
+        pcap_to_sawp(capture.as_slice(), &flow, &mut writer).unwrap();
+        assert_eq!(buffer, record(&[]));
+    }
+
+    #[test]
+    fn test_retransmission_and_gap() {
+        let flow = tcp_flow();
+        let segment = |seq: u32, flags: u8, payload: &'static [u8], missing: usize| Segment {
+            transport: Transport::Tcp,
+            src: flow.client,
+            dst: flow.server,
+            seq,
+            flags,
+            payload,
+            missing,
+        };
+
+        let mut stream = TcpStream::default();
+        assert!(stream.reassemble(&segment(99, TCP_SYN, b"", 0)).is_empty());
+        assert_eq!(
+            vec![Chunk::Data(b"abcd")],
+            stream.reassemble(&segment(100, TCP_ACK, b"abcd", 0))
+        );
+        // Full retransmission
+        assert!(stream
+            .reassemble(&segment(100, TCP_ACK, b"abcd", 0))
+            .is_empty());
+        // Partial overlap
+        assert_eq!(
+            vec![Chunk::Data(b"ef")],
+            stream.reassemble(&segment(102, TCP_ACK, b"cdef", 0))
+        );
+        // Hole followed by data cut off by the snapshot length
+        assert_eq!(
+            vec![Chunk::Gap(4), Chunk::Data(b"kl"), Chunk::Gap(3)],
+            stream.reassemble(&segment(110, TCP_ACK, b"kl", 3))
+        );
+        assert_eq!(Some(115), stream.next_seq);
+    }
+
+    #[test]
+    fn test_big_endian_raw_capture() {
+        let mut capture = Vec::new();
+        capture.extend_from_slice(&PCAP_MAGIC_NANOS.to_be_bytes());
+        capture.extend_from_slice(&PCAP_VERSION_MAJOR.to_be_bytes());
+        capture.extend_from_slice(&PCAP_VERSION_MINOR.to_be_bytes());
+        capture.extend_from_slice(&[0; 8]);
+        capture.extend_from_slice(&PCAP_SNAPLEN.to_be_bytes());
+        capture.extend_from_slice(&LINKTYPE_RAW.to_be_bytes());
+
+        let packet = [
+            // IPv4, total length 33, UDP
+            0x45, 0x00, 0x00, 0x21, 0x00, 0x00, 0x00, 0x00, 0x40, 0x11, 0x00, 0x00, //
+            0x0a, 0x00, 0x00, 0x01, // 10.0.0.1
+            0x0a, 0x00, 0x00, 0x02, // 10.0.0.2
+            // Ports 1024 -> 53, length 13
+            0x04, 0x00, 0x00, 0x35, 0x00, 0x0d, 0x00, 0x00, //
+            b'h', b'e', b'l', b'l', b'o',
+        ];
+        capture.extend_from_slice(&[0; 8]);
+        capture.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+        capture.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+        capture.extend_from_slice(&packet);
+
+        let flow = Flow::new(
+            Transport::Udp,
+            "10.0.0.1:1024".parse().unwrap(),
+            "10.0.0.2:53".parse().unwrap(),
+        );
+        assert_eq!(vec![flow], flows(capture.as_slice()).unwrap());
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).unwrap();
+        pcap_to_sawp(capture.as_slice(), &flow, &mut writer).unwrap();
+        assert_eq!(buffer, record(&[parse(Direction::ToServer, b"hello")]));
+    }
+
+    #[test]
+    fn test_checksum() {
+        // Example IPv4 header with a known checksum of 0xb861
+        let header = [
+            0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0xc0, 0xa8,
+            0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7,
+        ];
+        assert_eq!(0xb861, checksum(&header));
+    }
+
+    #[should_panic(expected = "PcapFormat")]
+    #[test]
+    fn test_bad_magic() {
+        let _ = flows([0u8; PCAP_GLOBAL_HEADER_LEN].as_ref()).unwrap();
+    }
+
+    #[should_panic(expected = "AddressFamilyMismatch")]
+    #[test]
+    fn test_address_family_mismatch() {
+        let flow = Flow::new(
+            Transport::Tcp,
+            "10.0.0.1:1024".parse().unwrap(),
+            "[2001:db8::2]:53".parse().unwrap(),
+        );
+        sawp_to_pcap(vec![], &flow, Vec::new()).unwrap();
+    }
+}
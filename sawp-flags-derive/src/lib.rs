@@ -83,7 +83,7 @@ fn impl_enum_traits(name: &syn::Ident, repr: &Ident, data: &syn::DataEnum) -> To
             }
         }
 
-        impl std::ops::BitOr for #name {
+        impl core::ops::BitOr for #name {
             type Output = Flags<#name>;
 
             fn bitor(self, other: Self) -> Self::Output {
@@ -91,7 +91,7 @@ fn impl_enum_traits(name: &syn::Ident, repr: &Ident, data: &syn::DataEnum) -> To
             }
         }
 
-        impl std::ops::BitAnd for #name {
+        impl core::ops::BitAnd for #name {
             type Output = Flags<#name>;
 
             fn bitand(self, other: Self) -> Self::Output {
@@ -99,7 +99,7 @@ fn impl_enum_traits(name: &syn::Ident, repr: &Ident, data: &syn::DataEnum) -> To
             }
         }
 
-        impl std::ops::BitXor for #name {
+        impl core::ops::BitXor for #name {
             type Output = Flags<#name>;
 
             fn bitxor(self, other: Self) -> Self::Output {
@@ -107,7 +107,7 @@ fn impl_enum_traits(name: &syn::Ident, repr: &Ident, data: &syn::DataEnum) -> To
             }
         }
 
-        impl std::ops::Not for #name {
+        impl core::ops::Not for #name {
             type Output = Flags<#name>;
 
             fn not(self) -> Self::Output {
@@ -115,8 +115,8 @@ fn impl_enum_traits(name: &syn::Ident, repr: &Ident, data: &syn::DataEnum) -> To
             }
         }
 
-        impl std::fmt::Display for #name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        impl core::fmt::Display for #name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                 let empty = self.bits() == Self::none().bits();
                 let mut first = true;
                 #(
@@ -138,13 +138,15 @@ fn impl_enum_traits(name: &syn::Ident, repr: &Ident, data: &syn::DataEnum) -> To
             }
         }
 
-        impl std::str::FromStr for #name {
+        impl core::str::FromStr for #name {
             type Err = ();
-            fn from_str(val: &str) -> std::result::Result<#name, Self::Err> {
-                match val.to_lowercase().as_str() {
-                    #(stringify!(#from_str_items_str) => Ok(#name::#from_str_items),)*
-                    _ => Err(()),
-                }
+            fn from_str(val: &str) -> core::result::Result<#name, Self::Err> {
+                #(
+                    if val.eq_ignore_ascii_case(stringify!(#from_str_items_str)) {
+                        return Ok(#name::#from_str_items);
+                    }
+                )*
+                Err(())
             }
         }
 
@@ -154,9 +156,9 @@ fn impl_enum_traits(name: &syn::Ident, repr: &Ident, data: &syn::DataEnum) -> To
             }
         }
 
-        impl std::fmt::Binary for #name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                std::fmt::Binary::fmt(&self.bits(), f)
+        impl core::fmt::Binary for #name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Binary::fmt(&self.bits(), f)
             }
         }
     }
@@ -55,8 +55,12 @@
 //! assert!(flags.intersects(Test::A));
 //! assert!(flags.intersects(Test::A | Test::B));
 //! ```
+//!
+//! This crate is `no_std` and does not allocate.
+
+#![cfg_attr(not(test), no_std)]
 
-use std::ops::*;
+use core::ops::*;
 
 /// The `BitFlags` derive macro will implement the `Flags` Trait on your enum and
 /// provide convenience methods for bit operations and type conversions.
@@ -72,8 +76,8 @@ pub trait Primitive:
     + BitXor<Self, Output = Self>
     + Not<Output = Self>
     + PartialOrd<Self>
-    + std::fmt::Debug
-    + std::fmt::Binary
+    + core::fmt::Debug
+    + core::fmt::Binary
     + Copy
     + Clone
 {
@@ -86,7 +90,7 @@ impl Primitive for u64 {}
 impl Primitive for u128 {}
 
 /// A trait implemented by all flag enums.
-pub trait Flag: Copy + Clone + std::fmt::Debug + std::fmt::Display + 'static {
+pub trait Flag: Copy + Clone + core::fmt::Debug + core::fmt::Display + 'static {
     /// Associated primitive numeric type
     type Primitive: Primitive;
 
@@ -108,14 +112,14 @@ pub trait Flag: Copy + Clone + std::fmt::Debug + std::fmt::Display + 'static {
 #[repr(transparent)]
 pub struct Flags<Enum, Primitive = <Enum as Flag>::Primitive> {
     val: Primitive,
-    marker: std::marker::PhantomData<Enum>,
+    marker: core::marker::PhantomData<Enum>,
 }
 
-impl<Enum> std::fmt::Debug for Flags<Enum>
+impl<Enum> core::fmt::Debug for Flags<Enum>
 where
     Enum: Flag,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.val.fmt(f)
     }
 }
@@ -127,7 +131,7 @@ where
     fn default() -> Self {
         Self {
             val: <Enum as Flag>::Primitive::default(),
-            marker: std::marker::PhantomData,
+            marker: core::marker::PhantomData,
         }
     }
 }
@@ -140,7 +144,7 @@ where
     pub fn from_flag(flag: Enum) -> Self {
         Self {
             val: flag.bits(),
-            marker: std::marker::PhantomData,
+            marker: core::marker::PhantomData,
         }
     }
 
@@ -152,7 +156,7 @@ where
     pub fn from_bits(bits: <Enum as Flag>::Primitive) -> Self {
         Self {
             val: bits,
-            marker: std::marker::PhantomData,
+            marker: core::marker::PhantomData,
         }
     }
 
@@ -186,6 +190,17 @@ where
     }
 }
 
+#[cfg(feature = "serialize")]
+impl<Enum> serde::Serialize for Flags<Enum>
+where
+    Enum: Flag,
+    <Enum as Flag>::Primitive: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.val.serialize(serializer)
+    }
+}
+
 impl<Enum: Flag> From<Enum> for Flags<Enum> {
     fn from(flag: Enum) -> Self {
         Self::from_flag(flag)
@@ -198,7 +213,7 @@ impl<Enum: Flag> PartialEq<Enum> for Flags<Enum> {
     }
 }
 
-impl<T, B> std::ops::BitOr<B> for Flags<T>
+impl<T, B> core::ops::BitOr<B> for Flags<T>
 where
     T: Flag,
     B: Into<Flags<T>>,
@@ -209,7 +224,7 @@ where
     }
 }
 
-impl<T, B> std::ops::BitOrAssign<B> for Flags<T>
+impl<T, B> core::ops::BitOrAssign<B> for Flags<T>
 where
     T: Flag,
     B: Into<Flags<T>>,
@@ -219,7 +234,7 @@ where
     }
 }
 
-impl<T, B> std::ops::BitAnd<B> for Flags<T>
+impl<T, B> core::ops::BitAnd<B> for Flags<T>
 where
     T: Flag,
     B: Into<Flags<T>>,
@@ -230,7 +245,7 @@ where
     }
 }
 
-impl<T, B> std::ops::BitAndAssign<B> for Flags<T>
+impl<T, B> core::ops::BitAndAssign<B> for Flags<T>
 where
     T: Flag,
     B: Into<Flags<T>>,
@@ -240,7 +255,7 @@ where
     }
 }
 
-impl<T, B> std::ops::BitXor<B> for Flags<T>
+impl<T, B> core::ops::BitXor<B> for Flags<T>
 where
     T: Flag,
     B: Into<Flags<T>>,
@@ -251,7 +266,7 @@ where
     }
 }
 
-impl<T, B> std::ops::BitXorAssign<B> for Flags<T>
+impl<T, B> core::ops::BitXorAssign<B> for Flags<T>
 where
     T: Flag,
     B: Into<Flags<T>>,
@@ -261,7 +276,7 @@ where
     }
 }
 
-impl<T: Flag> std::ops::Not for Flags<T> {
+impl<T: Flag> core::ops::Not for Flags<T> {
     type Output = Flags<T>;
 
     fn not(self) -> Self::Output {
@@ -269,9 +284,9 @@ impl<T: Flag> std::ops::Not for Flags<T> {
     }
 }
 
-impl<T: Flag> std::fmt::Display for Flags<T> {
+impl<T: Flag> core::fmt::Display for Flags<T> {
     /// A pipe-separated list of set flags.
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let none = self.bits() == T::none().bits();
         let mut first = true;
         for val in <T as Flag>::ITEMS
@@ -295,9 +310,9 @@ impl<T: Flag> std::fmt::Display for Flags<T> {
     }
 }
 
-impl<T: Flag> std::fmt::Binary for Flags<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Binary::fmt(&self.bits(), f)
+impl<T: Flag> core::fmt::Binary for Flags<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Binary::fmt(&self.bits(), f)
     }
 }
 
@@ -0,0 +1,464 @@
+//! SAWP Golden Output Regression Testing
+//!
+//! Replays [sawp-file](sawp_file) recordings through a parser and compares
+//! everything the parser produced against a stored JSON rendering, the
+//! "golden" output.
+//!
+//! A test directory contains pairs of files: `session.sawp` is a recording
+//! and `session.json` is the output expected when replaying it. Recordings
+//! can be created with [`sawp_file::format::Writer`] or extracted from a pcap
+//! capture with [`sawp_file::pcap::pcap_to_sawp`].
+//!
+//! Each recorded call produces zero or more [`Event`]s:
+//! - every message returned by the parser, including its error flags,
+//! - every error other than `Incomplete`, after which the buffered data for
+//!   that direction is discarded,
+//! - every gap, which also discards the buffered data for that direction,
+//! - bytes left in a direction's buffer when the recording ends.
+//!
+//! Parsed messages must implement `serde::Serialize`. The protocol crates
+//! derive it behind their `serialize` feature.
+//!
+//! # Updating golden files
+//!
+//! Set the environment variable named by [`UPDATE_ENV`] to rewrite the
+//! expected output from the current parsers instead of comparing against it:
+//!
+//! ```text
+//! SAWP_UPDATE_GOLDEN=1 cargo test -p sawp-golden
+//! ```
+//!
+//! Review the resulting diff before committing it.
+//!
+//! # Example
+//! ```no_run
+//! use sawp_dns::Dns;
+//!
+//! #[test]
+//! fn dns() {
+//!     sawp_golden::check_dir(&Dns::default(), "tests/golden/dns");
+//! }
+//! ```
+
+#[macro_use]
+extern crate serde_derive;
+
+use sawp::error::ErrorKind;
+use sawp::parser::Parse;
+use sawp::protocol::Protocol;
+use sawp_file::format::{Call, Direction, Reader};
+use serde::Serialize;
+use serde_json::Value;
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Environment variable which switches [`check_dir`] to updating golden files.
+pub const UPDATE_ENV: &str = "SAWP_UPDATE_GOLDEN";
+
+/// Extension of recorded sessions.
+pub const RECORDING_EXTENSION: &str = "sawp";
+
+/// Extension of the expected output for a recorded session.
+pub const GOLDEN_EXTENSION: &str = "json";
+
+/// Observable result of replaying a recording.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A message returned by the parser.
+    Message {
+        direction: Direction,
+        message: Value,
+    },
+    /// An error returned by the parser.
+    Error { direction: Direction, error: String },
+    /// A gap in the recording.
+    Gap { direction: Direction, size: usize },
+    /// Bytes the parser did not consume by the end of the recording.
+    Trailing { direction: Direction, size: usize },
+}
+
+/// Input which has not been consumed by the parser yet.
+#[derive(Default)]
+struct Buffers {
+    to_server: Vec<u8>,
+    to_client: Vec<u8>,
+    unknown: Vec<u8>,
+}
+
+impl Buffers {
+    fn get(&mut self, direction: Direction) -> &mut Vec<u8> {
+        match direction {
+            Direction::ToServer => &mut self.to_server,
+            Direction::ToClient => &mut self.to_client,
+            Direction::Unknown => &mut self.unknown,
+        }
+    }
+}
+
+/// Replay the recorded calls through `parser` and collect the resulting events.
+///
+/// Data is buffered per direction so messages split across calls are parsed
+/// once enough data has arrived.
+pub fn replay<P, I>(parser: &P, calls: I) -> Vec<Event>
+where
+    P: for<'a> Parse<'a>,
+    for<'a> <P as Protocol<'a>>::Message: Serialize,
+    I: IntoIterator<Item = Call>,
+{
+    let mut events = Vec::new();
+    let mut buffers = Buffers::default();
+
+    for call in calls {
+        match call {
+            Call::Parse(data) => {
+                let direction = data.direction();
+                let buffer = buffers.get(direction);
+                buffer.extend_from_slice(data.data());
+                parse_buffer(parser, direction, buffer, &mut events);
+            }
+            Call::Gap(gap) => {
+                buffers.get(gap.direction()).clear();
+                events.push(Event::Gap {
+                    direction: gap.direction(),
+                    size: gap.gap(),
+                });
+            }
+        }
+    }
+
+    for direction in &[Direction::ToServer, Direction::ToClient, Direction::Unknown] {
+        let size = buffers.get(*direction).len();
+        if size > 0 {
+            events.push(Event::Trailing {
+                direction: *direction,
+                size,
+            });
+        }
+    }
+
+    events
+}
+
+/// Parse as many messages as possible out of `buffer`, removing the consumed bytes.
+fn parse_buffer<P>(parser: &P, direction: Direction, buffer: &mut Vec<u8>, events: &mut Vec<Event>)
+where
+    P: for<'a> Parse<'a>,
+    for<'a> <P as Protocol<'a>>::Message: Serialize,
+{
+    while !buffer.is_empty() {
+        let consumed = match parser.parse(buffer, direction.into()) {
+            Ok((rest, message)) => {
+                let consumed = buffer.len() - rest.len();
+                if let Some(message) = message {
+                    events.push(Event::Message {
+                        direction,
+                        message: serde_json::to_value(&message)
+                            .expect("failed to serialize message"),
+                    });
+                }
+                // A parser which stops making progress would loop forever
+                if consumed == 0 {
+                    break;
+                }
+                consumed
+            }
+            Err(err) => {
+                if let ErrorKind::Incomplete(_) = err.kind {
+                    break;
+                }
+                events.push(Event::Error {
+                    direction,
+                    error: format!("{:?}", err.kind),
+                });
+                buffer.len()
+            }
+        };
+        buffer.drain(..consumed);
+    }
+}
+
+/// Render events the way they are stored in golden files.
+///
+/// This is pretty printed JSON except that arrays of numbers, usually raw
+/// bytes, are kept on a single line to keep the files readable.
+pub fn render(events: &[Event]) -> String {
+    let value = serde_json::to_value(events).expect("failed to serialize events");
+    let mut out = String::new();
+    render_value(&value, 0, &mut out);
+    out.push('\n');
+    out
+}
+
+fn render_value(value: &Value, indent: usize, out: &mut String) {
+    let pad = |out: &mut String, indent: usize| out.push_str(&"  ".repeat(indent));
+    match value {
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Array(items) if items.iter().all(Value::is_number) => {
+            let items: Vec<String> = items.iter().map(Value::to_string).collect();
+            out.push('[');
+            out.push_str(&items.join(", "));
+            out.push(']');
+        }
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                pad(out, indent + 1);
+                render_value(item, indent + 1, out);
+                out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            pad(out, indent);
+            out.push(']');
+        }
+        Value::Object(fields) if fields.is_empty() => out.push_str("{}"),
+        Value::Object(fields) => {
+            out.push_str("{\n");
+            for (i, (key, field)) in fields.iter().enumerate() {
+                pad(out, indent + 1);
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push_str(": ");
+                render_value(field, indent + 1, out);
+                out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+            }
+            pad(out, indent);
+            out.push('}');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
+
+/// Describe the first difference between the expected and actual output.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let line = expected
+        .iter()
+        .zip(actual.iter())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.len().min(actual.len()));
+    let start = line.saturating_sub(3);
+    let context = |lines: &[&str]| {
+        lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(line - start + 4)
+            .map(|(i, l)| format!("{:>5} | {}", i + 1, l))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    format!(
+        "first difference at line {}\n--- expected\n{}\n+++ actual\n{}",
+        line + 1,
+        context(&expected),
+        context(&actual)
+    )
+}
+
+/// All recordings in a directory, sorted by name.
+fn recordings(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", dir.display(), err))
+        .map(|entry| entry.expect("failed to read directory entry").path())
+        .filter(|path| path.extension() == Some(RECORDING_EXTENSION.as_ref()))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Replay one recording and compare against its golden file.
+///
+/// Returns a description of the mismatch, if any. When `update` is set, the
+/// golden file is rewritten instead.
+pub fn check_file<P>(parser: &P, recording: &Path, update: bool) -> Option<String>
+where
+    P: for<'a> Parse<'a>,
+    for<'a> <P as Protocol<'a>>::Message: Serialize,
+{
+    let file = File::open(recording)
+        .unwrap_or_else(|err| panic!("failed to open {}: {}", recording.display(), err));
+    let reader = Reader::new(file)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", recording.display(), err));
+    let actual = render(&replay(parser, reader));
+    let golden = recording.with_extension(GOLDEN_EXTENSION);
+
+    if update {
+        std::fs::write(&golden, &actual)
+            .unwrap_or_else(|err| panic!("failed to write {}: {}", golden.display(), err));
+        return None;
+    }
+
+    match std::fs::read_to_string(&golden) {
+        Ok(expected) if expected == actual => None,
+        Ok(expected) => Some(format!(
+            "{}: output changed, {}",
+            recording.display(),
+            diff(&expected, &actual)
+        )),
+        Err(err) => Some(format!(
+            "{}: missing golden output {} ({}), run with {}=1 to create it",
+            recording.display(),
+            golden.display(),
+            err,
+            UPDATE_ENV
+        )),
+    }
+}
+
+/// Check every recording in `dir` against its golden file.
+///
+/// # Panics
+/// Panics listing every recording whose output differs from its golden file,
+/// or if the directory has no recordings.
+pub fn check_dir<P, D>(parser: &P, dir: D)
+where
+    P: for<'a> Parse<'a>,
+    for<'a> <P as Protocol<'a>>::Message: Serialize,
+    D: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    let update = std::env::var_os(UPDATE_ENV).is_some();
+    let recordings = recordings(dir);
+    assert!(
+        !recordings.is_empty(),
+        "no .{} recordings found in {}",
+        RECORDING_EXTENSION,
+        dir.display()
+    );
+
+    let failures: Vec<String> = recordings
+        .iter()
+        .filter_map(|recording| check_file(parser, recording, update))
+        .collect();
+    if !failures.is_empty() {
+        panic!(
+            "{} of {} recordings failed:\n\n{}",
+            failures.len(),
+            recordings.len(),
+            failures.join("\n\n")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sawp::error::{Error, Result};
+    use sawp_file::format::Writer;
+
+    /// Splits input on newlines, rejecting lines starting with '!'
+    struct Lines {}
+
+    impl Protocol<'_> for Lines {
+        type Message = String;
+
+        fn name() -> &'static str {
+            "lines"
+        }
+    }
+
+    impl<'a> Parse<'a> for Lines {
+        fn parse(
+            &self,
+            input: &'a [u8],
+            _direction: sawp::parser::Direction,
+        ) -> Result<(&'a [u8], Option<Self::Message>)> {
+            match input.iter().position(|b| *b == b'\n') {
+                Some(_) if input[0] == b'!' => Err(Error::new(ErrorKind::InvalidData)),
+                Some(end) => Ok((
+                    &input[end + 1..],
+                    Some(String::from_utf8_lossy(&input[..end]).into_owned()),
+                )),
+                None => Err(Error::incomplete()),
+            }
+        }
+    }
+
+    fn record(calls: &[(Direction, &[u8])], gap: Option<(Direction, usize)>) -> Vec<Call> {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).unwrap();
+        for (direction, data) in calls {
+            writer.parse(*direction, data).unwrap();
+        }
+        if let Some((direction, size)) = gap {
+            writer.gap(direction, size).unwrap();
+        }
+        Reader::new(buffer.as_slice()).unwrap().collect()
+    }
+
+    #[test]
+    fn replay_buffers_per_direction() {
+        let calls = record(
+            &[
+                (Direction::ToServer, b"he"),
+                (Direction::ToClient, b"wor"),
+                (Direction::ToServer, b"llo\nag"),
+                (Direction::ToClient, b"ld\n"),
+            ],
+            None,
+        );
+        assert_eq!(
+            vec![
+                Event::Message {
+                    direction: Direction::ToServer,
+                    message: Value::from("hello"),
+                },
+                Event::Message {
+                    direction: Direction::ToClient,
+                    message: Value::from("world"),
+                },
+                Event::Trailing {
+                    direction: Direction::ToServer,
+                    size: 2,
+                },
+            ],
+            replay(&Lines {}, calls)
+        );
+    }
+
+    #[test]
+    fn replay_errors_and_gaps() {
+        let calls = record(
+            &[
+                (Direction::ToServer, b"!bad\nok\n"),
+                (Direction::ToServer, b"partial"),
+            ],
+            Some((Direction::ToServer, 10)),
+        );
+        assert_eq!(
+            vec![
+                Event::Error {
+                    direction: Direction::ToServer,
+                    error: String::from("InvalidData"),
+                },
+                Event::Gap {
+                    direction: Direction::ToServer,
+                    size: 10,
+                },
+            ],
+            replay(&Lines {}, calls)
+        );
+    }
+
+    #[test]
+    fn render_keeps_bytes_on_one_line() {
+        let events = vec![Event::Message {
+            direction: Direction::ToClient,
+            message: serde_json::json!({ "data": [1, 2, 3], "empty": [] }),
+        }];
+        assert_eq!(
+            "[\n  {\n    \"direction\": \"ToClient\",\n    \"event\": \"message\",\n    \
+             \"message\": {\n      \"data\": [1, 2, 3],\n      \"empty\": []\n    }\n  }\n]\n",
+            render(&events)
+        );
+    }
+
+    #[test]
+    fn diff_reports_first_changed_line() {
+        let report = diff("a\nb\nc\n", "a\nx\nc\n");
+        assert!(report.starts_with("first difference at line 2"));
+        assert!(report.contains("    2 | b"));
+        assert!(report.contains("    2 | x"));
+    }
+}
@@ -0,0 +1,34 @@
+//! Replays the recordings in `tests/golden/<protocol>` and compares the parser
+//! output against the stored JSON. Run with `SAWP_UPDATE_GOLDEN=1` to update
+//! the expected output after an intended change in behaviour.
+
+use sawp_golden::check_dir;
+
+fn dir(protocol: &str) -> String {
+    format!("{}/tests/golden/{}", env!("CARGO_MANIFEST_DIR"), protocol)
+}
+
+#[test]
+fn dns() {
+    check_dir(&sawp_dns::Dns::default(), dir("dns"));
+}
+
+#[test]
+fn modbus() {
+    check_dir(&sawp_modbus::Modbus {}, dir("modbus"));
+}
+
+#[test]
+fn resp() {
+    check_dir(&sawp_resp::Resp {}, dir("resp"));
+}
+
+#[test]
+fn tftp() {
+    check_dir(&sawp_tftp::TFTP {}, dir("tftp"));
+}
+
+#[test]
+fn diameter() {
+    check_dir(&sawp_diameter::Diameter {}, dir("diameter"));
+}
@@ -0,0 +1,100 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sawp::parser::{Direction, Parse};
+use sawp_gre::Gre;
+
+#[path = "support/alloc.rs"]
+mod alloc;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+/// Size of the packet encapsulated in every GRE packet.
+const PAYLOAD_LEN: usize = 1400;
+
+/// Append a typical encapsulated packet after a GRE header.
+fn packet(header: &[u8]) -> Vec<u8> {
+    let mut packet = header.to_vec();
+    packet.extend((0..PAYLOAD_LEN).map(|i| i as u8));
+    packet
+}
+
+/// IPv4 and IPv6 tunnels without optional fields, as used by most routers.
+fn basic() -> Vec<Vec<u8>> {
+    vec![
+        packet(&[
+            0x00, 0x00, // header: No flags set. Version zero.
+            0x08, 0x00, // protocol type: IPv4
+        ]),
+        packet(&[
+            0x80, 0x00, // header: Checksum flag set. Version zero.
+            0x86, 0xdd, // protocol type: IPv6
+            0xab, 0xcd, // checksum
+            0x00, 0x00, // reserved1: zero
+        ]),
+    ]
+}
+
+/// Keyed and sequenced tunnels, e.g. NVGRE and ERSPAN.
+fn keyed() -> Vec<Vec<u8>> {
+    (0..4u8)
+        .map(|i| {
+            packet(&[
+                0x30, 0x00, // header: key and sequence flags set. Version zero.
+                0x65, 0x58, // protocol type: transparent ethernet bridging
+                0x00, 0x00, 0x10, 0x00, // key: virtual subnet 16
+                0x00, 0x00, 0x00, i, // sequence number
+            ])
+        })
+        .collect()
+}
+
+/// Enhanced GRE carrying PPP frames for PPTP.
+fn pptp() -> Vec<Vec<u8>> {
+    let length = (PAYLOAD_LEN as u16).to_be_bytes();
+    (0..4u8)
+        .map(|i| {
+            packet(&[
+                0x30, 0x81, // header: key, sequence and acknowledgement set. Version 1.
+                0x88, 0x0b, // protocol type: PPP
+                length[0], length[1], // payload length
+                0x00, 0x2f, // call id
+                0x00, 0x00, 0x00, i, // sequence number
+                0x00, 0x00, 0x00, i, // acknowledgement number
+            ])
+        })
+        .collect()
+}
+
+/// Parse the GRE header of every packet, returning how many were parsed.
+fn parse_corpus(gre: &Gre, corpus: &[Vec<u8>]) -> usize {
+    corpus
+        .iter()
+        .filter(|input| {
+            matches!(
+                gre.parse(input, Direction::Unknown),
+                Ok((_, Some(message))) if message.error_flags.is_empty()
+            )
+        })
+        .count()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let corpora = vec![("basic", basic()), ("keyed", keyed()), ("pptp", pptp())];
+
+    let mut group = c.benchmark_group("gre");
+    for (name, corpus) in &corpora {
+        // Assert every packet parses cleanly before benchmarking
+        assert_eq!(corpus.len(), parse_corpus(&Gre {}, corpus));
+        alloc::report(&format!("gre/{}", name), || parse_corpus(&Gre {}, corpus));
+
+        let bytes: usize = corpus.iter().map(Vec::len).sum();
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), corpus, |b, corpus| {
+            b.iter(|| parse_corpus(&Gre {}, black_box(corpus)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
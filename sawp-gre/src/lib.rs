@@ -41,8 +41,11 @@
 //! }
 //! ````
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::upper_case_acronyms)]
 
+extern crate alloc;
+
 use sawp::error::{Error, ErrorKind, Result};
 use sawp::parser::{Direction, Parse};
 use sawp::probe::{Probe, Status};
@@ -54,7 +57,9 @@ pub use sawp_flags::{Flag, Flags};
 use nom::bytes::streaming::take;
 use nom::number::streaming::{be_u16, be_u32, be_u8};
 
-use std::ops::BitAnd;
+use core::ops::BitAnd;
+
+use alloc::{vec, vec::Vec};
 
 /// Upper limit on number of Source Route Entries to be handled when routing bit is set in deprecated
 /// GRE to avoid an infinite loop.
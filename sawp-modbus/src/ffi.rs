@@ -25,6 +25,34 @@ pub unsafe extern "C" fn sawp_modbus_destroy(d: *mut Modbus) {
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn sawp_modbus_rtu_create() -> *mut ModbusRtu {
+    let parser = ModbusRtu {};
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_modbus_rtu_destroy(d: *mut ModbusRtu) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_modbus_ascii_create() -> *mut ModbusAscii {
+    let parser = ModbusAscii {};
+    parser.into_ffi_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sawp_modbus_ascii_destroy(d: *mut ModbusAscii) {
+    if !d.is_null() {
+        // d will be dropped when this box goes out of scope
+        Box::from_raw(d);
+    }
+}
+
 /// # Safety
 /// function will panic if called with null
 #[no_mangle]
@@ -33,9 +61,42 @@ pub unsafe extern "C" fn sawp_modbus_parse(
     direction: Direction,
     data: *const u8,
     length: usize,
+) -> *mut ParseResult {
+    parse(&*parser, direction, data, length)
+}
+
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_modbus_rtu_parse(
+    parser: *const ModbusRtu,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
+) -> *mut ParseResult {
+    parse(&*parser, direction, data, length)
+}
+
+/// # Safety
+/// function will panic if called with null
+#[no_mangle]
+pub unsafe extern "C" fn sawp_modbus_ascii_parse(
+    parser: *const ModbusAscii,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
+) -> *mut ParseResult {
+    parse(&*parser, direction, data, length)
+}
+
+unsafe fn parse<'a, P: Parse<'a, Message = Message>>(
+    parser: &P,
+    direction: Direction,
+    data: *const u8,
+    length: usize,
 ) -> *mut ParseResult {
     let input = std::slice::from_raw_parts(data, length);
-    match (*parser).parse(input, direction) {
+    match parser.parse(input, direction) {
         Ok((sl, message)) => ParseResult {
             message: message.into_ffi_ptr(),
             size_read: length - sl.len(),
@@ -3,10 +3,16 @@
 //! inform the caller about what went wrong if no message is returned (see [`sawp::parser::Parse`]
 //! for details on possible return types).
 //!
+//! [`Modbus`] parses Modbus/TCP, where each PDU follows an MBAP header. Use
+//! [`ModbusRtu`] and [`ModbusAscii`] for RTU and ASCII frames taken from serial
+//! lines. All three produce the same [`Message`].
+//!
 //! The following protocol references were used to create this module:
 //!
 //! [Modbus_V1_1b](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
 //!
+//! [Modbus_over_serial_line_V1_02](https://modbus.org/docs/Modbus_over_serial_line_V1_02.pdf)
+//!
 //! [PI_MBUS_300](https://modbus.org/docs/PI_MBUS_300.pdf)
 //!
 //! # Example
@@ -43,8 +49,11 @@
 //! }
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::upper_case_acronyms)]
 
+extern crate alloc;
+
 /// Re-export of the `Flags` struct that is used to represent bit flags
 /// in this crate.
 pub use sawp_flags::{Flag, Flags};
@@ -59,17 +68,26 @@ use sawp_flags::BitFlags;
 use nom::bytes::streaming::take;
 use nom::number::streaming::{be_u16, be_u8};
 
+use core::convert::TryFrom;
+use core::ops::RangeInclusive;
 use num_enum::TryFromPrimitive;
-use std::convert::TryFrom;
-use std::ops::RangeInclusive;
+
+use alloc::vec::Vec;
 
 /// FFI structs and Accessors
 #[cfg(feature = "ffi")]
 mod ffi;
 
+pub mod policy;
+pub mod registers;
+pub mod shadow;
+
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 // Used for exception handling -- any function above this is an exception
 const ERROR_MASK: u8 = 0x80;
 // Maximum read/write quantity
@@ -79,9 +97,34 @@ const MAX_QUANTITY_WORD_ACCESS: u16 = 125;
 const MIN_RD_COUNT: u8 = 1;
 const MAX_RD_COUNT: u8 = 250;
 
+// Valid byte count ranges of file record requests
+const MIN_RD_FILE_COUNT: u8 = 0x07;
+const MAX_RD_FILE_COUNT: u8 = 0xf5;
+const MIN_WR_FILE_COUNT: u8 = 0x09;
+const MAX_WR_FILE_COUNT: u8 = 0xfb;
+// Only valid reference type of file record sub-requests
+const FILE_REFERENCE_TYPE: u8 = 6;
+const MAX_FILE_RECORD_NUMBER: u16 = 0x270f;
+
+// Values of the More Follows byte of read device identification responses
+const DEVICE_ID_LAST: u8 = 0x00;
+const DEVICE_ID_MORE_FOLLOWS: u8 = 0xff;
+
 const MIN_LENGTH: u16 = 2;
 const MAX_LENGTH: u16 = 254;
 
+// RTU frames: unit id, function code and CRC, up to the 256 bytes of a serial line frame
+const RTU_CRC_LEN: usize = 2;
+const RTU_CRC_POLYNOMIAL: u16 = 0xa001;
+const RTU_MIN_FRAME_LEN: usize = 4;
+const RTU_MAX_FRAME_LEN: usize = 256;
+
+// ASCII frames: a colon, the hex digits of the unit id, PDU and LRC, then CR LF
+const ASCII_START: u8 = b':';
+const ASCII_END: &[u8] = b"\r\n";
+const ASCII_MIN_DATA_LEN: usize = 3;
+const ASCII_MAX_FRAME_LEN: usize = 513;
+
 /// Function code groups based on general use. Allows for easier
 /// parsing of certain functions, since generally most functions in a group
 /// will have the same request/response structure.
@@ -180,6 +223,7 @@ impl From<&Message> for Flags<CodeCategory> {
                         CodeCategory::PUBLIC_ASSIGNED.into()
                     }
                 }
+                Data::DeviceId(_) => CodeCategory::PUBLIC_ASSIGNED.into(),
                 _ => CodeCategory::none(),
             },
             FunctionCode::Unknown => CodeCategory::from_raw(msg.function.raw),
@@ -201,11 +245,14 @@ pub enum ErrorFlags {
     EXC_CODE = 0b00000100,
     FUNC_CODE = 0b00001000,
     PROTO_ID = 0b00010000,
+    /// The CRC of an RTU frame or the LRC of an ASCII frame does not match
+    CHECKSUM = 0b00100000,
 }
 
 /// Information on the function code parsed
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Function {
     /// Value of the function byte
@@ -231,6 +278,7 @@ impl Function {
 }
 
 /// Function code names as stated in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum FunctionCode {
@@ -262,8 +310,8 @@ pub enum FunctionCode {
     Unknown,
 }
 
-impl std::fmt::Display for FunctionCode {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for FunctionCode {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "{:?}", self)
     }
 }
@@ -277,6 +325,7 @@ impl FunctionCode {
 /// Information on the diagnostic subfunction code parsed
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Diagnostic {
     /// Value of the subfunction bytes
@@ -296,6 +345,7 @@ impl Diagnostic {
 }
 
 /// Subfunction code names as stated in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum DiagnosticSubfunction {
@@ -321,8 +371,8 @@ pub enum DiagnosticSubfunction {
     Reserved,
 }
 
-impl std::fmt::Display for DiagnosticSubfunction {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for DiagnosticSubfunction {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "{:?}", self)
     }
 }
@@ -330,6 +380,7 @@ impl std::fmt::Display for DiagnosticSubfunction {
 /// Information on the mei code parsed
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct MEI {
     /// Value of the mei function byte
@@ -349,6 +400,7 @@ impl MEI {
 }
 
 /// MEI function code names as stated in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum MEIType {
@@ -357,8 +409,186 @@ pub enum MEIType {
     RdDevId = 0x0e,
 }
 
-impl std::fmt::Display for MEIType {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for MEIType {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+/// [`MEIType::RdDevId`] requests/responses
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub enum DeviceId {
+    Request {
+        /// Value of the read device id code byte
+        code_raw: u8,
+        #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+        code: DeviceIdCode,
+        /// First object to read, or the only one with [`DeviceIdCode::Specific`]
+        object_id: u8,
+    },
+    Response {
+        /// Value of the read device id code byte
+        code_raw: u8,
+        #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+        code: DeviceIdCode,
+        /// Value of the conformity level byte
+        conformity_level_raw: u8,
+        #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+        conformity_level: ConformityLevel,
+        /// Whether more objects need to be read with another request
+        more_follows: bool,
+        /// First object of the next request if more follow
+        next_object_id: u8,
+        objects: Vec<DeviceObject>,
+    },
+}
+
+impl DeviceId {
+    /// Checks a response answers a request: the codes must be equal and a
+    /// specific object request must get that object
+    fn matches(&self, other: &DeviceId) -> bool {
+        match (self, other) {
+            (
+                DeviceId::Request {
+                    code_raw,
+                    code,
+                    object_id,
+                },
+                DeviceId::Response {
+                    code_raw: other_code_raw,
+                    objects,
+                    ..
+                },
+            )
+            | (
+                DeviceId::Response {
+                    code_raw: other_code_raw,
+                    objects,
+                    ..
+                },
+                DeviceId::Request {
+                    code_raw,
+                    code,
+                    object_id,
+                },
+            ) => {
+                code_raw == other_code_raw
+                    && (*code != DeviceIdCode::Specific
+                        || (objects.len() == 1 && objects[0].id == *object_id))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Read device id code names as stated in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum DeviceIdCode {
+    Unknown = 0x00,
+    /// Stream access to the basic objects
+    Basic = 0x01,
+    /// Stream access to the basic and regular objects
+    Regular,
+    /// Stream access to the basic, regular and extended objects
+    Extended,
+    /// Access to one object
+    Specific,
+}
+
+impl core::fmt::Display for DeviceIdCode {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+/// Conformity level names as stated in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf),
+/// giving the objects a device supports and whether they can be read individually
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum ConformityLevel {
+    Unknown = 0x00,
+    BasicStream = 0x01,
+    RegularStream,
+    ExtendedStream,
+    BasicStreamIndividual = 0x81,
+    RegularStreamIndividual,
+    ExtendedStreamIndividual,
+}
+
+impl core::fmt::Display for ConformityLevel {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+/// Device identification object of a [`DeviceId::Response`]
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct DeviceObject {
+    /// Value of the object id byte
+    pub id: u8,
+    /// Object name associated with the id
+    #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
+    pub code: DeviceObjectId,
+    pub value: Vec<u8>,
+}
+
+impl DeviceObject {
+    fn new(id: u8, value: &[u8]) -> DeviceObject {
+        DeviceObject {
+            id,
+            code: DeviceObjectId::from_raw(id),
+            value: value.to_vec(),
+        }
+    }
+
+    /// Value as a string, if it is valid UTF-8. Standard objects are ASCII strings
+    /// while private objects are device dependent.
+    pub fn value_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.value).ok()
+    }
+}
+
+/// Device identification object names as stated in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum DeviceObjectId {
+    // Basic
+    VendorName = 0x00,
+    ProductCode,
+    MajorMinorRevision,
+    // Regular
+    VendorUrl,
+    ProductName,
+    ModelName,
+    UserApplicationName,
+    // 0x07 - 0x7f: RESERVED
+    Reserved,
+    // 0x80 and on: extended objects, device dependent
+    Private = 0x80,
+}
+
+impl DeviceObjectId {
+    pub fn from_raw(val: u8) -> Self {
+        if val >= DeviceObjectId::Private as u8 {
+            DeviceObjectId::Private
+        } else {
+            DeviceObjectId::try_from(val).unwrap_or(DeviceObjectId::Reserved)
+        }
+    }
+}
+
+impl core::fmt::Display for DeviceObjectId {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "{:?}", self)
     }
 }
@@ -366,6 +596,7 @@ impl std::fmt::Display for MEIType {
 /// Information on the exception code parsed
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Exception {
     /// Value of the exception code byte
@@ -385,6 +616,7 @@ impl Exception {
 }
 
 /// Exception code names as stated in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum ExceptionCode {
@@ -401,8 +633,8 @@ pub enum ExceptionCode {
     Unknown,
 }
 
-impl std::fmt::Display for ExceptionCode {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for ExceptionCode {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "{:?}", self)
     }
 }
@@ -410,6 +642,7 @@ impl std::fmt::Display for ExceptionCode {
 /// Read information on parsed in function data
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Debug, PartialEq)]
 pub enum Read {
     Request { address: u16, quantity: u16 },
@@ -419,6 +652,7 @@ pub enum Read {
 /// Write information on parsed in function data
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub enum Write {
     /// [`AccessType::MULTIPLE`] requests, responses fall in [`Write::Other`]
@@ -439,9 +673,36 @@ pub enum Write {
     Other { address: u16, data: u16 },
 }
 
+/// File record sub-request or sub-response of [`FunctionCode::RdFileRec`] and
+/// [`FunctionCode::WrFileRec`]
+#[cfg_attr(feature = "ffi", derive(GenerateFFI))]
+#[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, PartialEq)]
+pub struct FileRecord {
+    pub reference_type: u8,
+    /// Not sent in read responses, left at 0
+    pub file_number: u16,
+    /// Not sent in read responses, left at 0
+    pub record_number: u16,
+    /// Number of registers in the record
+    pub record_length: u16,
+    /// Registers written or read, empty in read requests
+    pub data: Vec<u8>,
+}
+
+impl FileRecord {
+    fn is_valid_request(&self) -> bool {
+        self.reference_type == FILE_REFERENCE_TYPE
+            && self.file_number != 0
+            && self.record_number <= MAX_FILE_RECORD_NUMBER
+    }
+}
+
 /// Represents the various fields found in the PDU
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub enum Data {
     Exception(Exception),
@@ -459,6 +720,11 @@ pub enum Data {
         read: Read,
         write: Write,
     },
+    /// [`FunctionCode::RdFileRec`] and [`FunctionCode::WrFileRec`] requests/responses
+    FileRecords(Vec<FileRecord>),
+    /// [`MEIType::RdDevId`] requests/responses, other MEI types and device
+    /// identification too short to parse fall in [`Data::MEI`]
+    DeviceId(DeviceId),
     /// Used for data that doesn't fit elsewhere
     ByteVec(Vec<u8>),
     Empty,
@@ -470,6 +736,7 @@ pub struct Modbus {}
 /// Breakdown of the parsed modbus bytes
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_modbus"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Message {
     pub transaction_id: u16,
@@ -570,6 +837,149 @@ impl Message {
         Ok(input)
     }
 
+    //                             Num Bytes       Byte Placement
+    // Byte Count:                 1               (0)
+    // Sub-requests:               Count           (1..Count + 1)
+    //     Reference Type:         1               (0)
+    //     File Number:            2               (1,2)
+    //     Record Number:          2               (3,4)
+    //     Record Length:          2               (5,6)
+    //     Record Data:            2 * Length      (7..) (FunctionCode::WrFileRec only)
+    //
+    // Write responses echo the request.
+    fn parse_file_record_request<'a>(&mut self, input: &'a [u8]) -> Result<&'a [u8]> {
+        if self.data_length() < 1 {
+            self.error_flags |= ErrorFlags::DATA_LENGTH;
+            return Ok(input);
+        }
+
+        let (input, count) = be_u8(input)?;
+        let write = self.function.code == FunctionCode::WrFileRec;
+        let counts = if write {
+            MIN_WR_FILE_COUNT..=MAX_WR_FILE_COUNT
+        } else {
+            MIN_RD_FILE_COUNT..=MAX_RD_FILE_COUNT
+        };
+
+        if !counts.contains(&count) {
+            self.error_flags |= ErrorFlags::DATA_VALUE;
+        }
+
+        if self.data_length() - 1 != count.into() {
+            self.error_flags |= ErrorFlags::DATA_LENGTH;
+        }
+
+        let (input, mut data) = take(self.data_length() - 1)(input)?;
+        let mut records = Vec::new();
+        while !data.is_empty() {
+            match parse_file_sub_request(data, write) {
+                Ok((rest, record)) => {
+                    if !record.is_valid_request() {
+                        self.error_flags |= ErrorFlags::DATA_VALUE;
+                    }
+                    records.push(record);
+                    data = rest;
+                }
+                Err(_) => {
+                    self.error_flags |= ErrorFlags::DATA_LENGTH;
+                    break;
+                }
+            }
+        }
+
+        self.data = Data::FileRecords(records);
+        Ok(input)
+    }
+
+    //                             Num Bytes       Byte Placement
+    // Response Data Length:       1               (0)
+    // Sub-responses:              Count           (1..Count + 1)
+    //     File Response Length:   1               (0)
+    //     Reference Type:         1               (1)
+    //     Record Data:            Length - 1      (2..Length + 1)
+    fn parse_file_record_response<'a>(&mut self, input: &'a [u8]) -> Result<&'a [u8]> {
+        if self.data_length() < 1 {
+            self.error_flags |= ErrorFlags::DATA_LENGTH;
+            return Ok(input);
+        }
+
+        let (input, count) = be_u8(input)?;
+
+        if count > MAX_RD_FILE_COUNT {
+            self.error_flags |= ErrorFlags::DATA_VALUE;
+        }
+
+        if self.data_length() - 1 != count.into() {
+            self.error_flags |= ErrorFlags::DATA_LENGTH;
+        }
+
+        let (input, mut data) = take(self.data_length() - 1)(input)?;
+        let mut records = Vec::new();
+        while !data.is_empty() {
+            match parse_file_sub_response(data) {
+                Ok((rest, record)) => {
+                    if record.reference_type != FILE_REFERENCE_TYPE {
+                        self.error_flags |= ErrorFlags::DATA_VALUE;
+                    }
+                    if record.data.len() != 2 * usize::from(record.record_length) {
+                        self.error_flags |= ErrorFlags::DATA_LENGTH;
+                    }
+                    records.push(record);
+                    data = rest;
+                }
+                Err(_) => {
+                    self.error_flags |= ErrorFlags::DATA_LENGTH;
+                    break;
+                }
+            }
+        }
+
+        self.data = Data::FileRecords(records);
+        Ok(input)
+    }
+
+    //                             Num Bytes   Byte Placement
+    // Request:
+    //     Read Device ID Code:    1           (0)
+    //     Object Id:              1           (1)
+    // Response:
+    //     Read Device ID Code:    1           (0)
+    //     Conformity Level:       1           (1)
+    //     More Follows:           1           (2)
+    //     Next Object Id:         1           (3)
+    //     Number of Objects:      1           (4)
+    //     Objects:                x           (5..)
+    //         Object Id:          1           (0)
+    //         Object Length:      1           (1)
+    //         Object Value:       Length      (2..Length + 2)
+    //
+    // Decodes the data of a parsed Read Device ID MEI, leaving it as is if too
+    // short to be decoded.
+    fn parse_device_id(&mut self, direction: &Direction) {
+        let data = match &self.data {
+            Data::MEI { mei_type, data } if mei_type.code == MEIType::RdDevId => data,
+            _ => return,
+        };
+
+        let mut flags = ErrorFlags::none();
+        let result = match direction {
+            Direction::ToServer => parse_device_id_request(data, &mut flags),
+            Direction::ToClient => parse_device_id_response(data, &mut flags),
+            Direction::Unknown => return,
+        };
+        match result {
+            Ok((rest, device_id)) => {
+                if !rest.is_empty() {
+                    flags |= ErrorFlags::DATA_LENGTH;
+                }
+                self.data = Data::DeviceId(device_id);
+            }
+            Err(_) => flags |= ErrorFlags::DATA_LENGTH,
+        }
+
+        self.error_flags |= flags;
+    }
+
     fn parse_bytevec<'a>(&mut self, input: &'a [u8]) -> Result<&'a [u8]> {
         let (input, data) = take(self.data_length())(input)?;
         self.data = Data::ByteVec(data.to_vec());
@@ -805,9 +1215,13 @@ impl Message {
 
                 return Ok(input);
             }
-            FunctionCode::MEI => return self.parse_mei(input),
-            FunctionCode::RdFileRec | FunctionCode::WrFileRec if self.data_length() == 0 => {
-                self.error_flags |= ErrorFlags::DATA_LENGTH
+            FunctionCode::MEI => {
+                let input = self.parse_mei(input)?;
+                self.parse_device_id(&Direction::ToServer);
+                return Ok(input);
+            }
+            FunctionCode::RdFileRec | FunctionCode::WrFileRec => {
+                return self.parse_file_record_request(input)
             }
             FunctionCode::RdExcStatus
             | FunctionCode::GetCommEventCtr
@@ -848,7 +1262,13 @@ impl Message {
         match self.function.code {
             _ if self.function.raw >= ERROR_MASK => return self.parse_exception(input),
             FunctionCode::Diagnostic => return self.parse_diagnostic(input),
-            FunctionCode::MEI => return self.parse_mei(input),
+            FunctionCode::MEI => {
+                let input = self.parse_mei(input)?;
+                self.parse_device_id(&Direction::ToClient);
+                return Ok(input);
+            }
+            FunctionCode::RdFileRec => return self.parse_file_record_response(input),
+            FunctionCode::WrFileRec => return self.parse_file_record_request(input),
             FunctionCode::RdExcStatus if self.data_length() != 1 => {
                 self.error_flags |= ErrorFlags::DATA_LENGTH
             }
@@ -878,6 +1298,40 @@ impl Message {
         }
     }
 
+    /// Parses the unit id and the PDU following it, which together span `length` bytes
+    fn parse_pdu(&mut self, data: &[u8], direction: Direction) -> Result<()> {
+        let (data, unit_id) = be_u8(data)?;
+        let (data, raw_func) = be_u8(data)?;
+        self.unit_id = unit_id;
+        self.function = Function::new(raw_func);
+        self.access_type = self.function.code.into();
+
+        let result = match direction {
+            Direction::ToServer => self.parse_request(data),
+            Direction::ToClient => self.parse_response(data),
+            Direction::Unknown => self.parse_unknown(data),
+        };
+        match result {
+            Ok(rest) => {
+                if !rest.is_empty() {
+                    self.error_flags |= ErrorFlags::DATA_LENGTH;
+                }
+            }
+            Err(Error {
+                kind: ErrorKind::Incomplete(_),
+            }) => {
+                self.error_flags |= ErrorFlags::DATA_LENGTH;
+                if self.data == Data::Empty {
+                    self.data = Data::ByteVec(data.to_vec());
+                }
+            }
+            Err(err) => return Err(err),
+        }
+
+        self.category = Flags::from(&*self);
+        Ok(())
+    }
+
     /// Matches this message with another. Used to validate requests with responses.
     pub fn matches(&mut self, other: &Message) -> bool {
         if self.transaction_id != other.transaction_id
@@ -1034,6 +1488,25 @@ impl Message {
 
                 true
             }
+            (Data::FileRecords(records), Data::FileRecords(other_records)) => {
+                // Write responses echo the request, read responses carry the
+                // requested number of registers of each record
+                let valid = if self.function.code == FunctionCode::WrFileRec {
+                    records == other_records
+                } else {
+                    records.len() == other_records.len()
+                        && records
+                            .iter()
+                            .zip(other_records)
+                            .all(|(record, other)| record.record_length == other.record_length)
+                };
+
+                if !valid {
+                    self.error_flags |= ErrorFlags::DATA_VALUE;
+                }
+
+                true
+            }
             (
                 Data::Diagnostic { func, data: _ },
                 Data::Diagnostic {
@@ -1048,6 +1521,19 @@ impl Message {
                     data: _,
                 },
             ) => mei_type == other_mei,
+            (Data::DeviceId(device_id), Data::DeviceId(other_device_id)) => {
+                if !device_id.matches(other_device_id) {
+                    self.error_flags |= ErrorFlags::DATA_VALUE;
+                }
+
+                true
+            }
+            (Data::MEI { .. }, Data::DeviceId(_)) => {
+                self.error_flags.intersects(ErrorFlags::DATA_LENGTH)
+            }
+            (Data::DeviceId(_), Data::MEI { .. }) => {
+                other.error_flags.intersects(ErrorFlags::DATA_LENGTH)
+            }
             _ => false,
         }
     }
@@ -1099,30 +1585,26 @@ impl Message {
                 _ => return None,
             };
 
-            if *start == std::u16::MAX || start >= address {
+            if *start == core::u16::MAX || start >= address {
                 return None;
             }
 
-            // Multiply by two because each register value is 2 bytes
-            let mut offset = (address - (start + 1)) as usize * 2;
+            let index = usize::from(address - (start + 1));
 
-            // In case of Coils, offset is in bit (convert to byte)
+            // Coils are packed 8 to a byte, starting from the low bit
             if self.access_type.contains(AccessType::COILS) {
-                offset >>= 3;
+                return data
+                    .get(index >> 3)
+                    .map(|byte| u16::from((byte >> (index & 0x7)) & 0x1));
             }
 
-            let mut value =
-                if let (Some(val1), Some(val2)) = (data.get(offset), data.get(offset + 1)) {
-                    ((*val1 as u16) << 8) | *val2 as u16
-                } else {
-                    return None;
-                };
-
-            if self.access_type.contains(AccessType::COILS) {
-                value = (value >> ((address - (start + 1)) & 0x7)) & 0x1;
+            // Multiply by two because each register value is 2 bytes
+            let offset = index * 2;
+            if let (Some(val1), Some(val2)) = (data.get(offset), data.get(offset + 1)) {
+                Some(((*val1 as u16) << 8) | *val2 as u16)
+            } else {
+                None
             }
-
-            Some(value)
         } else {
             None
         }
@@ -1132,6 +1614,7 @@ impl Message {
     /// match and they can't be found, None is returned.
     /// The range returned is offset by 1 to account to reflect the coils/registers
     /// that start at 1 instead of in the PDU numbers where they start at 0.
+    /// Ranges ending past 65535 are not representable and also give None.
     /// More details can be found in the [protocol reference](https://modbus.org/docs/Modbus_Application_Protocol_V1_1b.pdf)
     pub fn get_address_range(&self) -> Option<RangeInclusive<u16>> {
         match &self.data {
@@ -1140,7 +1623,7 @@ impl Message {
                 address,
                 and_mask: _,
                 or_mask: _,
-            }) => Some((address + 1)..=(address + 1)),
+            }) => address.checked_add(1).map(|address| address..=address),
             Data::Read(Read::Request { address, quantity })
             | Data::Write(Write::MultReq {
                 address,
@@ -1157,7 +1640,7 @@ impl Message {
                     },
             } => {
                 if *quantity > 0 {
-                    Some((address + 1)..=(address + quantity))
+                    Some(address.checked_add(1)?..=address.checked_add(*quantity)?)
                 } else {
                     None
                 }
@@ -1167,6 +1650,131 @@ impl Message {
     }
 }
 
+/// Parses a file record sub-request, followed by its record data if `write` is set
+fn parse_file_sub_request(input: &[u8], write: bool) -> Result<(&[u8], FileRecord)> {
+    let (input, reference_type) = be_u8(input)?;
+    let (input, file_number) = be_u16(input)?;
+    let (input, record_number) = be_u16(input)?;
+    let (input, record_length) = be_u16(input)?;
+    let (input, data) = if write {
+        take(2 * usize::from(record_length))(input)?
+    } else {
+        (input, &input[..0])
+    };
+
+    Ok((
+        input,
+        FileRecord {
+            reference_type,
+            file_number,
+            record_number,
+            record_length,
+            data: data.to_vec(),
+        },
+    ))
+}
+
+/// Parses a read file record sub-response. The file and record numbers are not
+/// part of it and are left at 0.
+fn parse_file_sub_response(input: &[u8]) -> Result<(&[u8], FileRecord)> {
+    let (input, length) = be_u8(input)?;
+    let (input, sub_response) = take(length)(input)?;
+    let (data, reference_type) = be_u8(sub_response)?;
+
+    Ok((
+        input,
+        FileRecord {
+            reference_type,
+            file_number: 0,
+            record_number: 0,
+            record_length: (data.len() / 2) as u16,
+            data: data.to_vec(),
+        },
+    ))
+}
+
+/// Parses the id, length and value of a device identification object
+fn parse_device_object(input: &[u8]) -> Result<(&[u8], DeviceObject)> {
+    let (input, id) = be_u8(input)?;
+    let (input, length) = be_u8(input)?;
+    let (input, value) = take(length)(input)?;
+    Ok((input, DeviceObject::new(id, value)))
+}
+
+/// Parses a read device identification request
+fn parse_device_id_request<'a>(
+    input: &'a [u8],
+    flags: &mut Flags<ErrorFlags>,
+) -> Result<(&'a [u8], DeviceId)> {
+    let (input, code_raw) = be_u8(input)?;
+    let (input, object_id) = be_u8(input)?;
+
+    let code = DeviceIdCode::try_from(code_raw).unwrap_or(DeviceIdCode::Unknown);
+    if code == DeviceIdCode::Unknown {
+        *flags |= ErrorFlags::DATA_VALUE;
+    }
+
+    Ok((
+        input,
+        DeviceId::Request {
+            code_raw,
+            code,
+            object_id,
+        },
+    ))
+}
+
+/// Parses a read device identification response. A truncated object ends the
+/// list of objects.
+fn parse_device_id_response<'a>(
+    input: &'a [u8],
+    flags: &mut Flags<ErrorFlags>,
+) -> Result<(&'a [u8], DeviceId)> {
+    let (input, code_raw) = be_u8(input)?;
+    let (input, conformity_level_raw) = be_u8(input)?;
+    let (input, more_follows) = be_u8(input)?;
+    let (input, next_object_id) = be_u8(input)?;
+    let (mut input, num_objects) = be_u8(input)?;
+
+    let code = DeviceIdCode::try_from(code_raw).unwrap_or(DeviceIdCode::Unknown);
+    let conformity_level =
+        ConformityLevel::try_from(conformity_level_raw).unwrap_or(ConformityLevel::Unknown);
+    if code == DeviceIdCode::Unknown
+        || conformity_level == ConformityLevel::Unknown
+        || (more_follows != DEVICE_ID_LAST && more_follows != DEVICE_ID_MORE_FOLLOWS)
+        || (more_follows == DEVICE_ID_LAST && next_object_id != 0)
+    {
+        *flags |= ErrorFlags::DATA_VALUE;
+    }
+
+    let mut objects = Vec::new();
+    for _ in 0..num_objects {
+        match parse_device_object(input) {
+            Ok((rest, object)) => {
+                objects.push(object);
+                input = rest;
+            }
+            Err(_) => {
+                *flags |= ErrorFlags::DATA_LENGTH;
+                break;
+            }
+        }
+    }
+
+    Ok((
+        input,
+        DeviceId::Response {
+            code_raw,
+            code,
+            conformity_level_raw,
+            conformity_level,
+            more_follows: more_follows == DEVICE_ID_MORE_FOLLOWS,
+            next_object_id,
+            objects,
+        },
+    ))
+}
+
 impl Protocol<'_> for Modbus {
     type Message = Message;
 
@@ -1175,22 +1783,27 @@ impl Protocol<'_> for Modbus {
     }
 }
 
-impl<'a> Probe<'a> for Modbus {
-    fn probe(&self, input: &'a [u8], direction: Direction) -> Status {
-        match self.parse(input, direction) {
-            Ok((_, Some(msg))) => {
-                if msg.error_flags == ErrorFlags::none() {
-                    Status::Recognized
-                } else {
-                    Status::Unrecognized
-                }
+/// Messages with error flags are not recognized
+fn probe_status(result: Result<(&[u8], Option<Message>)>) -> Status {
+    match result {
+        Ok((_, Some(msg))) => {
+            if msg.error_flags == ErrorFlags::none() {
+                Status::Recognized
+            } else {
+                Status::Unrecognized
             }
-            Ok((_, _)) => Status::Recognized,
-            Err(Error {
-                kind: ErrorKind::Incomplete(_),
-            }) => Status::Incomplete,
-            Err(_) => Status::Unrecognized,
         }
+        Ok((_, _)) => Status::Recognized,
+        Err(Error {
+            kind: ErrorKind::Incomplete(_),
+        }) => Status::Incomplete,
+        Err(_) => Status::Unrecognized,
+    }
+}
+
+impl<'a> Probe<'a> for Modbus {
+    fn probe(&self, input: &'a [u8], direction: Direction) -> Status {
+        probe_status(self.parse(input, direction))
     }
 }
 
@@ -1231,35 +1844,267 @@ impl<'a> Parse<'a> for Modbus {
         }
 
         let (input, data) = take(length)(input)?;
-        let (data, unit_id) = be_u8(data)?;
-        let (data, raw_func) = be_u8(data)?;
-        message.unit_id = unit_id;
-        message.function = Function::new(raw_func);
-        message.access_type = message.function.code.into();
+        message.parse_pdu(data, direction)?;
 
-        let result = match direction {
-            Direction::ToServer => message.parse_request(data),
-            Direction::ToClient => message.parse_response(data),
-            Direction::Unknown => message.parse_unknown(data),
-        };
-        match result {
-            Ok(rest) => {
-                if !rest.is_empty() {
-                    message.error_flags |= ErrorFlags::DATA_LENGTH;
-                }
+        Ok((input, Some(message)))
+    }
+}
+
+/// Modbus CRC-16 of an RTU frame, sent low byte first after the PDU
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xffff, |crc, byte| {
+        (0..8).fold(crc ^ u16::from(*byte), |crc, _| {
+            if crc & 0x0001 != 0 {
+                (crc >> 1) ^ RTU_CRC_POLYNOMIAL
+            } else {
+                crc >> 1
             }
-            Err(Error {
-                kind: ErrorKind::Incomplete(_),
-            }) => {
-                message.error_flags |= ErrorFlags::DATA_LENGTH;
-                if message.data == Data::Empty {
-                    message.data = Data::ByteVec(data.to_vec());
-                }
-            }
-            Err(err) => return Err(err),
+        })
+    })
+}
+
+/// Length of the RTU frame starting `input`, from its function code and direction.
+/// Returns None if the function code does not give the length.
+//                             Num Bytes   Byte Placement
+// Address:                    1           (0)
+// Function Code:              1           (1)
+// Data:                       x           (2..)
+// CRC:                        2           (x + 2, x + 3)
+fn rtu_frame_length(input: &[u8], direction: &Direction) -> Result<Option<usize>> {
+    if input.len() < 2 {
+        return Err(Error::incomplete_needed(2 - input.len()));
+    }
+
+    // Frame whose data is `fixed` bytes followed by the number of bytes in a count
+    // of `width` bytes ending at `fixed`
+    let counted = |fixed: usize, width: usize| -> Result<Option<usize>> {
+        if input.len() < fixed {
+            return Err(Error::incomplete_needed(fixed - input.len()));
+        }
+        let count = input[fixed - width..fixed]
+            .iter()
+            .fold(0, |count, byte| count << 8 | usize::from(*byte));
+        Ok(Some(fixed + count + RTU_CRC_LEN))
+    };
+    let fixed = |length: usize| Ok(Some(length + RTU_CRC_LEN));
+
+    let raw_func = input[1];
+    match direction {
+        Direction::ToServer => match FunctionCode::from_raw(raw_func) {
+            _ if raw_func >= ERROR_MASK => Ok(None),
+            FunctionCode::RdCoils
+            | FunctionCode::RdDiscreteInputs
+            | FunctionCode::RdHoldRegs
+            | FunctionCode::RdInputRegs
+            | FunctionCode::WrSingleCoil
+            | FunctionCode::WrSingleReg
+            | FunctionCode::Diagnostic => fixed(6),
+            FunctionCode::RdExcStatus
+            | FunctionCode::GetCommEventCtr
+            | FunctionCode::GetCommEventLog
+            | FunctionCode::ReportServerID => fixed(2),
+            FunctionCode::WrMultCoils | FunctionCode::WrMultRegs => counted(7, 1),
+            FunctionCode::RdFileRec | FunctionCode::WrFileRec => counted(3, 1),
+            FunctionCode::MaskWrReg => fixed(8),
+            FunctionCode::RdWrMultRegs => counted(11, 1),
+            FunctionCode::RdFIFOQueue => fixed(4),
+            _ => Ok(None),
+        },
+        Direction::ToClient => match FunctionCode::from_raw(raw_func) {
+            _ if raw_func >= ERROR_MASK => fixed(3),
+            FunctionCode::RdCoils
+            | FunctionCode::RdDiscreteInputs
+            | FunctionCode::RdHoldRegs
+            | FunctionCode::RdInputRegs
+            | FunctionCode::GetCommEventLog
+            | FunctionCode::ReportServerID
+            | FunctionCode::RdFileRec
+            | FunctionCode::WrFileRec
+            | FunctionCode::RdWrMultRegs => counted(3, 1),
+            FunctionCode::WrSingleCoil
+            | FunctionCode::WrSingleReg
+            | FunctionCode::Diagnostic
+            | FunctionCode::GetCommEventCtr
+            | FunctionCode::WrMultCoils
+            | FunctionCode::WrMultRegs => fixed(6),
+            FunctionCode::RdExcStatus => fixed(3),
+            FunctionCode::MaskWrReg => fixed(8),
+            FunctionCode::RdFIFOQueue => counted(4, 2),
+            _ => Ok(None),
+        },
+        Direction::Unknown => Ok(None),
+    }
+}
+
+/// Modbus RTU, as sent over serial lines: the unit id (the slave address) and PDU
+/// followed by their CRC-16. See the [serial line reference](https://modbus.org/docs/Modbus_over_serial_line_V1_02.pdf)
+///
+/// There is no MBAP header, the transaction and protocol ids of messages are 0.
+/// Frames are delimited by silent intervals on the line, which do not survive
+/// in a byte stream. The length of a frame is instead found from its function
+/// code and direction or, when these do not give it, as the shortest length
+/// ending in a matching CRC.
+#[derive(Debug)]
+pub struct ModbusRtu {}
+
+impl Protocol<'_> for ModbusRtu {
+    type Message = Message;
+
+    fn name() -> &'static str {
+        "modbus-rtu"
+    }
+}
+
+impl<'a> Probe<'a> for ModbusRtu {
+    fn probe(&self, input: &'a [u8], direction: Direction) -> Status {
+        probe_status(self.parse(input, direction))
+    }
+}
+
+/// Returns ErrorKind::Incomplete until the whole frame is available.
+/// Returns ErrorKind::InvalidData if the length of the frame is not known from its function
+/// code and direction and no CRC matches within the largest frame.
+/// Flags [`ErrorFlags::CHECKSUM`] if the CRC does not match.
+impl<'a> Parse<'a> for ModbusRtu {
+    fn parse(
+        &self,
+        input: &'a [u8],
+        direction: Direction,
+    ) -> Result<(&'a [u8], Option<Self::Message>)> {
+        let length = match rtu_frame_length(input, &direction)? {
+            Some(length) => length,
+            None => (RTU_MIN_FRAME_LEN..=core::cmp::min(input.len(), RTU_MAX_FRAME_LEN))
+                .find(|length| {
+                    let (data, crc) = input[..*length].split_at(length - RTU_CRC_LEN);
+                    crc16(data) == u16::from_le_bytes([crc[0], crc[1]])
+                })
+                .ok_or_else(|| {
+                    if input.len() < RTU_MAX_FRAME_LEN {
+                        Error::incomplete()
+                    } else {
+                        Error::new(ErrorKind::InvalidData)
+                    }
+                })?,
+        };
+        if input.len() < length {
+            return Err(Error::incomplete_needed(length - input.len()));
+        }
+        let (frame, input) = input.split_at(length);
+        let (data, crc) = frame.split_at(length - RTU_CRC_LEN);
+
+        let mut message = Message {
+            transaction_id: 0,
+            protocol_id: 0,
+            length: data.len() as u16,
+            unit_id: 0,
+            function: Function::new(0),
+            access_type: AccessType::none(),
+            category: CodeCategory::none(),
+            data: Data::Empty,
+            error_flags: ErrorFlags::none(),
+        };
+        if crc16(data) != u16::from_le_bytes([crc[0], crc[1]]) {
+            message.error_flags |= ErrorFlags::CHECKSUM;
+        }
+        message.parse_pdu(data, direction)?;
+
+        Ok((input, Some(message)))
+    }
+}
+
+/// Modbus LRC of an ASCII frame: the two's complement of the sum of the unit id and PDU bytes
+pub fn lrc(data: &[u8]) -> u8 {
+    data.iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+        .wrapping_neg()
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|value| value as u8)
+}
+
+/// Decode pairs of hex digits. Returns None for an odd number of digits or other characters.
+fn hex_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let pairs = input.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| Some(hex_value(pair[0])? << 4 | hex_value(pair[1])?))
+        .collect()
+}
+
+/// Modbus ASCII, as sent over serial lines: a colon, the unit id, PDU and their LRC
+/// as pairs of hex digits, then CR LF. See the [serial line reference](https://modbus.org/docs/Modbus_over_serial_line_V1_02.pdf)
+///
+/// There is no MBAP header, the transaction and protocol ids of messages are 0.
+#[derive(Debug)]
+pub struct ModbusAscii {}
+
+impl Protocol<'_> for ModbusAscii {
+    type Message = Message;
+
+    fn name() -> &'static str {
+        "modbus-ascii"
+    }
+}
+
+impl<'a> Probe<'a> for ModbusAscii {
+    fn probe(&self, input: &'a [u8], direction: Direction) -> Status {
+        probe_status(self.parse(input, direction))
+    }
+}
+
+/// Returns ErrorKind::Incomplete until the CR LF ending the frame is available.
+/// Returns ErrorKind::InvalidData if the frame does not start with a colon, has no CR LF
+/// within the largest frame or holds anything but the hex digits of at least a unit id,
+/// function code and LRC.
+/// Flags [`ErrorFlags::CHECKSUM`] if the LRC does not match.
+impl<'a> Parse<'a> for ModbusAscii {
+    fn parse(
+        &self,
+        input: &'a [u8],
+        direction: Direction,
+    ) -> Result<(&'a [u8], Option<Self::Message>)> {
+        match input.first() {
+            Some(&ASCII_START) => {}
+            Some(_) => return Err(Error::new(ErrorKind::InvalidData)),
+            None => return Err(Error::incomplete_needed(1)),
         }
+        let searched = core::cmp::min(input.len(), ASCII_MAX_FRAME_LEN);
+        let end = input[..searched]
+            .windows(ASCII_END.len())
+            .position(|window| window == ASCII_END)
+            .ok_or_else(|| {
+                if input.len() < ASCII_MAX_FRAME_LEN {
+                    Error::incomplete()
+                } else {
+                    Error::new(ErrorKind::InvalidData)
+                }
+            })?;
+        let (frame, input) = input.split_at(end + ASCII_END.len());
+
+        let frame = match hex_decode(&frame[1..end]) {
+            Some(frame) if frame.len() >= ASCII_MIN_DATA_LEN => frame,
+            _ => return Err(Error::new(ErrorKind::InvalidData)),
+        };
+        let (data, checksum) = frame.split_at(frame.len() - 1);
 
-        message.category = Flags::from(&message);
+        let mut message = Message {
+            transaction_id: 0,
+            protocol_id: 0,
+            length: data.len() as u16,
+            unit_id: 0,
+            function: Function::new(0),
+            access_type: AccessType::none(),
+            category: CodeCategory::none(),
+            data: Data::Empty,
+            error_flags: ErrorFlags::none(),
+        };
+        if lrc(data) != checksum[0] {
+            message.error_flags |= ErrorFlags::CHECKSUM;
+        }
+        message.parse_pdu(data, direction)?;
 
         Ok((input, Some(message)))
     }
@@ -2568,6 +3413,250 @@ mod tests {
                 error_flags: ErrorFlags::none(),
             })))
         ),
+        case::read_file_record(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 17
+                0x00, 0x11,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Read File Record (20)
+                0x14,
+                // Byte Count: 14
+                0x0e,
+                // Reference Type: 6, File Number: 4, Record Number: 1, Record Length: 2
+                0x06, 0x00, 0x04, 0x00, 0x01, 0x00, 0x02,
+                // Reference Type: 6, File Number: 3, Record Number: 9, Record Length: 2
+                0x06, 0x00, 0x03, 0x00, 0x09, 0x00, 0x02
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 17,
+                unit_id: 1,
+                function: Function { raw: 20, code: FunctionCode::RdFileRec },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 4,
+                        record_number: 1,
+                        record_length: 2,
+                        data: vec![],
+                    },
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 3,
+                        record_number: 9,
+                        record_length: 2,
+                        data: vec![],
+                    },
+                ]),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::read_file_record_invalid(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 10
+                0x00, 0x0a,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Read File Record (20)
+                0x14,
+                // Byte Count: 7
+                0x07,
+                // Reference Type: 5, File Number: 0, Record Number: 10000, Record Length: 1
+                0x05, 0x00, 0x00, 0x27, 0x10, 0x00, 0x01
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 10,
+                unit_id: 1,
+                function: Function { raw: 20, code: FunctionCode::RdFileRec },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 5,
+                        file_number: 0,
+                        record_number: 10000,
+                        record_length: 1,
+                        data: vec![],
+                    },
+                ]),
+                error_flags: ErrorFlags::DATA_VALUE.into(),
+            })))
+        ),
+        case::write_file_record(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 16
+                0x00, 0x10,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Write File Record (21)
+                0x15,
+                // Byte Count: 13
+                0x0d,
+                // Reference Type: 6, File Number: 4, Record Number: 7, Record Length: 3
+                0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x03,
+                // Record Data
+                0x06, 0xaf, 0x04, 0xbe, 0x10, 0x0d
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 16,
+                unit_id: 1,
+                function: Function { raw: 21, code: FunctionCode::WrFileRec },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 4,
+                        record_number: 7,
+                        record_length: 3,
+                        data: vec![0x06, 0xaf, 0x04, 0xbe, 0x10, 0x0d],
+                    },
+                ]),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::write_file_record_truncated(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 14
+                0x00, 0x0e,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Write File Record (21)
+                0x15,
+                // Byte Count: 11
+                0x0b,
+                // Reference Type: 6, File Number: 4, Record Number: 7, Record Length: 3
+                0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x03,
+                // Record Data: 4 of the 6 bytes
+                0x06, 0xaf, 0x04, 0xbe
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 14,
+                unit_id: 1,
+                function: Function { raw: 21, code: FunctionCode::WrFileRec },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::FileRecords(vec![]),
+                error_flags: ErrorFlags::DATA_LENGTH.into(),
+            })))
+        ),
+        case::device_id(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 5
+                0x00, 0x05,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Encapsulated Interface Transport (43)
+                0x2b,
+                // MEI type: Read Device ID (14)
+                0x0e,
+                // Read Device ID Code: Basic (1)
+                0x01,
+                // Object Id: VendorName (0)
+                0x00
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 5,
+                unit_id: 1,
+                function: Function { raw: 43, code: FunctionCode::MEI },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::DeviceId(DeviceId::Request { code_raw: 1, code: DeviceIdCode::Basic, object_id: 0 }),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::device_id_invalid_code(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 5
+                0x00, 0x05,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Encapsulated Interface Transport (43)
+                0x2b,
+                // MEI type: Read Device ID (14)
+                0x0e,
+                // Read Device ID Code: 5
+                0x05,
+                // Object Id: VendorName (0)
+                0x00
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 5,
+                unit_id: 1,
+                function: Function { raw: 43, code: FunctionCode::MEI },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::DeviceId(DeviceId::Request { code_raw: 5, code: DeviceIdCode::Unknown, object_id: 0 }),
+                error_flags: ErrorFlags::DATA_VALUE.into(),
+            })))
+        ),
+        case::device_id_missing_object_id(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 4
+                0x00, 0x04,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Encapsulated Interface Transport (43)
+                0x2b,
+                // MEI type: Read Device ID (14)
+                0x0e,
+                // Read Device ID Code: Specific (4)
+                0x04
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 4,
+                unit_id: 1,
+                function: Function { raw: 43, code: FunctionCode::MEI },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::MEI{ mei_type: MEI { raw: 14, code: MEIType::RdDevId }, data: vec![0x04] },
+                error_flags: ErrorFlags::DATA_LENGTH.into(),
+            })))
+        ),
     )]
     fn test_request(
         input: &[u8],
@@ -2860,14 +3949,343 @@ mod tests {
                 error_flags: ErrorFlags::none(),
             })))
         ),
-    )]
-    fn test_response(
-        input: &[u8],
-        expected: Result<(usize, Option<<Modbus as Protocol>::Message>)>,
-    ) {
-        let modbus = Modbus {};
-        assert_eq!(
-            modbus
+        case::read_file_record(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 15
+                0x00, 0x0f,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Read File Record (20)
+                0x14,
+                // Response Data Length: 12
+                0x0c,
+                // File Response Length: 5, Reference Type: 6
+                0x05, 0x06,
+                // Record Data
+                0x0d, 0xfe, 0x00, 0x20,
+                // File Response Length: 5, Reference Type: 6
+                0x05, 0x06,
+                // Record Data
+                0x33, 0xcd, 0x00, 0x40
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 15,
+                unit_id: 1,
+                function: Function { raw: 20, code: FunctionCode::RdFileRec },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 0,
+                        record_number: 0,
+                        record_length: 2,
+                        data: vec![0x0d, 0xfe, 0x00, 0x20],
+                    },
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 0,
+                        record_number: 0,
+                        record_length: 2,
+                        data: vec![0x33, 0xcd, 0x00, 0x40],
+                    },
+                ]),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::read_file_record_odd_length(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 8
+                0x00, 0x08,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Read File Record (20)
+                0x14,
+                // Response Data Length: 5
+                0x05,
+                // File Response Length: 4, Reference Type: 6
+                0x04, 0x06,
+                // Record Data
+                0x0d, 0xfe, 0x00
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 8,
+                unit_id: 1,
+                function: Function { raw: 20, code: FunctionCode::RdFileRec },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 0,
+                        record_number: 0,
+                        record_length: 1,
+                        data: vec![0x0d, 0xfe, 0x00],
+                    },
+                ]),
+                error_flags: ErrorFlags::DATA_LENGTH.into(),
+            })))
+        ),
+        case::write_file_record(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 16
+                0x00, 0x10,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Write File Record (21)
+                0x15,
+                // Byte Count: 13
+                0x0d,
+                // Reference Type: 6, File Number: 4, Record Number: 7, Record Length: 3
+                0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x03,
+                // Record Data
+                0x06, 0xaf, 0x04, 0xbe, 0x10, 0x0d
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 16,
+                unit_id: 1,
+                function: Function { raw: 21, code: FunctionCode::WrFileRec },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 4,
+                        record_number: 7,
+                        record_length: 3,
+                        data: vec![0x06, 0xaf, 0x04, 0xbe, 0x10, 0x0d],
+                    },
+                ]),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::device_id(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 30
+                0x00, 0x1e,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Encapsulated Interface Transport (43)
+                0x2b,
+                // MEI type: Read Device ID (14)
+                0x0e,
+                // Read Device ID Code: Basic (1)
+                0x01,
+                // Conformity Level: Basic Stream (1)
+                0x01,
+                // More Follows: No (0)
+                0x00,
+                // Next Object Id: 0
+                0x00,
+                // Number of Objects: 3
+                0x03,
+                // Object Id: VendorName (0), Length: 4, Value: Acme
+                0x00, 0x04, 0x41, 0x63, 0x6d, 0x65,
+                // Object Id: ProductCode (1), Length: 7, Value: PLC-100
+                0x01, 0x07, 0x50, 0x4c, 0x43, 0x2d, 0x31, 0x30, 0x30,
+                // Object Id: MajorMinorRevision (2), Length: 5, Value: V2.11
+                0x02, 0x05, 0x56, 0x32, 0x2e, 0x31, 0x31
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 30,
+                unit_id: 1,
+                function: Function { raw: 43, code: FunctionCode::MEI },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::DeviceId(DeviceId::Response {
+                    code_raw: 1,
+                    code: DeviceIdCode::Basic,
+                    conformity_level_raw: 1,
+                    conformity_level: ConformityLevel::BasicStream,
+                    more_follows: false,
+                    next_object_id: 0,
+                    objects: vec![
+                        DeviceObject { id: 0x00, code: DeviceObjectId::VendorName, value: b"Acme".to_vec() },
+                        DeviceObject { id: 0x01, code: DeviceObjectId::ProductCode, value: b"PLC-100".to_vec() },
+                        DeviceObject { id: 0x02, code: DeviceObjectId::MajorMinorRevision, value: b"V2.11".to_vec() },
+                    ],
+                }),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::device_id_more_follows(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 14
+                0x00, 0x0e,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Encapsulated Interface Transport (43)
+                0x2b,
+                // MEI type: Read Device ID (14)
+                0x0e,
+                // Read Device ID Code: Extended (3)
+                0x03,
+                // Conformity Level: Extended Stream with Individual Access (131)
+                0x83,
+                // More Follows: Yes (255)
+                0xff,
+                // Next Object Id: 129
+                0x81,
+                // Number of Objects: 1
+                0x01,
+                // Object Id: 128, Length: 4, Value: 0001
+                0x80, 0x04, 0x30, 0x30, 0x30, 0x31
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 14,
+                unit_id: 1,
+                function: Function { raw: 43, code: FunctionCode::MEI },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::DeviceId(DeviceId::Response {
+                    code_raw: 3,
+                    code: DeviceIdCode::Extended,
+                    conformity_level_raw: 0x83,
+                    conformity_level: ConformityLevel::ExtendedStreamIndividual,
+                    more_follows: true,
+                    next_object_id: 0x81,
+                    objects: vec![
+                        DeviceObject { id: 0x80, code: DeviceObjectId::Private, value: b"0001".to_vec() },
+                    ],
+                }),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::device_id_invalid_values(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 8
+                0x00, 0x08,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Encapsulated Interface Transport (43)
+                0x2b,
+                // MEI type: Read Device ID (14)
+                0x0e,
+                // Read Device ID Code: Basic (1)
+                0x01,
+                // Conformity Level: 4
+                0x04,
+                // More Follows: No (0)
+                0x00,
+                // Next Object Id: 1
+                0x01,
+                // Number of Objects: 0
+                0x00
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 8,
+                unit_id: 1,
+                function: Function { raw: 43, code: FunctionCode::MEI },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::DeviceId(DeviceId::Response {
+                    code_raw: 1,
+                    code: DeviceIdCode::Basic,
+                    conformity_level_raw: 4,
+                    conformity_level: ConformityLevel::Unknown,
+                    more_follows: false,
+                    next_object_id: 1,
+                    objects: vec![
+                    ],
+                }),
+                error_flags: ErrorFlags::DATA_VALUE.into(),
+            })))
+        ),
+        case::device_id_truncated_object(
+            &[
+                // Transaction ID: 1
+                0x00, 0x01,
+                // Protocol ID: 0
+                0x00, 0x00,
+                // Length: 15
+                0x00, 0x0f,
+                // Unit ID: 1
+                0x01,
+                // Function Code: Encapsulated Interface Transport (43)
+                0x2b,
+                // MEI type: Read Device ID (14)
+                0x0e,
+                // Read Device ID Code: Regular (2)
+                0x02,
+                // Conformity Level: Regular Stream (2)
+                0x02,
+                // More Follows: No (0)
+                0x00,
+                // Next Object Id: 0
+                0x00,
+                // Number of Objects: 2
+                0x02,
+                // Object Id: VendorUrl (3), Length: 2, Value: ab
+                0x03, 0x02, 0x61, 0x62,
+                // Object Id: ProductName (4), Length: 3, Value: 2 of 3 bytes
+                0x04, 0x03, 0x61
+            ],
+            Ok((0, Some(Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 15,
+                unit_id: 1,
+                function: Function { raw: 43, code: FunctionCode::MEI },
+                access_type: AccessType::none(),
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::DeviceId(DeviceId::Response {
+                    code_raw: 2,
+                    code: DeviceIdCode::Regular,
+                    conformity_level_raw: 2,
+                    conformity_level: ConformityLevel::RegularStream,
+                    more_follows: false,
+                    next_object_id: 0,
+                    objects: vec![
+                        DeviceObject { id: 0x03, code: DeviceObjectId::VendorUrl, value: b"ab".to_vec() },
+                    ],
+                }),
+                error_flags: ErrorFlags::DATA_LENGTH.into(),
+            })))
+        ),
+    )]
+    fn test_response(
+        input: &[u8],
+        expected: Result<(usize, Option<<Modbus as Protocol>::Message>)>,
+    ) {
+        let modbus = Modbus {};
+        assert_eq!(
+            modbus
                 .parse(input, sawp::parser::Direction::ToClient)
                 .map(|(left, msg)| (left.len(), msg)),
             expected
@@ -2988,7 +4406,15 @@ mod tests {
                 function: Function { raw: 20, code: FunctionCode::RdFileRec },
                 access_type: AccessType::none(),
                 category: CodeCategory::PUBLIC_ASSIGNED.into(),
-                data: Data::ByteVec(vec![0x07, 0x06, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 1,
+                        record_number: 0,
+                        record_length: 2,
+                        data: vec![],
+                    },
+                ]),
                 error_flags: ErrorFlags::none(),
             },
             Message{
@@ -2999,7 +4425,15 @@ mod tests {
                 function: Function { raw: 20, code: FunctionCode::RdFileRec },
                 access_type: AccessType::none(),
                 category: CodeCategory::PUBLIC_ASSIGNED.into(),
-                data: Data::ByteVec(vec![0x07, 0x07, 0x06, 0x01, 0x00, 0x00, 0x00, 0x00]),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 0,
+                        record_number: 0,
+                        record_length: 2,
+                        data: vec![0x00, 0x01, 0x00, 0x02],
+                    },
+                ]),
                 error_flags: ErrorFlags::none(),
             },
             true
@@ -3072,6 +4506,72 @@ mod tests {
         assert_eq!(resp.matches(&req), expected);
     }
 
+    #[rstest(
+        req,
+        resp,
+        expected,
+        case::read(
+            &[0x14, 0x0e, 0x06, 0x00, 0x04, 0x00, 0x01, 0x00, 0x02, 0x06, 0x00, 0x03, 0x00, 0x09, 0x00, 0x01],
+            &[0x14, 0x0a, 0x05, 0x06, 0x0d, 0xfe, 0x00, 0x20, 0x03, 0x06, 0x33, 0xcd],
+            ErrorFlags::none()
+        ),
+        case::read_length_mismatch(
+            &[0x14, 0x07, 0x06, 0x00, 0x04, 0x00, 0x01, 0x00, 0x02],
+            &[0x14, 0x04, 0x03, 0x06, 0x0d, 0xfe],
+            ErrorFlags::DATA_VALUE.into()
+        ),
+        case::read_count_mismatch(
+            &[0x14, 0x07, 0x06, 0x00, 0x04, 0x00, 0x01, 0x00, 0x01],
+            &[0x14, 0x08, 0x03, 0x06, 0x0d, 0xfe, 0x03, 0x06, 0x33, 0xcd],
+            ErrorFlags::DATA_VALUE.into()
+        ),
+        case::write(
+            &[0x15, 0x09, 0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x01, 0x06, 0xaf],
+            &[0x15, 0x09, 0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x01, 0x06, 0xaf],
+            ErrorFlags::none()
+        ),
+        case::write_data_mismatch(
+            &[0x15, 0x09, 0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x01, 0x06, 0xaf],
+            &[0x15, 0x09, 0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x01, 0x06, 0xae],
+            ErrorFlags::DATA_VALUE.into()
+        ),
+        case::device_id(
+            &[0x2b, 0x0e, 0x01, 0x00],
+            &[0x2b, 0x0e, 0x01, 0x01, 0x00, 0x00, 0x01, 0x00, 0x01, 0x41],
+            ErrorFlags::none()
+        ),
+        case::device_id_code_mismatch(
+            &[0x2b, 0x0e, 0x02, 0x00],
+            &[0x2b, 0x0e, 0x01, 0x01, 0x00, 0x00, 0x01, 0x00, 0x01, 0x41],
+            ErrorFlags::DATA_VALUE.into()
+        ),
+        case::device_id_specific(
+            &[0x2b, 0x0e, 0x04, 0x05],
+            &[0x2b, 0x0e, 0x04, 0x83, 0x00, 0x00, 0x01, 0x05, 0x01, 0x41],
+            ErrorFlags::none()
+        ),
+        case::device_id_specific_mismatch(
+            &[0x2b, 0x0e, 0x04, 0x05],
+            &[0x2b, 0x0e, 0x04, 0x83, 0x00, 0x00, 0x01, 0x04, 0x01, 0x41],
+            ErrorFlags::DATA_VALUE.into()
+        )
+    )]
+    fn test_matching_flags(req: &[u8], resp: &[u8], expected: Flags<ErrorFlags>) {
+        let parse = |pdu: &[u8], direction| {
+            let mut input = vec![0x00, 0x01, 0x00, 0x00, 0x00, pdu.len() as u8 + 1, 0x01];
+            input.extend_from_slice(pdu);
+            let (_, msg) = Modbus {}.parse(&input, direction).unwrap();
+            msg.unwrap()
+        };
+        let mut req = parse(req, Direction::ToServer);
+        let resp = parse(resp, Direction::ToClient);
+        assert_eq!(req.error_flags, ErrorFlags::none());
+        assert_eq!(resp.error_flags, ErrorFlags::none());
+
+        assert!(req.matches(&resp));
+        assert_eq!(req.error_flags, expected);
+    }
+
     #[rstest(
         msg,
         addr,
@@ -3117,6 +4617,27 @@ mod tests {
             4,
             Some(0x0a0b)
         ),
+        case::write_mult_coils(
+            Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 8,
+                unit_id: 1,
+                function: Function { raw: 15, code: FunctionCode::WrMultCoils },
+                access_type: AccessType::COILS | AccessType::WRITE_MULTIPLE,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Write (
+                    Write::MultReq {
+                        address: 0x0003,
+                        quantity: 0x000a,
+                        data: vec![0b0000_0000, 0b0000_0010]
+                    }
+                ),
+                error_flags: ErrorFlags::none(),
+            },
+            13,
+            Some(1)
+        ),
         case::read_file_record(
             Message{
                 transaction_id: 1,
@@ -3126,7 +4647,15 @@ mod tests {
                 function: Function { raw: 20, code: FunctionCode::RdFileRec },
                 access_type: AccessType::none(),
                 category: CodeCategory::PUBLIC_ASSIGNED.into(),
-                data: Data::ByteVec(vec![0x07, 0x06, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 1,
+                        record_number: 0,
+                        record_length: 2,
+                        data: vec![],
+                    },
+                ]),
                 error_flags: ErrorFlags::none(),
             },
             0,
@@ -3199,6 +4728,20 @@ mod tests {
             },
             Some(2..=2)
         ),
+        case::overflow(
+            Message{
+                transaction_id: 1,
+                protocol_id: 0,
+                length: 6,
+                unit_id: 1,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Read(Read::Request { address: 0xfffe, quantity: 2 }),
+                error_flags: ErrorFlags::none(),
+            },
+            None
+        ),
         case::read_file_record(
             Message{
                 transaction_id: 1,
@@ -3208,7 +4751,15 @@ mod tests {
                 function: Function { raw: 20, code: FunctionCode::RdFileRec },
                 access_type: AccessType::none(),
                 category: CodeCategory::PUBLIC_ASSIGNED.into(),
-                data: Data::ByteVec(vec![0x07, 0x06, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]),
+                data: Data::FileRecords(vec![
+                    FileRecord {
+                        reference_type: 6,
+                        file_number: 1,
+                        record_number: 0,
+                        record_length: 2,
+                        data: vec![],
+                    },
+                ]),
                 error_flags: ErrorFlags::none(),
             },
             None
@@ -3263,6 +4814,275 @@ mod tests {
         assert_eq!(modbus.probe(input, Direction::Unknown), expected);
     }
 
+    #[test]
+    fn test_crc16() {
+        assert_eq!(crc16(b"123456789"), 0x4b37);
+        assert_eq!(crc16(b""), 0xffff);
+    }
+
+    #[rstest(
+        input,
+        direction,
+        expected,
+        case::empty(b"", Direction::ToServer, Err(Error::incomplete_needed(2))),
+        case::read_request(
+            &[
+                // Address: 17
+                0x11,
+                // Function Code: Read Holding Registers (3)
+                0x03,
+                // Start Address: 107
+                0x00, 0x6b,
+                // Quantity: 3
+                0x00, 0x03,
+                // CRC: 0x8776
+                0x76, 0x87
+            ],
+            Direction::ToServer,
+            Ok((0, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 6,
+                unit_id: 17,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Read(Read::Request { address: 107, quantity: 3 }),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::read_response(
+            &[
+                // Address: 17
+                0x11,
+                // Function Code: Read Holding Registers (3)
+                0x03,
+                // Byte Count: 6
+                0x06,
+                // Data: ae41 5652 4340
+                0xae, 0x41, 0x56, 0x52, 0x43, 0x40,
+                // CRC: 0xad49
+                0x49, 0xad
+            ],
+            Direction::ToClient,
+            Ok((0, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 9,
+                unit_id: 17,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Read(Read::Response(vec![0xae, 0x41, 0x56, 0x52, 0x43, 0x40])),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::write_request(
+            &[
+                // Address: 17
+                0x11,
+                // Function Code: Write Multiple Registers (16)
+                0x10,
+                // Start Address: 1
+                0x00, 0x01,
+                // Quantity: 2
+                0x00, 0x02,
+                // Byte Count: 4
+                0x04,
+                // Data: 000a 0102
+                0x00, 0x0a, 0x01, 0x02,
+                // CRC: 0xf0c6
+                0xc6, 0xf0,
+                // Start of the next frame
+                0x11, 0x03
+            ],
+            Direction::ToServer,
+            Ok((2, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 11,
+                unit_id: 17,
+                function: Function { raw: 16, code: FunctionCode::WrMultRegs },
+                access_type: AccessType::HOLDING | AccessType::WRITE_MULTIPLE,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Write(Write::MultReq { address: 1, quantity: 2, data: vec![0x00, 0x0a, 0x01, 0x02] }),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::exception(
+            &[
+                // Address: 17
+                0x11,
+                // Function Code: Read Holding Registers (3) -- Exception
+                0x83,
+                // Exception Code: Illegal Data Address (2)
+                0x02,
+                // CRC: 0x34c1
+                0xc1, 0x34
+            ],
+            Direction::ToClient,
+            Ok((0, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 3,
+                unit_id: 17,
+                function: Function { raw: 131, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Exception(Exception { raw: 2, code: ExceptionCode::IllegalDataAddr }),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::bad_crc(
+            &[
+                // Address: 17
+                0x11,
+                // Function Code: Read Holding Registers (3)
+                0x03,
+                // Start Address: 107
+                0x00, 0x6b,
+                // Quantity: 3
+                0x00, 0x03,
+                // CRC: 0x8876
+                0x76, 0x88
+            ],
+            Direction::ToServer,
+            Ok((0, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 6,
+                unit_id: 17,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Read(Read::Request { address: 107, quantity: 3 }),
+                error_flags: ErrorFlags::CHECKSUM.into(),
+            })))
+        ),
+        case::unknown_direction(
+            &[
+                // Address: 17
+                0x11,
+                // Function Code: Read Holding Registers (3)
+                0x03,
+                // Start Address: 107
+                0x00, 0x6b,
+                // Quantity: 3
+                0x00, 0x03,
+                // CRC: 0x8776
+                0x76, 0x87,
+                // Start of the next frame
+                0x11, 0x03
+            ],
+            Direction::Unknown,
+            Ok((2, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 6,
+                unit_id: 17,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::ByteVec(vec![0x00, 0x6b, 0x00, 0x03]),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::incomplete_frame(&[0x11, 0x03, 0x00, 0x6b, 0x00], Direction::ToServer, Err(Error::incomplete_needed(3))),
+        case::incomplete_count(&[0x11, 0x10, 0x00, 0x01, 0x00], Direction::ToServer, Err(Error::incomplete_needed(2))),
+        case::unknown_incomplete(&[0x11, 0x03, 0x00, 0x6b, 0x00, 0x03, 0x76], Direction::Unknown, Err(Error::incomplete())),
+        case::unknown_invalid(&[0xff; RTU_MAX_FRAME_LEN], Direction::Unknown, Err(Error::new(ErrorKind::InvalidData)))
+    )]
+    fn test_rtu_parse(
+        input: &[u8],
+        direction: Direction,
+        expected: Result<(usize, Option<<ModbusRtu as Protocol>::Message>)>,
+    ) {
+        let modbus = ModbusRtu {};
+        assert_eq!(
+            modbus
+                .parse(input, direction)
+                .map(|(left, msg)| (left.len(), msg)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_lrc() {
+        assert_eq!(lrc(&[0x11, 0x03, 0x00, 0x6b, 0x00, 0x03]), 0x7e);
+        assert_eq!(lrc(&[]), 0x00);
+    }
+
+    #[rstest(
+        input,
+        direction,
+        expected,
+        case::empty(b"", Direction::ToServer, Err(Error::incomplete_needed(1))),
+        case::read_request(
+            b":1103006B00037E\r\n",
+            Direction::ToServer,
+            Ok((0, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 6,
+                unit_id: 17,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Read(Read::Request { address: 107, quantity: 3 }),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::read_response(
+            b":110306ae4156524340cc\r\n:11",
+            Direction::ToClient,
+            Ok((3, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 9,
+                unit_id: 17,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Read(Read::Response(vec![0xae, 0x41, 0x56, 0x52, 0x43, 0x40])),
+                error_flags: ErrorFlags::none(),
+            })))
+        ),
+        case::bad_lrc(
+            b":1103006B00037F\r\n",
+            Direction::ToServer,
+            Ok((0, Some(Message{
+                transaction_id: 0,
+                protocol_id: 0,
+                length: 6,
+                unit_id: 17,
+                function: Function { raw: 3, code: FunctionCode::RdHoldRegs },
+                access_type: AccessType::HOLDING | AccessType::READ,
+                category: CodeCategory::PUBLIC_ASSIGNED.into(),
+                data: Data::Read(Read::Request { address: 107, quantity: 3 }),
+                error_flags: ErrorFlags::CHECKSUM.into(),
+            })))
+        ),
+        case::incomplete(b":1103006B00037E\r", Direction::ToServer, Err(Error::incomplete())),
+        case::missing_start(b"1103006B00037E\r\n", Direction::ToServer, Err(Error::new(ErrorKind::InvalidData))),
+        case::odd_digits(b":1103006B00037\r\n", Direction::ToServer, Err(Error::new(ErrorKind::InvalidData))),
+        case::not_hex(b":1103006G00037E\r\n", Direction::ToServer, Err(Error::new(ErrorKind::InvalidData))),
+        case::too_short(b":11EF\r\n", Direction::ToServer, Err(Error::new(ErrorKind::InvalidData))),
+        case::too_long(&[b':'; ASCII_MAX_FRAME_LEN], Direction::ToServer, Err(Error::new(ErrorKind::InvalidData)))
+    )]
+    fn test_ascii_parse(
+        input: &[u8],
+        direction: Direction,
+        expected: Result<(usize, Option<<ModbusAscii as Protocol>::Message>)>,
+    ) {
+        let modbus = ModbusAscii {};
+        assert_eq!(
+            modbus
+                .parse(input, direction)
+                .map(|(left, msg)| (left.len(), msg)),
+            expected
+        );
+    }
+
     #[test]
     fn test_categories() {
         assert_eq!(CodeCategory::PUBLIC_UNASSIGNED, CodeCategory::from_raw(99));
@@ -3291,6 +5111,20 @@ mod tests {
         );
     }
 
+    #[rstest(
+        id,
+        expected,
+        case::vendor_name(0x00, DeviceObjectId::VendorName),
+        case::user_application_name(0x06, DeviceObjectId::UserApplicationName),
+        case::reserved(0x07, DeviceObjectId::Reserved),
+        case::reserved_last(0x7f, DeviceObjectId::Reserved),
+        case::private(0x80, DeviceObjectId::Private),
+        case::private_last(0xff, DeviceObjectId::Private)
+    )]
+    fn test_device_object_id(id: u8, expected: DeviceObjectId) {
+        assert_eq!(DeviceObjectId::from_raw(id), expected);
+    }
+
     #[test]
     fn test_printing() {
         assert_eq!(
@@ -3314,6 +5148,12 @@ mod tests {
             DiagnosticSubfunction::RetQueryData.to_string()
         );
         assert_eq!("Unknown", MEIType::Unknown.to_string());
+        assert_eq!("Specific", DeviceIdCode::Specific.to_string());
+        assert_eq!(
+            "BasicStreamIndividual",
+            ConformityLevel::BasicStreamIndividual.to_string()
+        );
+        assert_eq!("VendorName", DeviceObjectId::VendorName.to_string());
         assert_eq!(
             "IllegalFunction",
             ExceptionCode::IllegalFunction.to_string()
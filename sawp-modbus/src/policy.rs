@@ -0,0 +1,346 @@
+//! Allow or deny requests based on rules set for each client.
+//!
+//! A [`Rule`] restricts the unit ids, function codes, access types and addresses
+//! of requests. A request is allowed if any rule of its client allows it. When
+//! none does, the reason given is the check that failed last among the rules,
+//! as from the rule closest to allowing the request.
+//!
+//! Addresses are the coil/register numbers starting at 1, as returned by
+//! [`Message::get_address_range`].
+//!
+//! # Example
+//! ```
+//! use sawp::parser::{Direction, Parse};
+//! use sawp_modbus::policy::{Decision, DenyReason, Policy, Rule};
+//! use sawp_modbus::{AccessType, Modbus};
+//!
+//! // The HMI may read holding registers 1 to 100 of unit 1
+//! let mut policy = Policy::new();
+//! policy.allow(
+//!     "hmi",
+//!     Rule {
+//!         unit_ids: Some(vec![1]),
+//!         access_types: Some(AccessType::READ | AccessType::HOLDING),
+//!         address_ranges: Some(vec![1..=100]),
+//!         ..Rule::default()
+//!     },
+//! );
+//!
+//! // Write Single Register 1 (address 0) of unit 1
+//! let request = [0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x00, 0x00, 0x00, 0x0a];
+//! let (_, message) = Modbus {}.parse(&request, Direction::ToServer).unwrap();
+//! let message = message.unwrap();
+//! assert_eq!(policy.evaluate(&"hmi", &message), Decision::Deny(DenyReason::AccessType));
+//! assert_eq!(policy.evaluate(&"plc", &message), Decision::Deny(DenyReason::UnknownClient));
+//! ```
+
+use crate::{AccessType, Data, ErrorFlags, Flag, Flags, FunctionCode, Message, Read};
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+/// Restrictions on requests, each field allowing anything when None
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rule {
+    /// Unit ids that may be addressed
+    pub unit_ids: Option<Vec<u8>>,
+    /// Function codes that may be used
+    pub functions: Option<Vec<FunctionCode>>,
+    /// Access types that may be used: all flags of the request must be among them.
+    /// Functions without an access type, like diagnostics, are denied.
+    pub access_types: Option<Flags<AccessType>>,
+    /// Ranges of addresses that may be accessed: each range read or written by the
+    /// request must be within one of them. Requests without addresses are denied.
+    pub address_ranges: Option<Vec<RangeInclusive<u16>>>,
+}
+
+impl Rule {
+    /// Check each restriction in turn, returning the first not met
+    fn check(&self, request: &Message) -> Result<(), DenyReason> {
+        if let Some(unit_ids) = &self.unit_ids {
+            if !unit_ids.contains(&request.unit_id) {
+                return Err(DenyReason::UnitId);
+            }
+        }
+        if let Some(functions) = &self.functions {
+            if !functions.contains(&request.function.code) {
+                return Err(DenyReason::FunctionCode);
+            }
+        }
+        if let Some(access_types) = self.access_types {
+            if request.access_type.is_empty() || !access_types.contains(request.access_type) {
+                return Err(DenyReason::AccessType);
+            }
+        }
+        if let Some(address_ranges) = &self.address_ranges {
+            let ranges = accessed_ranges(request).ok_or(DenyReason::Address)?;
+            let allowed = ranges.iter().all(|range| {
+                address_ranges
+                    .iter()
+                    .any(|allowed| allowed.contains(range.start()) && allowed.contains(range.end()))
+            });
+            if !allowed {
+                return Err(DenyReason::Address);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ranges read or written by a request. Returns None for requests without
+/// addresses or with addresses past 65535, which no range allows.
+fn accessed_ranges(request: &Message) -> Option<Vec<RangeInclusive<u16>>> {
+    let mut ranges = Vec::new();
+    match &request.data {
+        // get_address_range only gives the range written by read-write requests
+        Data::ReadWrite {
+            read: Read::Request { address, quantity },
+            write: _,
+        } => {
+            if *quantity == 0 {
+                return None;
+            }
+            ranges.push(address.checked_add(1)?..=address.checked_add(*quantity)?);
+            ranges.push(request.get_address_range()?);
+        }
+        Data::Read(_) | Data::Write(_) => ranges.push(request.get_address_range()?),
+        _ => return None,
+    }
+    Some(ranges)
+}
+
+/// Restriction a denied request did not meet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DenyReason {
+    /// The client has no rules
+    UnknownClient,
+    /// The request has error flags set
+    Malformed,
+    UnitId,
+    FunctionCode,
+    AccessType,
+    Address,
+}
+
+/// Result of evaluating a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny(DenyReason),
+}
+
+/// Rules of each client, identified by any ordered key such as its address
+#[derive(Debug)]
+pub struct Policy<C> {
+    clients: BTreeMap<C, Vec<Rule>>,
+    /// Rules of clients without their own. Clients without rules are denied
+    /// if empty.
+    pub default_rules: Vec<Rule>,
+}
+
+impl<C: Ord> Default for Policy<C> {
+    fn default() -> Self {
+        Policy {
+            clients: BTreeMap::new(),
+            default_rules: Vec::new(),
+        }
+    }
+}
+
+impl<C: Ord> Policy<C> {
+    pub fn new() -> Self {
+        Policy::default()
+    }
+
+    /// Add a rule for a client
+    pub fn allow(&mut self, client: C, rule: Rule) {
+        self.clients.entry(client).or_default().push(rule);
+    }
+
+    /// Rules of a client, without the default rules
+    pub fn rules(&self, client: &C) -> Option<&[Rule]> {
+        self.clients.get(client).map(|rules| rules.as_slice())
+    }
+
+    /// Evaluate a request sent by a client. The message should be parsed with
+    /// [`sawp::parser::Direction::ToServer`].
+    pub fn evaluate(&self, client: &C, request: &Message) -> Decision {
+        let rules = self.rules(client).unwrap_or(&self.default_rules);
+        if rules.is_empty() {
+            return Decision::Deny(DenyReason::UnknownClient);
+        }
+        if request.error_flags != ErrorFlags::none() {
+            return Decision::Deny(DenyReason::Malformed);
+        }
+
+        let mut reason = DenyReason::UnknownClient;
+        for rule in rules {
+            match rule.check(request) {
+                Ok(()) => return Decision::Allow,
+                Err(denied) => reason = reason.max(denied),
+            }
+        }
+        Decision::Deny(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Modbus;
+    use rstest::rstest;
+    use sawp::parser::{Direction, Parse};
+
+    /// Parse a Modbus/TCP request from its unit id and PDU
+    fn request(unit_id: u8, pdu: &[u8]) -> Message {
+        let mut input = vec![0x00, 0x01, 0x00, 0x00, 0x00, pdu.len() as u8 + 1, unit_id];
+        input.extend_from_slice(pdu);
+        let (_, message) = Modbus {}.parse(&input, Direction::ToServer).unwrap();
+        message.unwrap()
+    }
+
+    fn policy() -> Policy<&'static str> {
+        let mut policy = Policy::new();
+        // Read anything from addresses 1 to 100 of unit 1
+        policy.allow(
+            "hmi",
+            Rule {
+                unit_ids: Some(vec![1]),
+                access_types: Some(AccessType::READ | AccessType::FUNC_MASK),
+                address_ranges: Some(vec![1..=100]),
+                ..Rule::default()
+            },
+        );
+        // Write single registers 50 to 60 of unit 1
+        policy.allow(
+            "hmi",
+            Rule {
+                unit_ids: Some(vec![1]),
+                functions: Some(vec![FunctionCode::WrSingleReg]),
+                address_ranges: Some(vec![50..=60]),
+                ..Rule::default()
+            },
+        );
+        // Read and write addresses 1 to 10 of any table and unit
+        policy.allow(
+            "scada",
+            Rule {
+                address_ranges: Some(vec![1..=10]),
+                ..Rule::default()
+            },
+        );
+        policy.default_rules = vec![Rule {
+            functions: Some(vec![FunctionCode::Diagnostic]),
+            ..Rule::default()
+        }];
+        policy
+    }
+
+    #[rstest(
+        client,
+        unit_id,
+        pdu,
+        expected,
+        case::read(
+            "hmi",
+            1,
+            &[0x03, 0x00, 0x00, 0x00, 0x0a],
+            Decision::Allow
+        ),
+        case::read_coils(
+            "hmi",
+            1,
+            &[0x01, 0x00, 0x00, 0x00, 0x0a],
+            Decision::Allow
+        ),
+        case::read_out_of_range(
+            "hmi",
+            1,
+            &[0x03, 0x00, 0x63, 0x00, 0x05],
+            Decision::Deny(DenyReason::Address)
+        ),
+        case::other_unit(
+            "hmi",
+            2,
+            &[0x03, 0x00, 0x00, 0x00, 0x0a],
+            Decision::Deny(DenyReason::UnitId)
+        ),
+        case::write(
+            "hmi",
+            1,
+            &[0x06, 0x00, 0x36, 0x00, 0x01],
+            Decision::Allow
+        ),
+        case::write_out_of_range(
+            "hmi",
+            1,
+            &[0x06, 0x00, 0x00, 0x00, 0x01],
+            Decision::Deny(DenyReason::Address)
+        ),
+        case::write_multiple(
+            "hmi",
+            1,
+            &[0x10, 0x00, 0x36, 0x00, 0x01, 0x02, 0x00, 0x01],
+            Decision::Deny(DenyReason::AccessType)
+        ),
+        case::diagnostic(
+            "hmi",
+            1,
+            &[0x08, 0x00, 0x01, 0x00, 0x00],
+            Decision::Deny(DenyReason::AccessType)
+        ),
+        case::malformed(
+            "hmi",
+            1,
+            &[0x03, 0x00, 0x00, 0x00, 0x00],
+            Decision::Deny(DenyReason::Malformed)
+        ),
+        case::read_write(
+            "scada",
+            7,
+            &[0x17, 0x00, 0x00, 0x00, 0x02, 0x00, 0x09, 0x00, 0x01, 0x02, 0x00, 0x07],
+            Decision::Allow
+        ),
+        case::read_write_out_of_range(
+            "scada",
+            7,
+            &[0x17, 0x00, 0x09, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x07],
+            Decision::Deny(DenyReason::Address)
+        ),
+        case::no_addresses(
+            "scada",
+            7,
+            &[0x08, 0x00, 0x01, 0x00, 0x00],
+            Decision::Deny(DenyReason::Address)
+        ),
+        case::default_rules(
+            "plc",
+            1,
+            &[0x08, 0x00, 0x01, 0x00, 0x00],
+            Decision::Allow
+        ),
+        case::default_rules_denied(
+            "plc",
+            1,
+            &[0x03, 0x00, 0x00, 0x00, 0x0a],
+            Decision::Deny(DenyReason::FunctionCode)
+        )
+    )]
+    fn evaluate(client: &str, unit_id: u8, pdu: &[u8], expected: Decision) {
+        assert_eq!(policy().evaluate(&client, &request(unit_id, pdu)), expected);
+    }
+
+    #[test]
+    fn unknown_client() {
+        let mut policy = policy();
+        policy.default_rules.clear();
+        let request = request(1, &[0x08, 0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(
+            policy.evaluate(&"plc", &request),
+            Decision::Deny(DenyReason::UnknownClient)
+        );
+        assert_eq!(policy.rules(&"plc"), None);
+        assert_eq!(policy.rules(&"scada").map(|rules| rules.len()), Some(1));
+    }
+}
@@ -0,0 +1,415 @@
+//! Typed values held in registers, decoded from the raw bytes of read responses
+//! and write requests with the help of a map of the registers of a device.
+//!
+//! Values wider than a register span consecutive registers. Devices differ in
+//! the order they store their bytes in, named after where each byte of the
+//! value `AB CD` (A being the most significant) ends up: see [`ByteOrder`].
+//!
+//! Addresses are the register numbers starting at 1, as returned by
+//! [`Message::get_address_range`].
+//!
+//! # Example
+//! ```
+//! use sawp_modbus::registers::{ByteOrder, DataType, Register, RegisterMap, Value};
+//! use sawp_modbus::shadow::Table;
+//!
+//! let mut map = RegisterMap::new();
+//! map.add(Register {
+//!     name: "flow".into(),
+//!     table: Table::HoldingRegisters,
+//!     address: 1,
+//!     data_type: DataType::F32,
+//!     byte_order: ByteOrder::CDAB,
+//! });
+//!
+//! // Holding registers 1 and 2 hold 0x0000 0x3fc0
+//! let values = map.decode(Table::HoldingRegisters, 1, &[0x00, 0x00, 0x3f, 0xc0]);
+//! assert_eq!(values[0].1, Value::F32(1.5));
+//! ```
+
+use crate::shadow::Table;
+use crate::{AccessType, Data, Message, Read, Write};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
+/// Order of the bytes of a value in its registers
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ByteOrder {
+    /// Big endian: registers from the most significant, each high byte first
+    ABCD,
+    /// Registers from the least significant, each high byte first
+    CDAB,
+    /// Registers from the most significant, each low byte first
+    BADC,
+    /// Little endian: registers from the least significant, each low byte first
+    DCBA,
+}
+
+impl ByteOrder {
+    fn swaps_words(self) -> bool {
+        self == ByteOrder::CDAB || self == ByteOrder::DCBA
+    }
+
+    fn swaps_bytes(self) -> bool {
+        self == ByteOrder::BADC || self == ByteOrder::DCBA
+    }
+}
+
+/// Type of the value held by one or more registers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataType {
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+    F64,
+    /// ASCII string of two characters per register, without its trailing NULs
+    String(u16),
+}
+
+impl DataType {
+    /// Number of registers holding a value
+    pub fn registers(self) -> usize {
+        match self {
+            DataType::U16 | DataType::I16 => 1,
+            DataType::U32 | DataType::I32 | DataType::F32 => 2,
+            DataType::F64 => 4,
+            DataType::String(registers) => usize::from(registers),
+        }
+    }
+}
+
+/// Decoded value
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    F32(f32),
+    F64(f64),
+    String(String),
+}
+
+impl core::fmt::Display for Value {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Value::U16(value) => write!(fmt, "{}", value),
+            Value::I16(value) => write!(fmt, "{}", value),
+            Value::U32(value) => write!(fmt, "{}", value),
+            Value::I32(value) => write!(fmt, "{}", value),
+            Value::F32(value) => write!(fmt, "{}", value),
+            Value::F64(value) => write!(fmt, "{}", value),
+            Value::String(value) => write!(fmt, "{}", value),
+        }
+    }
+}
+
+/// Decode the value held by the registers starting `data`. Returns None if
+/// `data` is shorter than the value.
+pub fn decode(data: &[u8], data_type: DataType, byte_order: ByteOrder) -> Option<Value> {
+    let data = data.get(..data_type.registers() * 2)?;
+    let mut registers: Vec<[u8; 2]> = data
+        .chunks_exact(2)
+        .map(|register| {
+            if byte_order.swaps_bytes() {
+                [register[1], register[0]]
+            } else {
+                [register[0], register[1]]
+            }
+        })
+        .collect();
+    if byte_order.swaps_words() {
+        registers.reverse();
+    }
+    let bytes: Vec<u8> = registers
+        .iter()
+        .flat_map(|register| register.iter())
+        .copied()
+        .collect();
+
+    Some(match data_type {
+        DataType::U16 => Value::U16(number(&bytes) as u16),
+        DataType::I16 => Value::I16(number(&bytes) as u16 as i16),
+        DataType::U32 => Value::U32(number(&bytes) as u32),
+        DataType::I32 => Value::I32(number(&bytes) as u32 as i32),
+        DataType::F32 => Value::F32(f32::from_bits(number(&bytes) as u32)),
+        DataType::F64 => Value::F64(f64::from_bits(number(&bytes))),
+        DataType::String(_) => {
+            let end = bytes
+                .iter()
+                .rposition(|byte| *byte != 0)
+                .map_or(0, |position| position + 1);
+            Value::String(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        }
+    })
+}
+
+/// Big endian number of up to 8 bytes
+fn number(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |number, byte| number << 8 | u64::from(*byte))
+}
+
+/// Value held by registers of a device
+#[derive(Clone, Debug, PartialEq)]
+pub struct Register {
+    pub name: String,
+    /// [`Table::InputRegisters`] or [`Table::HoldingRegisters`]
+    pub table: Table,
+    /// Address of the first register
+    pub address: u16,
+    pub data_type: DataType,
+    pub byte_order: ByteOrder,
+}
+
+impl Register {
+    /// Decode the value if the registers starting at `address` in `data` hold all of it
+    fn decode(&self, table: Table, address: u16, data: &[u8]) -> Option<Value> {
+        if self.table != table || self.address < address {
+            return None;
+        }
+        let offset = usize::from(self.address - address) * 2;
+        decode(data.get(offset..)?, self.data_type, self.byte_order)
+    }
+}
+
+/// Registers of a device
+#[derive(Debug, Default)]
+pub struct RegisterMap {
+    registers: Vec<Register>,
+}
+
+impl RegisterMap {
+    pub fn new() -> Self {
+        RegisterMap::default()
+    }
+
+    pub fn add(&mut self, register: Register) {
+        self.registers.push(register);
+    }
+
+    pub fn registers(&self) -> &[Register] {
+        &self.registers
+    }
+
+    /// Values of the registers fully held by `data`, the contents of the registers
+    /// of `table` starting at `address`
+    pub fn decode(&self, table: Table, address: u16, data: &[u8]) -> Vec<(&Register, Value)> {
+        self.registers
+            .iter()
+            .filter_map(|register| {
+                register
+                    .decode(table, address, data)
+                    .map(|value| (register, value))
+            })
+            .collect()
+    }
+
+    /// Values written by a request
+    pub fn decode_write(&self, request: &Message) -> Vec<(&Register, Value)> {
+        let table = match register_table(request) {
+            Some(table) => table,
+            None => return Vec::new(),
+        };
+        match &request.data {
+            Data::Write(Write::MultReq { address, data, .. })
+            | Data::ReadWrite {
+                write: Write::MultReq { address, data, .. },
+                ..
+            } => self.decode_from(table, *address, data),
+            Data::Write(Write::Other { address, data })
+                if request.access_type.contains(AccessType::SINGLE) =>
+            {
+                self.decode_from(table, *address, &data.to_be_bytes())
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Values read by a response to `request`
+    pub fn decode_read(&self, request: &Message, response: &Message) -> Vec<(&Register, Value)> {
+        let table = match register_table(request) {
+            Some(table) => table,
+            None => return Vec::new(),
+        };
+        match (&request.data, &response.data) {
+            (Data::Read(Read::Request { address, .. }), Data::Read(Read::Response(data)))
+            | (
+                Data::ReadWrite {
+                    read: Read::Request { address, .. },
+                    ..
+                },
+                Data::Read(Read::Response(data)),
+            ) => self.decode_from(table, *address, data),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Decode from the PDU address of the first register in `data`
+    fn decode_from(&self, table: Table, address: u16, data: &[u8]) -> Vec<(&Register, Value)> {
+        match address.checked_add(1) {
+            Some(address) => self.decode(table, address, data),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Register table accessed by a message
+fn register_table(message: &Message) -> Option<Table> {
+    match Table::from_access_type(message.access_type)? {
+        Table::InputRegisters => Some(Table::InputRegisters),
+        Table::HoldingRegisters => Some(Table::HoldingRegisters),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Modbus;
+    use rstest::rstest;
+    use sawp::parser::{Direction, Parse};
+
+    /// Parse the PDU of unit 1 in a Modbus/TCP frame
+    fn message(pdu: &[u8], direction: Direction) -> Message {
+        let mut input = vec![0x00, 0x01, 0x00, 0x00, 0x00, pdu.len() as u8 + 1, 0x01];
+        input.extend_from_slice(pdu);
+        let (_, message) = Modbus {}.parse(&input, direction).unwrap();
+        message.unwrap()
+    }
+
+    #[rstest(
+        data,
+        data_type,
+        byte_order,
+        expected,
+        case::u16(&[0x12, 0x34], DataType::U16, ByteOrder::ABCD, Some(Value::U16(0x1234))),
+        case::u16_byte_swap(&[0x12, 0x34], DataType::U16, ByteOrder::BADC, Some(Value::U16(0x3412))),
+        case::u16_word_swap(&[0x12, 0x34], DataType::U16, ByteOrder::CDAB, Some(Value::U16(0x1234))),
+        case::i16(&[0xff, 0xfe], DataType::I16, ByteOrder::ABCD, Some(Value::I16(-2))),
+        case::u32_abcd(&[0x12, 0x34, 0x56, 0x78], DataType::U32, ByteOrder::ABCD, Some(Value::U32(0x1234_5678))),
+        case::u32_cdab(&[0x12, 0x34, 0x56, 0x78], DataType::U32, ByteOrder::CDAB, Some(Value::U32(0x5678_1234))),
+        case::u32_badc(&[0x12, 0x34, 0x56, 0x78], DataType::U32, ByteOrder::BADC, Some(Value::U32(0x3412_7856))),
+        case::u32_dcba(&[0x12, 0x34, 0x56, 0x78], DataType::U32, ByteOrder::DCBA, Some(Value::U32(0x7856_3412))),
+        case::i32(&[0xff, 0xfe, 0xff, 0xff], DataType::I32, ByteOrder::CDAB, Some(Value::I32(-2))),
+        case::f32(&[0x3f, 0xc0, 0x00, 0x00], DataType::F32, ByteOrder::ABCD, Some(Value::F32(1.5))),
+        case::f64(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3f, 0xf8], DataType::F64, ByteOrder::CDAB, Some(Value::F64(1.5))),
+        case::f64_dcba(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf8, 0x3f], DataType::F64, ByteOrder::DCBA, Some(Value::F64(1.5))),
+        case::string(b"ABCDE\0\0\0", DataType::String(4), ByteOrder::ABCD, Some(Value::String("ABCDE".into()))),
+        case::string_byte_swap(b"BADC\0E", DataType::String(3), ByteOrder::BADC, Some(Value::String("ABCDE".into()))),
+        case::trailing_data(&[0x12, 0x34, 0x56, 0x78], DataType::U16, ByteOrder::ABCD, Some(Value::U16(0x1234))),
+        case::too_short(&[0x12, 0x34, 0x56], DataType::U32, ByteOrder::ABCD, None)
+    )]
+    fn decode_value(
+        data: &[u8],
+        data_type: DataType,
+        byte_order: ByteOrder,
+        expected: Option<Value>,
+    ) {
+        assert_eq!(decode(data, data_type, byte_order), expected);
+    }
+
+    fn map() -> RegisterMap {
+        let mut map = RegisterMap::new();
+        for (name, address, data_type, byte_order) in [
+            ("total", 1, DataType::U32, ByteOrder::ABCD),
+            ("flow", 3, DataType::F32, ByteOrder::CDAB),
+            ("tag", 5, DataType::String(2), ByteOrder::ABCD),
+            ("setpoint", 10, DataType::I16, ByteOrder::ABCD),
+        ]
+        .iter()
+        {
+            map.add(Register {
+                name: (*name).into(),
+                table: Table::HoldingRegisters,
+                address: *address,
+                data_type: *data_type,
+                byte_order: *byte_order,
+            });
+        }
+        map
+    }
+
+    fn named(values: Vec<(&Register, Value)>) -> Vec<(&str, Value)> {
+        values
+            .into_iter()
+            .map(|(register, value)| (register.name.as_str(), value))
+            .collect()
+    }
+
+    #[test]
+    fn decode_read() {
+        let map = map();
+        // Read Holding Registers 1 to 6
+        let request = message(&[0x03, 0x00, 0x00, 0x00, 0x06], Direction::ToServer);
+        let response = message(
+            &[
+                0x03, 0x0c, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x3f, 0xc0, 0x46, 0x54, 0x31, 0x00,
+            ],
+            Direction::ToClient,
+        );
+        assert_eq!(
+            named(map.decode_read(&request, &response)),
+            vec![
+                ("total", Value::U32(0x0001_0002)),
+                ("flow", Value::F32(1.5)),
+                ("tag", Value::String("FT1".into())),
+            ]
+        );
+
+        // Read Holding Registers 2 to 4 only holds all of flow
+        let request = message(&[0x03, 0x00, 0x01, 0x00, 0x03], Direction::ToServer);
+        let response = message(
+            &[0x03, 0x06, 0x00, 0x02, 0x00, 0x00, 0x3f, 0xc0],
+            Direction::ToClient,
+        );
+        assert_eq!(
+            named(map.decode_read(&request, &response)),
+            vec![("flow", Value::F32(1.5))]
+        );
+
+        // Input registers are another table
+        let request = message(&[0x04, 0x00, 0x00, 0x00, 0x02], Direction::ToServer);
+        let response = message(&[0x04, 0x04, 0x00, 0x01, 0x00, 0x02], Direction::ToClient);
+        assert_eq!(map.decode_read(&request, &response), vec![]);
+    }
+
+    #[test]
+    fn decode_write() {
+        let map = map();
+        // Write Single Register 10
+        let request = message(&[0x06, 0x00, 0x09, 0xff, 0x9c], Direction::ToServer);
+        assert_eq!(
+            named(map.decode_write(&request)),
+            vec![("setpoint", Value::I16(-100))]
+        );
+        // Write Multiple Registers 3 and 4
+        let request = message(
+            &[0x10, 0x00, 0x02, 0x00, 0x02, 0x04, 0x00, 0x00, 0x40, 0x20],
+            Direction::ToServer,
+        );
+        assert_eq!(
+            named(map.decode_write(&request)),
+            vec![("flow", Value::F32(2.5))]
+        );
+        // Coils hold no registers
+        let request = message(&[0x05, 0x00, 0x09, 0xff, 0x00], Direction::ToServer);
+        assert_eq!(map.decode_write(&request), vec![]);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Value::F32(1.5).to_string(), "1.5");
+        assert_eq!(Value::I16(-2).to_string(), "-2");
+        assert_eq!(Value::String("FT1".into()).to_string(), "FT1");
+    }
+}
@@ -0,0 +1,601 @@
+//! Image of the coils, discrete inputs, input registers and holding registers of
+//! each unit, learned from the read responses and write requests observed on a
+//! connection.
+//!
+//! Addresses are the coil/register numbers starting at 1, as returned by
+//! [`Message::get_address_range`], and coils and discrete inputs have the values
+//! 0 and 1.
+//!
+//! # Example
+//! ```
+//! use sawp::parser::{Direction, Parse};
+//! use sawp_modbus::shadow::{Event, Shadow, Table};
+//! use sawp_modbus::Modbus;
+//!
+//! let modbus = Modbus {};
+//! let mut shadow = Shadow::new();
+//!
+//! // Write Single Register 1 (address 0) of unit 1 to 10
+//! let request = [0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x00, 0x00, 0x00, 0x0a];
+//! let (_, message) = modbus.parse(&request, Direction::ToServer).unwrap();
+//! assert_eq!(
+//!     shadow.observe(&message.unwrap()),
+//!     vec![Event::UnobservedWrite {
+//!         unit_id: 1,
+//!         table: Table::HoldingRegisters,
+//!         address: 1,
+//!         value: 10,
+//!     }]
+//! );
+//! assert_eq!(shadow.value(1, Table::HoldingRegisters, 1), Some(10));
+//! ```
+
+use crate::{AccessType, Data, ErrorFlags, Flag, Flags, FunctionCode, Message, Read, Write};
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+/// Read requests kept while waiting for their response
+const MAX_PENDING_READS: usize = 256;
+
+/// The four tables of a unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Table {
+    Coils,
+    DiscreteInputs,
+    InputRegisters,
+    HoldingRegisters,
+}
+
+impl Table {
+    /// Table accessed by a function, from its [`AccessType`]
+    pub fn from_access_type(access_type: Flags<AccessType>) -> Option<Self> {
+        if access_type.contains(AccessType::COILS) {
+            Some(Table::Coils)
+        } else if access_type.contains(AccessType::DISCRETES) {
+            Some(Table::DiscreteInputs)
+        } else if access_type.contains(AccessType::INPUT) {
+            Some(Table::InputRegisters)
+        } else if access_type.contains(AccessType::HOLDING) {
+            Some(Table::HoldingRegisters)
+        } else {
+            None
+        }
+    }
+
+    fn is_bit_access(self) -> bool {
+        self == Table::Coils || self == Table::DiscreteInputs
+    }
+}
+
+/// Change to the image reported by [`Shadow::observe`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A read response gave the value of an address for the first time
+    FirstSeen {
+        unit_id: u8,
+        table: Table,
+        address: u16,
+        value: u16,
+    },
+    /// A read response or write request changed the value of a known address
+    Changed {
+        unit_id: u8,
+        table: Table,
+        address: u16,
+        old: u16,
+        new: u16,
+    },
+    /// A write request to an address no read response or earlier write covered
+    UnobservedWrite {
+        unit_id: u8,
+        table: Table,
+        address: u16,
+        value: u16,
+    },
+}
+
+/// Values known for the tables of one unit
+#[derive(Debug, Default)]
+pub struct Unit {
+    coils: BTreeMap<u16, u16>,
+    discrete_inputs: BTreeMap<u16, u16>,
+    input_registers: BTreeMap<u16, u16>,
+    holding_registers: BTreeMap<u16, u16>,
+}
+
+impl Unit {
+    /// Known value at an address of a table
+    pub fn get(&self, table: Table, address: u16) -> Option<u16> {
+        self.table(table).get(&address).copied()
+    }
+
+    /// Known addresses of a table and their values, by increasing address
+    pub fn values(&self, table: Table) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.table(table)
+            .iter()
+            .map(|(address, value)| (*address, *value))
+    }
+
+    fn table(&self, table: Table) -> &BTreeMap<u16, u16> {
+        match table {
+            Table::Coils => &self.coils,
+            Table::DiscreteInputs => &self.discrete_inputs,
+            Table::InputRegisters => &self.input_registers,
+            Table::HoldingRegisters => &self.holding_registers,
+        }
+    }
+
+    fn table_mut(&mut self, table: Table) -> &mut BTreeMap<u16, u16> {
+        match table {
+            Table::Coils => &mut self.coils,
+            Table::DiscreteInputs => &mut self.discrete_inputs,
+            Table::InputRegisters => &mut self.input_registers,
+            Table::HoldingRegisters => &mut self.holding_registers,
+        }
+    }
+}
+
+/// Read request waiting for its response
+#[derive(Debug)]
+struct PendingRead {
+    unit_id: u8,
+    transaction_id: u16,
+    function: FunctionCode,
+    table: Table,
+    address: u16,
+    quantity: u16,
+}
+
+impl PendingRead {
+    fn matches(&self, message: &Message) -> bool {
+        self.unit_id == message.unit_id
+            && self.transaction_id == message.transaction_id
+            && self.function == message.function.code
+    }
+}
+
+/// Image of the units behind a server, fed with the messages of its connections
+/// in both directions.
+///
+/// Read responses are matched to their requests by unit id, transaction id and
+/// function code. Messages with error flags are not applied.
+#[derive(Debug, Default)]
+pub struct Shadow {
+    units: BTreeMap<u8, Unit>,
+    pending: VecDeque<PendingRead>,
+}
+
+impl Shadow {
+    pub fn new() -> Self {
+        Shadow::default()
+    }
+
+    /// Known values of a unit
+    pub fn unit(&self, unit_id: u8) -> Option<&Unit> {
+        self.units.get(&unit_id)
+    }
+
+    /// Known value at an address of a table of a unit
+    pub fn value(&self, unit_id: u8, table: Table, address: u16) -> Option<u16> {
+        self.unit(unit_id).and_then(|unit| unit.get(table, address))
+    }
+
+    /// Apply a parsed message to the image, returning the changes it made
+    pub fn observe(&mut self, message: &Message) -> Vec<Event> {
+        let mut events = Vec::new();
+        let table = match Table::from_access_type(message.access_type) {
+            Some(table) => table,
+            None => return events,
+        };
+
+        let pending = match &message.data {
+            Data::Read(Read::Response(_)) | Data::Exception(_) => self.take_pending(message),
+            _ => None,
+        };
+        if message.error_flags != ErrorFlags::none() {
+            return events;
+        }
+
+        match &message.data {
+            Data::Read(Read::Request { address, quantity }) => {
+                self.add_pending(message, table, *address, *quantity)
+            }
+            Data::Read(Read::Response(data)) => {
+                if let Some(pending) = pending {
+                    self.read(&pending, data, &mut events);
+                }
+            }
+            Data::ReadWrite {
+                read: Read::Request { address, quantity },
+                write: _,
+            } => {
+                // The write is done before the read
+                self.write(message, table, &mut events);
+                self.add_pending(message, table, *address, *quantity);
+            }
+            Data::Write(Write::MultReq { .. }) => self.write(message, table, &mut events),
+            // Write::Other also holds the responses to multiple writes, with their quantity
+            Data::Write(Write::Other { .. })
+                if message.access_type.contains(AccessType::SINGLE) =>
+            {
+                self.write(message, table, &mut events)
+            }
+            Data::Write(Write::Mask {
+                address,
+                and_mask,
+                or_mask,
+            }) => {
+                // The result depends on the current value, unknown values stay unknown
+                if let Some(address) = address.checked_add(1) {
+                    if let Some(current) = self.value(message.unit_id, table, address) {
+                        let value = (current & and_mask) | (or_mask & !and_mask);
+                        self.update(message.unit_id, table, address, value, true, &mut events);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        events
+    }
+
+    fn add_pending(&mut self, message: &Message, table: Table, address: u16, quantity: u16) {
+        if self.pending.len() == MAX_PENDING_READS {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(PendingRead {
+            unit_id: message.unit_id,
+            transaction_id: message.transaction_id,
+            function: message.function.code,
+            table,
+            address,
+            quantity,
+        });
+    }
+
+    fn take_pending(&mut self, message: &Message) -> Option<PendingRead> {
+        let position = self
+            .pending
+            .iter()
+            .position(|pending| pending.matches(message))?;
+        self.pending.remove(position)
+    }
+
+    fn read(&mut self, pending: &PendingRead, data: &[u8], events: &mut Vec<Event>) {
+        for index in 0..usize::from(pending.quantity) {
+            let address = match u16::try_from(usize::from(pending.address) + 1 + index) {
+                Ok(address) => address,
+                Err(_) => break,
+            };
+            let value = if pending.table.is_bit_access() {
+                match data.get(index >> 3) {
+                    Some(byte) => u16::from((byte >> (index & 0x7)) & 0x1),
+                    None => break,
+                }
+            } else {
+                match (data.get(index * 2), data.get(index * 2 + 1)) {
+                    (Some(high), Some(low)) => u16::from_be_bytes([*high, *low]),
+                    _ => break,
+                }
+            };
+            self.update(
+                pending.unit_id,
+                pending.table,
+                address,
+                value,
+                false,
+                events,
+            );
+        }
+    }
+
+    fn write(&mut self, message: &Message, table: Table, events: &mut Vec<Event>) {
+        if let Some(range) = message.get_address_range() {
+            for address in range {
+                if let Some(value) = message.get_write_value_at_address(&address) {
+                    self.update(message.unit_id, table, address, value, true, events);
+                }
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        unit_id: u8,
+        table: Table,
+        address: u16,
+        value: u16,
+        write: bool,
+        events: &mut Vec<Event>,
+    ) {
+        let values = self.units.entry(unit_id).or_default().table_mut(table);
+        match values.insert(address, value) {
+            None if write => events.push(Event::UnobservedWrite {
+                unit_id,
+                table,
+                address,
+                value,
+            }),
+            None => events.push(Event::FirstSeen {
+                unit_id,
+                table,
+                address,
+                value,
+            }),
+            Some(old) if old != value => events.push(Event::Changed {
+                unit_id,
+                table,
+                address,
+                old,
+                new: value,
+            }),
+            Some(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Modbus;
+    use sawp::parser::{Direction, Parse};
+
+    /// Parse the PDU of unit 1 in a Modbus/TCP frame
+    fn message(transaction_id: u16, pdu: &[u8], direction: Direction) -> Message {
+        let mut input = transaction_id.to_be_bytes().to_vec();
+        input.extend_from_slice(&[0x00, 0x00, 0x00, pdu.len() as u8 + 1, 0x01]);
+        input.extend_from_slice(pdu);
+        let (_, message) = Modbus {}.parse(&input, direction).unwrap();
+        message.unwrap()
+    }
+
+    fn holding(address: u16) -> (u8, Table, u16) {
+        (1, Table::HoldingRegisters, address)
+    }
+
+    #[test]
+    fn registers() {
+        let mut shadow = Shadow::new();
+        // Read Holding Registers 1 and 2
+        let request = message(1, &[0x03, 0x00, 0x00, 0x00, 0x02], Direction::ToServer);
+        assert_eq!(shadow.observe(&request), vec![]);
+        let response = message(
+            1,
+            &[0x03, 0x04, 0x00, 0x0a, 0x00, 0x0b],
+            Direction::ToClient,
+        );
+        let (unit_id, table, _) = holding(1);
+        assert_eq!(
+            shadow.observe(&response),
+            vec![
+                Event::FirstSeen {
+                    unit_id,
+                    table,
+                    address: 1,
+                    value: 10
+                },
+                Event::FirstSeen {
+                    unit_id,
+                    table,
+                    address: 2,
+                    value: 11
+                },
+            ]
+        );
+        // Replayed responses without a request are ignored
+        assert_eq!(shadow.observe(&response), vec![]);
+
+        // Write Single Register 1, then its echo
+        let write = message(2, &[0x06, 0x00, 0x00, 0x00, 0x0c], Direction::ToServer);
+        assert_eq!(
+            shadow.observe(&write),
+            vec![Event::Changed {
+                unit_id,
+                table,
+                address: 1,
+                old: 10,
+                new: 12
+            }]
+        );
+        let echo = message(2, &[0x06, 0x00, 0x00, 0x00, 0x0c], Direction::ToClient);
+        assert_eq!(shadow.observe(&echo), vec![]);
+
+        // Write Multiple Registers 2 and 3
+        let write = message(
+            3,
+            &[0x10, 0x00, 0x01, 0x00, 0x02, 0x04, 0x00, 0x0b, 0x00, 0x0d],
+            Direction::ToServer,
+        );
+        assert_eq!(
+            shadow.observe(&write),
+            vec![Event::UnobservedWrite {
+                unit_id,
+                table,
+                address: 3,
+                value: 13
+            }]
+        );
+        // The response holds the quantity written, not a value
+        let response = message(3, &[0x10, 0x00, 0x01, 0x00, 0x02], Direction::ToClient);
+        assert_eq!(shadow.observe(&response), vec![]);
+
+        // Mask Write Register 1: (12 & 0xf2) | (0x25 & !0xf2)
+        let mask = message(
+            4,
+            &[0x16, 0x00, 0x00, 0x00, 0xf2, 0x00, 0x25],
+            Direction::ToServer,
+        );
+        assert_eq!(
+            shadow.observe(&mask),
+            vec![Event::Changed {
+                unit_id,
+                table,
+                address: 1,
+                old: 12,
+                new: 0x05
+            }]
+        );
+        // Masks of unknown registers give no value
+        let mask = message(
+            5,
+            &[0x16, 0x00, 0x09, 0x00, 0xf2, 0x00, 0x25],
+            Direction::ToServer,
+        );
+        assert_eq!(shadow.observe(&mask), vec![]);
+
+        assert_eq!(
+            shadow.unit(1).unwrap().values(table).collect::<Vec<_>>(),
+            vec![(1, 0x05), (2, 11), (3, 13)]
+        );
+        assert_eq!(shadow.value(2, table, 1), None);
+    }
+
+    #[test]
+    fn coils() {
+        let mut shadow = Shadow::new();
+        // Read Coils 1 to 10
+        let request = message(1, &[0x01, 0x00, 0x00, 0x00, 0x0a], Direction::ToServer);
+        assert_eq!(shadow.observe(&request), vec![]);
+        let response = message(
+            1,
+            &[0x01, 0x02, 0b0000_0101, 0b0000_0010],
+            Direction::ToClient,
+        );
+        assert_eq!(shadow.observe(&response).len(), 10);
+        assert_eq!(
+            shadow
+                .unit(1)
+                .unwrap()
+                .values(Table::Coils)
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 1),
+                (2, 0),
+                (3, 1),
+                (4, 0),
+                (5, 0),
+                (6, 0),
+                (7, 0),
+                (8, 0),
+                (9, 0),
+                (10, 1)
+            ]
+        );
+
+        // Write Multiple Coils 9 to 12
+        let write = message(
+            2,
+            &[0x0f, 0x00, 0x08, 0x00, 0x04, 0x01, 0b0000_1101],
+            Direction::ToServer,
+        );
+        let table = Table::Coils;
+        assert_eq!(
+            shadow.observe(&write),
+            vec![
+                Event::Changed {
+                    unit_id: 1,
+                    table,
+                    address: 9,
+                    old: 0,
+                    new: 1
+                },
+                Event::Changed {
+                    unit_id: 1,
+                    table,
+                    address: 10,
+                    old: 1,
+                    new: 0
+                },
+                Event::UnobservedWrite {
+                    unit_id: 1,
+                    table,
+                    address: 11,
+                    value: 1
+                },
+                Event::UnobservedWrite {
+                    unit_id: 1,
+                    table,
+                    address: 12,
+                    value: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn exceptions_and_errors() {
+        let mut shadow = Shadow::new();
+        // Read Input Registers 1, answered by an exception
+        let request = message(1, &[0x04, 0x00, 0x00, 0x00, 0x01], Direction::ToServer);
+        shadow.observe(&request);
+        let exception = message(1, &[0x84, 0x02], Direction::ToClient);
+        assert_eq!(shadow.observe(&exception), vec![]);
+        let response = message(1, &[0x04, 0x02, 0x00, 0x01], Direction::ToClient);
+        assert_eq!(shadow.observe(&response), vec![]);
+
+        // Responses with error flags drop their request without applying it
+        shadow.observe(&request);
+        let response = message(1, &[0x04, 0x03, 0x00, 0x01], Direction::ToClient);
+        assert_ne!(response.error_flags, ErrorFlags::none());
+        assert_eq!(shadow.observe(&response), vec![]);
+        let response = message(1, &[0x04, 0x02, 0x00, 0x01], Direction::ToClient);
+        assert_eq!(shadow.observe(&response), vec![]);
+        assert!(shadow.unit(1).is_none());
+    }
+
+    #[test]
+    fn read_write() {
+        let mut shadow = Shadow::new();
+        // Write Holding Register 3 then read Holding Registers 3 and 4
+        let request = message(
+            1,
+            &[
+                0x17, 0x00, 0x02, 0x00, 0x02, 0x00, 0x02, 0x00, 0x01, 0x02, 0x00, 0x07,
+            ],
+            Direction::ToServer,
+        );
+        let (unit_id, table, _) = holding(3);
+        assert_eq!(
+            shadow.observe(&request),
+            vec![Event::UnobservedWrite {
+                unit_id,
+                table,
+                address: 3,
+                value: 7
+            }]
+        );
+        let response = message(
+            1,
+            &[0x17, 0x04, 0x00, 0x07, 0x00, 0x08],
+            Direction::ToClient,
+        );
+        assert_eq!(
+            shadow.observe(&response),
+            vec![Event::FirstSeen {
+                unit_id,
+                table,
+                address: 4,
+                value: 8
+            }]
+        );
+    }
+
+    #[test]
+    fn pending_limit() {
+        let mut shadow = Shadow::new();
+        for transaction_id in 0..=MAX_PENDING_READS as u16 {
+            let request = message(
+                transaction_id,
+                &[0x03, 0x00, 0x00, 0x00, 0x01],
+                Direction::ToServer,
+            );
+            shadow.observe(&request);
+        }
+        let response = message(0, &[0x03, 0x02, 0x00, 0x01], Direction::ToClient);
+        assert_eq!(shadow.observe(&response), vec![]);
+        let response = message(1, &[0x03, 0x02, 0x00, 0x01], Direction::ToClient);
+        assert_eq!(shadow.observe(&response).len(), 1);
+    }
+}
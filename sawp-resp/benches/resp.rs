@@ -0,0 +1,84 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sawp::parser::{Direction, Parse};
+use sawp_resp::Resp;
+
+#[path = "support/alloc.rs"]
+mod alloc;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+/// Number of commands sent back to back in the pipeline corpus.
+const PIPELINE_DEPTH: usize = 100;
+
+/// Size of the value in the large bulk string corpus.
+const LARGE_BULK_STRING_LEN: usize = 64 * 1024;
+
+/// Client pipelining SET and GET commands without waiting for replies.
+fn pipeline() -> Vec<u8> {
+    let mut stream = Vec::new();
+    for i in 0..PIPELINE_DEPTH / 2 {
+        let key = format!("session:{:08}", i);
+        let value = format!("{{\"user\":{},\"ttl\":3600}}", i);
+        stream.extend_from_slice(
+            format!(
+                "*3\r\n$3\r\nSET\r\n${}\r\n{}\r\n${}\r\n{}\r\n",
+                key.len(),
+                key,
+                value.len(),
+                value
+            )
+            .as_bytes(),
+        );
+        stream.extend_from_slice(
+            format!("*2\r\n$3\r\nGET\r\n${}\r\n{}\r\n", key.len(), key).as_bytes(),
+        );
+    }
+    stream
+}
+
+/// Server replying to a GET of a large value.
+fn large_bulk_string() -> Vec<u8> {
+    let mut stream = format!("${}\r\n", LARGE_BULK_STRING_LEN).into_bytes();
+    stream.extend((0..LARGE_BULK_STRING_LEN).map(|i| b'a' + (i % 26) as u8));
+    stream.extend_from_slice(b"\r\n");
+    stream
+}
+
+/// Parse every message in the stream, returning how many were parsed.
+fn parse_stream(resp: &Resp, mut input: &[u8]) -> usize {
+    let mut messages = 0;
+    while !input.is_empty() {
+        match resp.parse(input, Direction::Unknown) {
+            Ok((rest, Some(_))) => {
+                messages += 1;
+                input = rest;
+            }
+            _ => break,
+        }
+    }
+    messages
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let corpora = vec![
+        ("pipeline", pipeline(), PIPELINE_DEPTH),
+        ("large_bulk_string", large_bulk_string(), 1),
+    ];
+
+    let mut group = c.benchmark_group("resp");
+    for (name, stream, messages) in &corpora {
+        // Assert the whole stream parses before benchmarking
+        assert_eq!(*messages, parse_stream(&Resp {}, stream));
+        alloc::report(&format!("resp/{}", name), || parse_stream(&Resp {}, stream));
+
+        group.throughput(Throughput::Bytes(stream.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), stream, |b, stream| {
+            b.iter(|| parse_stream(&Resp {}, black_box(stream)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -63,6 +63,9 @@ mod ffi;
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 pub const CRLF: &[u8] = b"\r\n";
 pub const DATA_TYPE_TOKENS: &str = "$*+-:";
 pub const MAX_ARRAY_DEPTH: usize = 64;
@@ -85,6 +88,7 @@ pub enum ErrorFlags {
 }
 
 /// RESP signals data types by prepending these one-character tokens
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum DataTypeToken {
@@ -117,6 +121,7 @@ impl DataTypeToken {
 /// Entry types to return in the parsed message
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_resp"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub enum Entry {
     /// Arrays of entries
@@ -149,6 +154,7 @@ pub enum StringResult<'a> {
 /// Breakdown of the parsed resp bytes
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_resp"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Message {
     pub entry: Entry,
@@ -267,7 +273,9 @@ impl Resp {
                     let (mut local_input, length, mut error_flags) = Resp::parse_integer(input)?;
                     match length {
                         IntegerResult::Integer(length) => {
-                            let mut entries: Vec<Entry> = Vec::with_capacity(length as usize);
+                            // The length comes off the wire, every entry takes at least one byte
+                            let capacity = std::cmp::min(length.max(0) as usize, local_input.len());
+                            let mut entries: Vec<Entry> = Vec::with_capacity(capacity);
 
                             for _ in 0..length {
                                 let (rem, entry, inner_error_flags) =
@@ -455,6 +463,22 @@ mod test {
             )
         ))
     ),
+    case::parse_negative_length_array(
+        b"*-6413760313388004135\r\n",
+        Ok((
+            0,
+            Some(
+                Message {
+                    entry: Entry::Array(vec![]),
+                    error_flags: ErrorFlags::none(),
+                }
+            )
+        ))
+    ),
+    case::parse_huge_length_array(
+        b"*4000000000000\r\n:1\r\n",
+        Err(sawp::error::Error::incomplete_needed(1))
+    ),
     case::nested_array_exceeds_max_depth(
     b"*2\r\n$3\r\nfoo\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n\
     *1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n*1\r\n\
@@ -0,0 +1,85 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sawp::parser::{Direction, Parse};
+use sawp_tftp::TFTP;
+
+#[path = "support/alloc.rs"]
+mod alloc;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+/// Default TFTP block size.
+const BLOCK_SIZE: usize = 512;
+
+/// Number of full blocks in the transferred file.
+const BLOCKS: u16 = 32;
+
+fn data(block: u16, len: usize) -> Vec<u8> {
+    let mut packet = vec![0x00, 0x03]; // OpCode: 3 (Data)
+    packet.extend_from_slice(&block.to_be_bytes());
+    packet.extend((0..len).map(|i| b'a' + (i % 26) as u8));
+    packet
+}
+
+fn ack(block: u16) -> Vec<u8> {
+    let mut packet = vec![0x00, 0x04]; // OpCode: 4 (Acknowledgement)
+    packet.extend_from_slice(&block.to_be_bytes());
+    packet
+}
+
+/// Read of a file spanning several blocks, acknowledged one by one.
+fn transfer() -> Vec<Vec<u8>> {
+    let mut session = vec![b"\x00\x01pxelinux.0\x00octet\x00".to_vec()];
+    for block in 1..=BLOCKS {
+        session.push(data(block, BLOCK_SIZE));
+        session.push(ack(block));
+    }
+    session.push(data(BLOCKS + 1, BLOCK_SIZE / 3));
+    session.push(ack(BLOCKS + 1));
+    session
+}
+
+/// Requests negotiating option extensions, and an error reply.
+fn options() -> Vec<Vec<u8>> {
+    vec![
+        b"\x00\x01boot/grub/x86_64-efi/grub.cfg\x00octet\x00blksize\x001468\x00tsize\x000\x00"
+            .to_vec(),
+        b"\x00\x06blksize\x001468\x00tsize\x004096\x00".to_vec(),
+        b"\x00\x02backup/router-config.txt\x00netascii\x00timeout\x005\x00".to_vec(),
+        b"\x00\x05\x00\x02Access violation\x00".to_vec(),
+    ]
+}
+
+/// Parse every packet in the corpus, returning how many were parsed.
+fn parse_corpus(tftp: &TFTP, corpus: &[Vec<u8>]) -> usize {
+    corpus
+        .iter()
+        .filter(|input| {
+            matches!(
+                tftp.parse(input, Direction::Unknown),
+                Ok((rest, Some(_))) if rest.is_empty()
+            )
+        })
+        .count()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let corpora = vec![("transfer", transfer()), ("options", options())];
+
+    let mut group = c.benchmark_group("tftp");
+    for (name, corpus) in &corpora {
+        // Assert every packet parses before benchmarking
+        assert_eq!(corpus.len(), parse_corpus(&TFTP {}, corpus));
+        alloc::report(&format!("tftp/{}", name), || parse_corpus(&TFTP {}, corpus));
+
+        let bytes: usize = corpus.iter().map(Vec::len).sum();
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), corpus, |b, corpus| {
+            b.iter(|| parse_corpus(&TFTP {}, black_box(corpus)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
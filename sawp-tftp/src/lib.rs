@@ -62,8 +62,12 @@ mod ffi;
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
+#[cfg(feature = "serialize")]
+use serde_derive::Serialize;
+
 /// The TFTP header of a packet contains the  opcode  associated  with
 /// that packet. TFTP supports five types of packets
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum OpCode {
@@ -76,6 +80,7 @@ pub enum OpCode {
 }
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI), sawp_ffi(prefix = "sawp_tftp"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub enum Mode {
     NetASCII,
@@ -85,6 +90,7 @@ pub enum Mode {
 }
 
 ///  The error code is an integer indicating the nature of the error.
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u16)]
 pub enum ErrorCode {
@@ -102,6 +108,7 @@ pub enum ErrorCode {
 
 #[cfg_attr(feature = "ffi", derive(GenerateFFI))]
 #[cfg_attr(feature = "ffi", sawp_ffi(prefix = "sawp_tftp"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct OptionExtension {
     pub name: String,
@@ -110,6 +117,7 @@ pub struct OptionExtension {
 
 /// Represents the various types of TFTP Packets
 #[cfg_attr(feature = "ffi", derive(GenerateFFI), sawp_ffi(prefix = "sawp_tftp"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub enum Packet {
     ReadWriteRequest {
@@ -132,6 +140,7 @@ pub enum Packet {
 
 /// Breakdown of the parsed TFTP bytes
 #[cfg_attr(feature = "ffi", derive(GenerateFFI), sawp_ffi(prefix = "sawp_tftp"))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, PartialEq)]
 pub struct Message {
     #[cfg_attr(feature = "ffi", sawp_ffi(copy))]
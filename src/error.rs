@@ -1,14 +1,15 @@
 #[cfg(feature = "ffi")]
 use sawp_ffi::GenerateFFI;
 
-use std::num::NonZeroUsize;
+use alloc::string::String;
+use core::num::NonZeroUsize;
 
 // Re-export types used for ErrorKind
 use nom::error::ErrorKind as NomErrorKind;
 use nom::Needed as NomNeeded;
 
 /// Helper that uses this module's error type
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Helper for nom's default error type
 // A better nom error will be available once we can migrate
@@ -102,7 +103,7 @@ pub enum ErrorKind {
 impl From<NomErrorKind> for ErrorKind {
     #[cfg(verbose)]
     fn from(kind: NomErrorKind) -> Self {
-        Self::ParseError(Some(format!("{:?}", kind)))
+        Self::ParseError(Some(alloc::format!("{:?}", kind)))
     }
 
     #[cfg(not(verbose))]
@@ -111,7 +112,7 @@ impl From<NomErrorKind> for ErrorKind {
     }
 }
 
-impl<I: std::fmt::Debug> From<nom::Err<NomError<I>>> for Error {
+impl<I: core::fmt::Debug> From<nom::Err<NomError<I>>> for Error {
     fn from(nom_err: nom::Err<NomError<I>>) -> Self {
         match nom_err {
             nom::Err::Error(err) | nom::Err::Failure(err) => Error::new(err.1.into()),
@@ -123,10 +124,11 @@ impl<I: std::fmt::Debug> From<nom::Err<NomError<I>>> for Error {
     }
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, _: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+impl core::fmt::Display for Error {
+    fn fmt(&self, _: &mut core::fmt::Formatter) -> core::result::Result<(), core::fmt::Error> {
         todo!()
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
@@ -24,8 +24,17 @@ referring to the protocol parser you wish to use:
 
 The following utility packages also exist:
 - [File](/sawp-file) Serializes API calls for debugging
+
+## Features
+
+Parsers are built with `std` by default. Disabling default features builds
+the base library and the parsers supporting it with only `core` and `alloc`.
 */
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 /// Return common errors
 pub mod error;
 